@@ -6,6 +6,7 @@
 // Clippy marudzi, że w przykładzie z "kładką" grupy cyfr dwójkowych nie mają jednakowych rozmiarów
 #![allow(clippy::unusual_byte_groupings)]
 use color_eyre::eyre::Result;
+use sekurak_hex_gynvael::literal::parse_int;
 use sekurak_hex_gynvael::pokaz_liczbe;
 
 fn main() -> Result<()> {
@@ -25,6 +26,12 @@ fn main() -> Result<()> {
         "Konwersja z liczby dwójkowej z grupowaniem cyfr"
     );
 
+    // Ten sam zapis w tekście (np. argumentach programów) parsuje
+    // `literal::parse_int`, sprawdzając przy okazji zakres typu docelowego
+    assert_eq!(256, parse_int::<u16>("0b_1_0000_0000")?);
+    assert_eq!(-128, parse_int::<i8>("-0x80")?);
+    assert!(parse_int::<u8>("0x1_00").is_err());
+
     // Formatowanie wyników
 
     let liczba = 0x1234;
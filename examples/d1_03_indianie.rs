@@ -1,4 +1,5 @@
 use color_eyre::eyre::Result;
+use sekurak_hex_gynvael::byte_order::ByteOrder;
 fn main() -> Result<()> {
     color_eyre::install()?;
 
@@ -18,6 +19,13 @@ fn main() -> Result<()> {
         0x1234_5678_9ABC_DEF0_u64.to_le_bytes()
     );
 
+    // Inne kolejności (PDP-11, zamienione słowa) i nietypowe szerokości (24,
+    // 48 bitów) obsługuje `byte_order::ByteOrder`. Litera A to najstarszy bajt.
+    let pdp: ByteOrder = "BADC".parse()?;
+    assert_eq!(0x0A0B_0C0D, pdp.decode_unsigned(&[0x0B, 0x0A, 0x0D, 0x0C])?);
+    let le24 = ByteOrder::little_endian(3)?;
+    assert_eq!(-2, le24.decode_signed(&[0xFE, 0xFF, 0xFF])?);
+
     parsuj_szereg_u16_le(&[
         0x4a, 0x9c, 0x28, 0x81, 0x2f, 0xa6, 0xfb, 0xb6, 0x64, 0x6d, 0xb0, 0x99, 0x4a, 0x40, 0xbb,
         0x0e,
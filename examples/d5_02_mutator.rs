@@ -1,15 +1,11 @@
 use clap::Parser;
-use env_logger::Env;
 use eyre::Result;
-use log::debug;
 use rand::{Rng, RngCore};
 use std::{
     fs::File,
     io::{Read, Write},
 };
 
-use sekurak_hex_gynvael::image_codec as ic;
-
 #[derive(Parser)]
 #[command(name = "bmp2png")]
 #[command(author = "Pyth0n")]
@@ -39,7 +35,7 @@ fn main() -> Result<()> {
     ifile.read_to_end(&mut data)?;
 
     // Potnij dane na modyfikowalne bloczki po 256B
-    for mut d in data.chunks_mut(256) {
+    for d in data.chunks_mut(256) {
         // Losowy rozmiar
         let siz = rng.gen_range(1..=8);
 
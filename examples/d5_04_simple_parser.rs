@@ -1,11 +1,7 @@
 use color_eyre::eyre::Result;
-use rand::{Rng, RngCore};
 use sekurak_hex_gynvael::files::File;
 
 fn main() -> Result<()> {
-    // Uchwyt do generatora danych losowych
-    let mut rng = rand::thread_rng();
-
     // Otwarcie pliku do odczytu
     let mut ifile = File::open("data/spec1_1.bin")?;
 
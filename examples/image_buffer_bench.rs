@@ -0,0 +1,104 @@
+use std::hint::black_box;
+use std::mem::size_of;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::Result;
+use sekurak_hex_gynvael::image_codec::{ColorRGB8, Frame, ImageBuffer, Pixel, PixelFormat};
+
+// Porównanie starego obrazu `Frame` (wektor linii enumów `Pixel`) z płaskim
+// `ImageBuffer`. Uruchamiać z optymalizacją:
+//
+//     cargo run --release --example image_buffer_bench
+
+const SZEROKOSC: usize = 3840;
+const WYSOKOSC: usize = 2160;
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+
+    let kolor = |x: usize, y: usize| ColorRGB8(x as u8, y as u8, (x ^ y) as u8);
+
+    let (frame, t_frame) = pomiar(|| -> Frame {
+        (0..WYSOKOSC)
+            .map(|y| (0..SZEROKOSC).map(|x| Pixel::RGB8(kolor(x, y))).collect())
+            .collect()
+    });
+    let (buffer, t_buffer) =
+        pomiar(|| ImageBuffer::from_fn(SZEROKOSC, WYSOKOSC, PixelFormat::RGB8, kolor));
+
+    // Pamięć: piksele plus nagłówek `Vec` dla każdej linii
+    let pamiec_frame = frame.capacity() * size_of::<Vec<Pixel>>()
+        + frame
+            .iter()
+            .map(|l| l.capacity() * size_of::<Pixel>())
+            .sum::<usize>();
+    let pamiec_buffer = buffer.as_bytes().len();
+
+    println!("Obraz {SZEROKOSC}x{WYSOKOSC} RGB8");
+    println!("{:<24}{:>12}{:>12}", "", "Frame", "ImageBuffer");
+    println!(
+        "{:<24}{:>12}{:>12}",
+        "pamięć [MiB]",
+        format!("{:.1}", pamiec_frame as f64 / (1 << 20) as f64),
+        format!("{:.1}", pamiec_buffer as f64 / (1 << 20) as f64)
+    );
+    println!("{:<24}{:>12}{:>12}", "alokacje", frame.len() + 1, 1);
+    wiersz("tworzenie [ms]", t_frame, t_buffer);
+
+    // Suma kanałów, piksel po pikselu
+    let suma = |p: &Pixel| match p {
+        Pixel::RGB8(ColorRGB8(r, g, b)) => *r as u64 + *g as u64 + *b as u64,
+        _ => 0,
+    };
+    let (s1, t1) = pomiar(|| frame.iter().flatten().map(suma).sum::<u64>());
+    let (s2, t2) = pomiar(|| {
+        buffer
+            .pixels()
+            .map(|ColorRGB8(r, g, b)| r as u64 + g as u64 + b as u64)
+            .sum::<u64>()
+    });
+    assert_eq!(s1, s2);
+    wiersz("suma pikseli [ms]", t1, t2);
+
+    // To samo na surowych bajtach linii
+    let (s3, t3) = pomiar(|| {
+        buffer
+            .rows()
+            .map(|l| l.iter().map(|b| *b as u64).sum::<u64>())
+            .sum::<u64>()
+    });
+    assert_eq!(s1, s3);
+    wiersz("suma bajtów linii [ms]", t1, t3);
+
+    // Wycinek 1920x1080: kopia linii kontra widok bez kopiowania
+    let (_, t1) = pomiar(|| -> Frame {
+        frame[540..1620]
+            .iter()
+            .map(|l| l[960..2880].to_vec())
+            .collect()
+    });
+    let (_, t2) = pomiar(|| buffer.view(960, 540, 1920, 1080).map(|v| v.width()));
+    wiersz("wycinek [ms]", t1, t2);
+
+    // Konwersje między postaciami
+    let (dynamiczny, t) = pomiar(|| ImageBuffer::from_frame(&frame));
+    println!("{:<24}{:>24.1}", "Frame -> ImageBuffer [ms]", ms(t));
+    let (_, t) = pomiar(|| dynamiczny.as_ref().map(|b| b.to_frame()));
+    println!("{:<24}{:>24.1}", "ImageBuffer -> Frame [ms]", ms(t));
+
+    Ok(())
+}
+
+fn pomiar<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let wynik = black_box(f());
+    (wynik, start.elapsed())
+}
+
+fn ms(t: Duration) -> f64 {
+    t.as_secs_f64() * 1000.0
+}
+
+fn wiersz(nazwa: &str, frame: Duration, buffer: Duration) {
+    println!("{nazwa:<24}{:>12.1}{:>12.1}", ms(frame), ms(buffer));
+}
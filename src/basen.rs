@@ -0,0 +1,801 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Rodzaj błędu dekodowania tekstu
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DecodeErrorKind {
+    /// Znak spoza alfabetu kodowania
+    InvalidCharacter(char),
+    /// Liczba znaków nie odpowiada pełnym bajtom
+    InvalidLength,
+    /// Niepoprawne dopełnienie `=`
+    InvalidPadding,
+    /// Grupa znaków koduje wartość większą niż mieści się w bajtach wyjściowych
+    Overflow,
+}
+
+/// Błąd dekodowania tekstu wraz z miejscem wystąpienia
+///
+/// `offset` to numer znaku (nie bajtu UTF-8) w tekście wejściowym. Dla błędów
+/// długości jest to długość tekstu.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct DecodeError {
+    pub offset: usize,
+    pub kind: DecodeErrorKind,
+}
+
+impl DecodeError {
+    fn new(offset: usize, kind: DecodeErrorKind) -> Self {
+        DecodeError { offset, kind }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let offset = self.offset;
+        match self.kind {
+            DecodeErrorKind::InvalidCharacter(c) => {
+                write!(f, "Invalid character {c:?} at offset {offset}")
+            }
+            DecodeErrorKind::InvalidLength => write!(f, "Invalid input length {offset}"),
+            DecodeErrorKind::InvalidPadding => write!(f, "Invalid padding at offset {offset}"),
+            DecodeErrorKind::Overflow => write!(f, "Group overflow at offset {offset}"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Opcje formatowania szesnastkowego
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct HexOptions {
+    /// Wielkie litery A-F
+    pub uppercase: bool,
+    /// Separator wstawiany między bajty, np. `" "` albo `":"`
+    pub separator: String,
+}
+
+/// Koduje bajty jako tekst szesnastkowy
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::basen::{hex_encode, HexOptions};
+/// assert_eq!("4a9c28", hex_encode(&[0x4a, 0x9c, 0x28], &HexOptions::default()));
+/// let opcje = HexOptions {
+///     uppercase: true,
+///     separator: ":".into(),
+/// };
+/// assert_eq!("4A:9C:28", hex_encode(&[0x4a, 0x9c, 0x28], &opcje));
+/// ```
+pub fn hex_encode(dane: &[u8], opcje: &HexOptions) -> String {
+    dane.iter()
+        .map(|b| {
+            if opcje.uppercase {
+                format!("{b:02X}")
+            } else {
+                format!("{b:02x}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(&opcje.separator)
+}
+
+/// Dekoduje tekst szesnastkowy. Wielkość liter nie ma znaczenia, a białe
+/// znaki oraz separatory `:` i `-` między bajtami są pomijane.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::basen::{hex_decode, DecodeError, DecodeErrorKind};
+/// assert_eq!(Ok(vec![0x4a, 0x9c, 0x28]), hex_decode("4A 9c:28"));
+/// assert_eq!(
+///     Err(DecodeError { offset: 3, kind: DecodeErrorKind::InvalidCharacter('g') }),
+///     hex_decode("4a9g")
+/// );
+/// assert_eq!(
+///     Err(DecodeError { offset: 3, kind: DecodeErrorKind::InvalidLength }),
+///     hex_decode("4a9")
+/// );
+/// ```
+pub fn hex_decode(tekst: &str) -> Result<Vec<u8>, DecodeError> {
+    let mut wynik = Vec::with_capacity(tekst.len() / 2);
+    // Starsza połówka bajtu czekająca na młodszą
+    let mut starsza: Option<u8> = None;
+
+    for (i, znak) in tekst.chars().enumerate() {
+        if let Some(cyfra) = znak.to_digit(16) {
+            match starsza.take() {
+                None => starsza = Some(cyfra as u8),
+                Some(s) => wynik.push((s << 4) | cyfra as u8),
+            }
+        } else if starsza.is_none() && (znak.is_ascii_whitespace() || znak == ':' || znak == '-') {
+            continue;
+        } else {
+            return Err(DecodeError::new(i, DecodeErrorKind::InvalidCharacter(znak)));
+        }
+    }
+
+    if starsza.is_some() {
+        return Err(DecodeError::new(
+            tekst.chars().count(),
+            DecodeErrorKind::InvalidLength,
+        ));
+    }
+    Ok(wynik)
+}
+
+/// Alfabet kodowania o podstawie będącej potęgą dwójki (base32, base64)
+struct Alfabet {
+    znaki: &'static [u8; 64],
+    /// Ile bitów niesie jeden znak
+    bity: u32,
+    /// Ile znaków tworzy pełny blok (do dopełnienia `=`)
+    blok: usize,
+    dopelnienie: bool,
+}
+
+const BASE32_ZNAKI: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567________________________________";
+const CROCKFORD_ZNAKI: &[u8; 64] =
+    b"0123456789ABCDEFGHJKMNPQRSTVWXYZ________________________________";
+const BASE64_ZNAKI: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const BASE64URL_ZNAKI: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+impl Alfabet {
+    fn rozmiar(&self) -> usize {
+        1 << self.bity
+    }
+
+    fn wartosc(&self, znak: char) -> Option<u32> {
+        let znak = u8::try_from(znak).ok()?;
+        // Tylko base32 ma mniej niż 64 znaki i jest niewrażliwy na wielkość liter
+        let znak = if self.bity == 5 {
+            znak.to_ascii_uppercase()
+        } else {
+            znak
+        };
+        self.znaki[..self.rozmiar()]
+            .iter()
+            .position(|z| *z == znak)
+            .map(|p| p as u32)
+    }
+
+    fn koduj(&self, dane: &[u8]) -> String {
+        let mut wynik = String::new();
+        let mut akumulator = 0_u32;
+        let mut ile_bitow = 0;
+        let maska = (1 << self.bity) - 1;
+
+        for bajt in dane {
+            akumulator = (akumulator << 8) | *bajt as u32;
+            ile_bitow += 8;
+            while ile_bitow >= self.bity {
+                ile_bitow -= self.bity;
+                wynik.push(self.znaki[((akumulator >> ile_bitow) & maska) as usize] as char);
+            }
+        }
+        // Resztę bitów dopełniamy zerami z prawej
+        if ile_bitow > 0 {
+            wynik.push(
+                self.znaki[((akumulator << (self.bity - ile_bitow)) & maska) as usize] as char,
+            );
+        }
+        if self.dopelnienie {
+            while !wynik.len().is_multiple_of(self.blok) {
+                wynik.push('=');
+            }
+        }
+        wynik
+    }
+
+    /// Dekoduje tekst; `tlumacz` pozwala podmienić znaki przed wyszukaniem w
+    /// alfabecie (None - znak pomijany)
+    fn dekoduj(
+        &self,
+        tekst: &str,
+        tlumacz: impl Fn(char) -> Option<char>,
+    ) -> Result<Vec<u8>, DecodeError> {
+        let mut wynik = Vec::new();
+        let mut akumulator = 0_u32;
+        let mut ile_bitow = 0;
+        let mut ile_znakow = 0;
+        let mut ile_dopelnien = 0;
+
+        for (i, znak) in tekst.chars().enumerate() {
+            if znak.is_ascii_whitespace() {
+                continue;
+            }
+            if znak == '=' && self.dopelnienie && ile_znakow > 0 {
+                ile_dopelnien += 1;
+                continue;
+            }
+            let Some(znak) = tlumacz(znak) else {
+                continue;
+            };
+            let wartosc = self
+                .wartosc(znak)
+                .filter(|_| ile_dopelnien == 0)
+                .ok_or(DecodeError::new(i, DecodeErrorKind::InvalidCharacter(znak)))?;
+
+            akumulator = (akumulator << self.bity) | wartosc;
+            ile_bitow += self.bity;
+            ile_znakow += 1;
+            if ile_bitow >= 8 {
+                ile_bitow -= 8;
+                wynik.push((akumulator >> ile_bitow) as u8);
+            }
+        }
+
+        let dlugosc = tekst.chars().count();
+        // Znak, który nie domyka żadnego bajtu, nie może wystąpić
+        if ile_bitow >= self.bity {
+            return Err(DecodeError::new(dlugosc, DecodeErrorKind::InvalidLength));
+        }
+        // Dopełnienie jest opcjonalne, ale jeśli jest, to musi domykać blok
+        if ile_dopelnien > 0
+            && (ile_dopelnien >= self.blok
+                || !(ile_znakow + ile_dopelnien).is_multiple_of(self.blok))
+        {
+            return Err(DecodeError::new(dlugosc, DecodeErrorKind::InvalidPadding));
+        }
+        Ok(wynik)
+    }
+}
+
+const BASE32: Alfabet = Alfabet {
+    znaki: BASE32_ZNAKI,
+    bity: 5,
+    blok: 8,
+    dopelnienie: true,
+};
+const CROCKFORD: Alfabet = Alfabet {
+    znaki: CROCKFORD_ZNAKI,
+    bity: 5,
+    blok: 8,
+    dopelnienie: false,
+};
+const BASE64: Alfabet = Alfabet {
+    znaki: BASE64_ZNAKI,
+    bity: 6,
+    blok: 4,
+    dopelnienie: true,
+};
+const BASE64URL: Alfabet = Alfabet {
+    znaki: BASE64URL_ZNAKI,
+    bity: 6,
+    blok: 4,
+    dopelnienie: true,
+};
+
+/// Tłumaczenie znaków podobnych do cyfr w base32 Crockforda; myślniki są
+/// pomijane
+fn crockford_znak(znak: char) -> Option<char> {
+    match znak.to_ascii_uppercase() {
+        '-' => None,
+        'O' => Some('0'),
+        'I' | 'L' => Some('1'),
+        z => Some(z),
+    }
+}
+
+/// Koduje bajty w base32 według RFC 4648 (z dopełnieniem `=`)
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::basen::base32_encode;
+/// // Wektory testowe z RFC 4648, rozdział 10
+/// assert_eq!("", base32_encode(b""));
+/// assert_eq!("MY======", base32_encode(b"f"));
+/// assert_eq!("MZXQ====", base32_encode(b"fo"));
+/// assert_eq!("MZXW6===", base32_encode(b"foo"));
+/// assert_eq!("MZXW6YQ=", base32_encode(b"foob"));
+/// assert_eq!("MZXW6YTB", base32_encode(b"fooba"));
+/// assert_eq!("MZXW6YTBOI======", base32_encode(b"foobar"));
+/// ```
+pub fn base32_encode(dane: &[u8]) -> String {
+    BASE32.koduj(dane)
+}
+
+/// Dekoduje base32 według RFC 4648. Dopełnienie jest opcjonalne, a małe litery
+/// są akceptowane.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::basen::{base32_decode, DecodeErrorKind};
+/// assert_eq!(Ok(b"foobar".to_vec()), base32_decode("MZXW6YTBOI======"));
+/// assert_eq!(Ok(b"foob".to_vec()), base32_decode("mzxw6yq"));
+/// assert_eq!(DecodeErrorKind::InvalidCharacter('1'), base32_decode("MZ1W").unwrap_err().kind);
+/// assert_eq!(2, base32_decode("MZ1W").unwrap_err().offset);
+/// ```
+pub fn base32_decode(tekst: &str) -> Result<Vec<u8>, DecodeError> {
+    BASE32.dekoduj(tekst, Some)
+}
+
+/// Koduje bajty w base32 Crockforda (bez dopełnienia)
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::basen::base32_crockford_encode;
+/// assert_eq!("CSQPYRK1E8", base32_crockford_encode(b"foobar"));
+/// ```
+pub fn base32_crockford_encode(dane: &[u8]) -> String {
+    CROCKFORD.koduj(dane)
+}
+
+/// Dekoduje base32 Crockforda. Wielkość liter nie ma znaczenia, `O` jest
+/// czytane jako `0`, `I` i `L` jako `1`, a myślniki są pomijane.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::basen::base32_crockford_decode;
+/// assert_eq!(Ok(b"foobar".to_vec()), base32_crockford_decode("csqp-yrk1-e8"));
+/// assert_eq!(base32_crockford_decode("0113"), base32_crockford_decode("oIL3"));
+/// assert!(base32_crockford_decode("CSQU").is_err());
+/// ```
+pub fn base32_crockford_decode(tekst: &str) -> Result<Vec<u8>, DecodeError> {
+    CROCKFORD.dekoduj(tekst, crockford_znak)
+}
+
+/// Warianty base64 z RFC 4648
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Base64Variant {
+    /// Alfabet `+/` z dopełnieniem
+    Standard,
+    /// Alfabet `-_` z dopełnieniem
+    UrlSafe,
+    /// Alfabet `+/` bez dopełnienia
+    StandardNoPad,
+    /// Alfabet `-_` bez dopełnienia
+    UrlSafeNoPad,
+}
+
+impl Base64Variant {
+    fn alfabet(&self) -> Alfabet {
+        match self {
+            Base64Variant::Standard => BASE64,
+            Base64Variant::UrlSafe => BASE64URL,
+            Base64Variant::StandardNoPad => Alfabet {
+                dopelnienie: false,
+                ..BASE64
+            },
+            Base64Variant::UrlSafeNoPad => Alfabet {
+                dopelnienie: false,
+                ..BASE64URL
+            },
+        }
+    }
+}
+
+/// Koduje bajty w base64
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::basen::{base64_encode, Base64Variant::*};
+/// // Wektory testowe z RFC 4648, rozdział 10
+/// assert_eq!("", base64_encode(b"", Standard));
+/// assert_eq!("Zg==", base64_encode(b"f", Standard));
+/// assert_eq!("Zm8=", base64_encode(b"fo", Standard));
+/// assert_eq!("Zm9v", base64_encode(b"foo", Standard));
+/// assert_eq!("Zm9vYg==", base64_encode(b"foob", Standard));
+/// assert_eq!("Zm9vYmE=", base64_encode(b"fooba", Standard));
+/// assert_eq!("Zm9vYmFy", base64_encode(b"foobar", Standard));
+/// assert_eq!("-_8", base64_encode(&[0xfb, 0xff], UrlSafeNoPad));
+/// assert_eq!("+/8=", base64_encode(&[0xfb, 0xff], Standard));
+/// ```
+pub fn base64_encode(dane: &[u8], wariant: Base64Variant) -> String {
+    wariant.alfabet().koduj(dane)
+}
+
+/// Dekoduje base64. W wariantach z dopełnieniem jest ono opcjonalne, ale jeśli
+/// występuje, musi być poprawne. Białe znaki są pomijane.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::basen::{base64_decode, Base64Variant::*, DecodeError, DecodeErrorKind};
+/// assert_eq!(Ok(b"foob".to_vec()), base64_decode("Zm9v\nYg==", Standard));
+/// assert_eq!(Ok(b"foob".to_vec()), base64_decode("Zm9vYg", Standard));
+/// assert_eq!(
+///     Err(DecodeError { offset: 1, kind: DecodeErrorKind::InvalidCharacter('_') }),
+///     base64_decode("+_8=", Standard)
+/// );
+/// assert_eq!(
+///     Err(DecodeError { offset: 3, kind: DecodeErrorKind::InvalidCharacter('=') }),
+///     base64_decode("-_8=", UrlSafeNoPad)
+/// );
+/// assert_eq!(
+///     Err(DecodeError { offset: 7, kind: DecodeErrorKind::InvalidPadding }),
+///     base64_decode("Zm9vYg=", Standard)
+/// );
+/// ```
+pub fn base64_decode(tekst: &str, wariant: Base64Variant) -> Result<Vec<u8>, DecodeError> {
+    wariant.alfabet().dekoduj(tekst, Some)
+}
+
+const Z85_ZNAKI: &[u8; 85] =
+    b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#";
+
+/// Koduje grupy po 4 bajty jako 5 cyfr o podstawie 85. Niepełna ostatnia
+/// grupa n bajtów daje n+1 znaków.
+fn base85_koduj(dane: &[u8], cyfra: impl Fn(u32) -> char, z_dla_zer: bool) -> String {
+    let mut wynik = String::new();
+    for grupa in dane.chunks(4) {
+        let mut bajty = [0u8; 4];
+        bajty[..grupa.len()].copy_from_slice(grupa);
+        let mut liczba = u32::from_be_bytes(bajty);
+
+        if z_dla_zer && grupa.len() == 4 && liczba == 0 {
+            wynik.push('z');
+            continue;
+        }
+
+        let mut cyfry = [0u32; 5];
+        for c in cyfry.iter_mut().rev() {
+            *c = liczba % 85;
+            liczba /= 85;
+        }
+        wynik.extend(cyfry[..grupa.len() + 1].iter().map(|c| cyfra(*c)));
+    }
+    wynik
+}
+
+/// Zamienia pełną lub niepełną (2-4 cyfry) grupę cyfr na bajty
+fn base85_grupa(
+    cyfry: &mut Vec<u32>,
+    poczatek: usize,
+    wynik: &mut Vec<u8>,
+) -> Result<(), DecodeError> {
+    let ile = cyfry.len();
+    // Niepełną grupę dopełniamy najwyższą cyfrą
+    cyfry.resize(5, 84);
+    let liczba = cyfry
+        .iter()
+        .try_fold(0_u32, |acc, c| acc.checked_mul(85)?.checked_add(*c))
+        .ok_or(DecodeError::new(poczatek, DecodeErrorKind::Overflow))?;
+    wynik.extend_from_slice(&liczba.to_be_bytes()[..ile - 1]);
+    cyfry.clear();
+    Ok(())
+}
+
+/// Odwrotność `base85_koduj`; `offset` to numer pierwszego znaku w oryginalnym
+/// tekście
+fn base85_dekoduj(
+    tekst: &str,
+    offset: usize,
+    wartosc: impl Fn(char) -> Option<u32>,
+    z_dla_zer: bool,
+) -> Result<Vec<u8>, DecodeError> {
+    let mut wynik = Vec::new();
+    // Cyfry bieżącej grupy i numer znaku, od którego się zaczyna
+    let mut cyfry = Vec::with_capacity(5);
+    let mut poczatek_grupy = offset;
+
+    for (i, znak) in tekst.chars().enumerate() {
+        let i = i + offset;
+        if znak.is_ascii_whitespace() {
+            continue;
+        }
+        if z_dla_zer && znak == 'z' && cyfry.is_empty() {
+            wynik.extend_from_slice(&[0; 4]);
+            continue;
+        }
+        let c =
+            wartosc(znak).ok_or(DecodeError::new(i, DecodeErrorKind::InvalidCharacter(znak)))?;
+        if cyfry.is_empty() {
+            poczatek_grupy = i;
+        }
+        cyfry.push(c);
+        if cyfry.len() == 5 {
+            base85_grupa(&mut cyfry, poczatek_grupy, &mut wynik)?;
+        }
+    }
+
+    match cyfry.len() {
+        0 => {}
+        1 => {
+            let dlugosc = offset + tekst.chars().count();
+            return Err(DecodeError::new(dlugosc, DecodeErrorKind::InvalidLength));
+        }
+        _ => base85_grupa(&mut cyfry, poczatek_grupy, &mut wynik)?,
+    }
+    Ok(wynik)
+}
+
+/// Koduje bajty w Ascii85 (wariant Adobe/btoa, bez ograniczników `<~ ~>`)
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::basen::ascii85_encode;
+/// assert_eq!("9jqo^BlbD-BleB1DJ+*+F(f,q", ascii85_encode(b"Man is distinguished"));
+/// assert_eq!("z", ascii85_encode(&[0, 0, 0, 0]));
+/// assert_eq!("!!", ascii85_encode(&[0]));
+/// ```
+pub fn ascii85_encode(dane: &[u8]) -> String {
+    base85_koduj(dane, |c| (b'!' + c as u8) as char, true)
+}
+
+/// Dekoduje Ascii85. Ograniczniki `<~ ~>` i białe znaki są pomijane.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::basen::{ascii85_decode, DecodeErrorKind};
+/// assert_eq!(Ok(b"Man is distinguished".to_vec()), ascii85_decode("<~9jqo^BlbD-BleB1DJ+*+F(f,q~>"));
+/// assert_eq!(Ok(vec![0, 0, 0, 0, 1]), ascii85_decode("z!<"));
+/// // Grupa "s8W-\"" to 2^32, o jeden za dużo
+/// assert_eq!(DecodeErrorKind::Overflow, ascii85_decode("s8W-\"").unwrap_err().kind);
+/// ```
+pub fn ascii85_decode(tekst: &str) -> Result<Vec<u8>, DecodeError> {
+    let wartosc = |z| ('!'..='u').contains(&z).then(|| z as u32 - '!' as u32);
+
+    // Ograniczniki są opcjonalne, ale numery znaków w błędach dotyczą całego
+    // tekstu
+    let poczatek = tekst.len() - tekst.trim_start().len();
+    match tekst[poczatek..].strip_prefix("<~") {
+        Some(reszta) => {
+            let reszta = reszta.trim_end();
+            let reszta = reszta.strip_suffix("~>").unwrap_or(reszta);
+            let offset = tekst[..poczatek].chars().count() + 2;
+            base85_dekoduj(reszta, offset, wartosc, true)
+        }
+        None => base85_dekoduj(tekst, 0, wartosc, true),
+    }
+}
+
+/// Koduje bajty w Z85 (ZeroMQ RFC 32). Specyfikacja wymaga długości
+/// podzielnej przez 4; dłuższe dane są kodowane jak w Ascii85 (n+1 znaków).
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::basen::z85_encode;
+/// // Wektor testowy ze specyfikacji
+/// let dane = [0x86, 0x4F, 0xD2, 0x6F, 0xB5, 0x59, 0xF7, 0x5B];
+/// assert_eq!("HelloWorld", z85_encode(&dane));
+/// ```
+pub fn z85_encode(dane: &[u8]) -> String {
+    base85_koduj(dane, |c| Z85_ZNAKI[c as usize] as char, false)
+}
+
+/// Dekoduje Z85
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::basen::z85_decode;
+/// let dane = vec![0x86, 0x4F, 0xD2, 0x6F, 0xB5, 0x59, 0xF7, 0x5B];
+/// assert_eq!(Ok(dane), z85_decode("HelloWorld"));
+/// assert_eq!(5, z85_decode("Hello~orld").unwrap_err().offset);
+/// ```
+pub fn z85_decode(tekst: &str) -> Result<Vec<u8>, DecodeError> {
+    base85_dekoduj(
+        tekst,
+        0,
+        |z| {
+            u8::try_from(z)
+                .ok()
+                .and_then(|z| Z85_ZNAKI.iter().position(|c| *c == z))
+                .map(|p| p as u32)
+        },
+        false,
+    )
+}
+
+const BASE58_ZNAKI: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Koduje bajty w base58 (alfabet Bitcoina). Każdy zerowy bajt na początku
+/// to znak `1`.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::basen::base58_encode;
+/// // Wektory testowe z draft-msporny-base58
+/// assert_eq!("2NEpo7TZRRrLZSi2U", base58_encode(b"Hello World!"));
+/// assert_eq!("11233QC4", base58_encode(&[0, 0, 0x28, 0x7f, 0xb4, 0xcd]));
+/// ```
+pub fn base58_encode(dane: &[u8]) -> String {
+    let zera = dane.iter().take_while(|b| **b == 0).count();
+
+    // Zamiana podstawy 256 -> 58 na cyfrach little endian
+    let mut cyfry: Vec<u32> = Vec::new();
+    for bajt in &dane[zera..] {
+        let mut przeniesienie = *bajt as u32;
+        for c in cyfry.iter_mut() {
+            przeniesienie += *c << 8;
+            *c = przeniesienie % 58;
+            przeniesienie /= 58;
+        }
+        while przeniesienie > 0 {
+            cyfry.push(przeniesienie % 58);
+            przeniesienie /= 58;
+        }
+    }
+
+    let mut wynik = "1".repeat(zera);
+    wynik.extend(
+        cyfry
+            .iter()
+            .rev()
+            .map(|c| BASE58_ZNAKI[*c as usize] as char),
+    );
+    wynik
+}
+
+/// Dekoduje base58 (alfabet Bitcoina)
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::basen::{base58_decode, DecodeErrorKind};
+/// assert_eq!(Ok(b"Hello World!".to_vec()), base58_decode("2NEpo7TZRRrLZSi2U"));
+/// assert_eq!(Ok(vec![0, 0, 0x28, 0x7f, 0xb4, 0xcd]), base58_decode("11233QC4"));
+/// // Zero nie należy do alfabetu
+/// assert_eq!(DecodeErrorKind::InvalidCharacter('0'), base58_decode("2NE0").unwrap_err().kind);
+/// ```
+pub fn base58_decode(tekst: &str) -> Result<Vec<u8>, DecodeError> {
+    let mut zera = 0;
+    let mut tylko_zera = true;
+    // Bajty wyniku little endian
+    let mut bajty: Vec<u8> = Vec::new();
+
+    for (i, znak) in tekst.chars().enumerate() {
+        if znak.is_ascii_whitespace() {
+            continue;
+        }
+        let cyfra = u8::try_from(znak)
+            .ok()
+            .and_then(|z| BASE58_ZNAKI.iter().position(|c| *c == z))
+            .ok_or(DecodeError::new(i, DecodeErrorKind::InvalidCharacter(znak)))?;
+
+        if tylko_zera && cyfra == 0 {
+            zera += 1;
+            continue;
+        }
+        tylko_zera = false;
+
+        let mut przeniesienie = cyfra as u32;
+        for b in bajty.iter_mut() {
+            przeniesienie += *b as u32 * 58;
+            *b = przeniesienie as u8;
+            przeniesienie >>= 8;
+        }
+        while przeniesienie > 0 {
+            bajty.push(przeniesienie as u8);
+            przeniesienie >>= 8;
+        }
+    }
+
+    let mut wynik = vec![0u8; zera];
+    wynik.extend(bajty.iter().rev());
+    Ok(wynik)
+}
+
+/// Wszystkie obsługiwane kodowania tekstowe
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Encoding {
+    Hex,
+    Base32,
+    Base32Crockford,
+    Base58,
+    Base64(Base64Variant),
+    Ascii85,
+    Z85,
+}
+
+impl Encoding {
+    /// Kodowania w kolejności od najbardziej specyficznego (najmniejszy
+    /// alfabet). W tej kolejności sprawdza je [`detect`].
+    pub const ALL: [Encoding; 10] = [
+        Encoding::Hex,
+        Encoding::Base32,
+        Encoding::Base32Crockford,
+        Encoding::Base58,
+        Encoding::Base64(Base64Variant::Standard),
+        Encoding::Base64(Base64Variant::UrlSafe),
+        Encoding::Base64(Base64Variant::StandardNoPad),
+        Encoding::Base64(Base64Variant::UrlSafeNoPad),
+        Encoding::Ascii85,
+        Encoding::Z85,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encoding::Hex => "hex",
+            Encoding::Base32 => "base32",
+            Encoding::Base32Crockford => "crockford",
+            Encoding::Base58 => "base58",
+            Encoding::Base64(Base64Variant::Standard) => "base64",
+            Encoding::Base64(Base64Variant::UrlSafe) => "base64url",
+            Encoding::Base64(Base64Variant::StandardNoPad) => "base64-nopad",
+            Encoding::Base64(Base64Variant::UrlSafeNoPad) => "base64url-nopad",
+            Encoding::Ascii85 => "ascii85",
+            Encoding::Z85 => "z85",
+        }
+    }
+
+    pub fn encode(&self, dane: &[u8]) -> String {
+        match self {
+            Encoding::Hex => hex_encode(dane, &HexOptions::default()),
+            Encoding::Base32 => base32_encode(dane),
+            Encoding::Base32Crockford => base32_crockford_encode(dane),
+            Encoding::Base58 => base58_encode(dane),
+            Encoding::Base64(wariant) => base64_encode(dane, *wariant),
+            Encoding::Ascii85 => ascii85_encode(dane),
+            Encoding::Z85 => z85_encode(dane),
+        }
+    }
+
+    pub fn decode(&self, tekst: &str) -> Result<Vec<u8>, DecodeError> {
+        match self {
+            Encoding::Hex => hex_decode(tekst),
+            Encoding::Base32 => base32_decode(tekst),
+            Encoding::Base32Crockford => base32_crockford_decode(tekst),
+            Encoding::Base58 => base58_decode(tekst),
+            Encoding::Base64(wariant) => base64_decode(tekst, *wariant),
+            Encoding::Ascii85 => ascii85_decode(tekst),
+            Encoding::Z85 => z85_decode(tekst),
+        }
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Encoding::ALL
+            .iter()
+            .find(|e| e.name() == s)
+            .copied()
+            .ok_or_else(|| format!("Unknown encoding {s:?}"))
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Zgaduje kodowanie próbki tekstu
+///
+/// Zwraca kodowania, którymi da się poprawnie zdekodować próbkę, od
+/// najbardziej prawdopodobnego. Krótkie próbki pasują zwykle do wielu
+/// kodowań - np. `cafe` to poprawny hex, base58 i base64.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::basen::{detect, Base64Variant, Encoding};
+/// assert_eq!(Some(&Encoding::Hex), detect("4a 9c 28 81").first());
+/// assert_eq!(Some(&Encoding::Base32), detect("MZXW6YTBOI======").first());
+/// assert_eq!(Some(&Encoding::Base58), detect("2NEpo7TZRRrLZSi2U").first());
+/// assert_eq!(
+///     Some(&Encoding::Base64(Base64Variant::Standard)),
+///     detect("Zm9vYmE=").first()
+/// );
+/// assert_eq!(Some(&Encoding::Ascii85), detect("<~9jqo^BlbD-BleB1DJ+*+F(f,q~>").first());
+/// assert!(detect("").is_empty());
+/// ```
+pub fn detect(tekst: &str) -> Vec<Encoding> {
+    let tekst = tekst.trim();
+    if tekst.is_empty() {
+        return Vec::new();
+    }
+    // Ograniczniki jednoznacznie wskazują Ascii85
+    if tekst.starts_with("<~") && tekst.ends_with("~>") && ascii85_decode(tekst).is_ok() {
+        return vec![Encoding::Ascii85];
+    }
+    Encoding::ALL
+        .iter()
+        .filter(|e| e.decode(tekst).is_ok())
+        .copied()
+        .collect()
+}
@@ -0,0 +1,109 @@
+use clap::Parser;
+use env_logger::Env;
+use eyre::{eyre, Result, WrapErr};
+use log::{error, info};
+use std::io::{Read, Write};
+
+use sekurak_hex_gynvael::basen::{self, Encoding};
+
+#[derive(Parser)]
+#[command(name = "basen")]
+#[command(author = "Pyth0n")]
+#[command(version = "1.0")]
+#[command(about = "Base-N text encoder/decoder", long_about = None)]
+struct Args {
+    /// Input file (stdin if missing)
+    #[arg(short, long)]
+    in_file: Option<String>,
+
+    /// Output file (stdout if missing)
+    #[arg(short, long)]
+    out_file: Option<String>,
+
+    /// Encoding: hex, base32, crockford, base58, base64, base64url,
+    /// base64-nopad, base64url-nopad, ascii85, z85. Guessed when decoding
+    /// without it.
+    #[arg(short, long)]
+    encoding: Option<Encoding>,
+
+    /// Decode text instead of encoding bytes
+    #[arg(short = 'D', long)]
+    decode: bool,
+
+    /// Only list encodings matching the input
+    #[arg(long)]
+    detect: bool,
+
+    /// Turn debugging on
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    debug: u8,
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let args = Args::parse();
+    let log_level = match args.debug {
+        0 => "info",
+        1 => "debug",
+        _ => "trace",
+    };
+    env_logger::Builder::from_env(Env::default().default_filter_or(log_level))
+        .format_timestamp(None)
+        .init();
+
+    let input = match &args.in_file {
+        Some(name) => std::fs::read(name).wrap_err_with(|| format!("Failed to read {name:?}"))?,
+        None => {
+            let mut buf = Vec::new();
+            std::io::stdin().read_to_end(&mut buf)?;
+            buf
+        }
+    };
+
+    let output = if args.detect || args.decode {
+        let text = String::from_utf8(input).wrap_err("Input is not UTF-8 text")?;
+        let candidates = basen::detect(&text);
+
+        if args.detect {
+            let names = candidates
+                .iter()
+                .map(|e| format!("{e}\n"))
+                .collect::<String>();
+            names.into_bytes()
+        } else {
+            let encoding = match args.encoding {
+                Some(e) => e,
+                None => {
+                    let e = *candidates
+                        .first()
+                        .ok_or_else(|| eyre!("Unable to guess encoding"))?;
+                    info!("Guessed encoding {e}");
+                    e
+                }
+            };
+            match encoding.decode(&text) {
+                Ok(data) => data,
+                Err(e) => {
+                    error!("{encoding}: {e}");
+                    return Ok(());
+                }
+            }
+        }
+    } else {
+        let encoding = args
+            .encoding
+            .ok_or_else(|| eyre!("Encoding is required when encoding"))?;
+        let mut text = encoding.encode(&input);
+        text.push('\n');
+        text.into_bytes()
+    };
+
+    match &args.out_file {
+        Some(name) => {
+            std::fs::write(name, output).wrap_err_with(|| format!("Failed to write {name:?}"))?
+        }
+        None => std::io::stdout().write_all(&output)?,
+    }
+
+    Ok(())
+}
@@ -1,24 +1,39 @@
 use clap::Parser;
 use env_logger::Env;
 use eyre::Result;
-use log::{debug, error};
+use log::{error, info};
 
 use sekurak_hex_gynvael::image_codec as ic;
+use sekurak_hex_gynvael::image_codec::raw::{RawFormat, RawOptions};
+use sekurak_hex_gynvael::literal::parse_int;
 
 #[derive(Parser)]
-#[command(name = "bmp2png")]
+#[command(name = "bmp2raw")]
 #[command(author = "Pyth0n")]
 #[command(version = "1.0")]
-#[command(about = "Simple UNZIP implementation", long_about = None)]
+#[command(about = "Convert BMP image to raw pixel data", long_about = None)]
 struct Args {
+    /// Input BMP file
     #[arg(short, long, default_value = "data/sing_scape.broken.bmp")]
-    /// Input ZIP file
     in_file: String,
 
-    /// Directory to unpack to
+    /// Output raw pixel file
     #[arg(short, long, default_value = "data/sing_scape.raw")]
     out_file: String,
 
+    /// Channel order: rgb, bgr, rgba, argb, gray8, index8 (image format if missing,
+    /// rgb for images with a palette)
+    #[arg(short, long)]
+    format: Option<RawFormat>,
+
+    /// Write each channel as a separate plane
+    #[arg(short, long)]
+    planar: bool,
+
+    /// Pad rows to a multiple of this many bytes
+    #[arg(short, long, default_value_t = 1, value_parser = parse_int::<usize>)]
+    align: usize,
+
     /// Turn debugging on
     #[arg(short, long, action = clap::ArgAction::Count)]
     debug: u8,
@@ -44,7 +59,23 @@ fn main() -> Result<()> {
         }
     };
 
-    ic::raw::write_raw(&args.out_file, &img)?;
+    let options = RawOptions {
+        // Indeksy bez palety nie pokazują obrazu - tylko na wyraźne żądanie
+        format: args.format.unwrap_or(match img.palette {
+            Some(_) => RawFormat::RGB,
+            None => RawFormat::from(img.pixels.format()),
+        }),
+        planar: args.planar,
+        row_align: args.align,
+    };
+    info!(
+        "{}x{} {}{}",
+        img.width(),
+        img.height(),
+        options.format,
+        if options.planar { " planar" } else { "" }
+    );
+    ic::raw::write_raw(&args.out_file, &img, &options)?;
 
     Ok(())
 }
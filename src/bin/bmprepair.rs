@@ -0,0 +1,69 @@
+use clap::Parser;
+use env_logger::Env;
+use eyre::{Result, WrapErr};
+use log::{error, info};
+
+use sekurak_hex_gynvael::image_codec::bmp_repair;
+
+#[derive(Parser)]
+#[command(name = "bmprepair")]
+#[command(author = "Pyth0n")]
+#[command(version = "1.0")]
+#[command(about = "Repair damaged BMP headers and palettes", long_about = None)]
+struct Args {
+    /// Damaged BMP file
+    in_file: String,
+
+    /// Repaired output file (<in_file>.repaired.bmp if missing)
+    #[arg(short, long)]
+    out_file: Option<String>,
+
+    /// Only report corrections, do not write the output
+    #[arg(short = 'n', long)]
+    dry_run: bool,
+
+    /// Turn debugging on
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    debug: u8,
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let args = Args::parse();
+    let log_level = match args.debug {
+        0 => "info",
+        1 => "debug",
+        _ => "trace",
+    };
+    env_logger::Builder::from_env(Env::default().default_filter_or(log_level))
+        .format_timestamp(None)
+        .init();
+
+    let data = std::fs::read(&args.in_file)
+        .wrap_err_with(|| format!("Failed to read {:?}", args.in_file))?;
+    let repaired = match bmp_repair::repair(&data) {
+        Ok(repaired) => repaired,
+        Err(e) => {
+            error!("{e}");
+            return Ok(());
+        }
+    };
+
+    if repaired.corrections.is_empty() {
+        info!("No damage found");
+    }
+    for correction in &repaired.corrections {
+        info!("Fixed {correction}");
+    }
+
+    if !args.dry_run {
+        let out_file = args
+            .out_file
+            .unwrap_or_else(|| format!("{}.repaired.bmp", args.in_file));
+        std::fs::write(&out_file, &repaired.data)
+            .wrap_err_with(|| format!("Failed to write {out_file:?}"))?;
+        info!("Written {out_file}");
+    }
+
+    Ok(())
+}
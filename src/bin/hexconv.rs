@@ -0,0 +1,110 @@
+use clap::Parser;
+use env_logger::Env;
+use eyre::{Result, WrapErr};
+use log::{error, info};
+use std::io::{Read, Write};
+
+use sekurak_hex_gynvael::byte_literals::{self, EmitOptions, Language, SourceFormat};
+
+#[derive(Parser)]
+#[command(name = "hexconv")]
+#[command(author = "Pyth0n")]
+#[command(version = "1.0")]
+#[command(about = "Convert bytes between hex dumps, escaped strings and source arrays", long_about = None)]
+struct Args {
+    /// Input file (stdin if missing)
+    #[arg(short, long)]
+    in_file: Option<String>,
+
+    /// Output file (stdout if missing)
+    #[arg(short, long)]
+    out_file: Option<String>,
+
+    /// Input text format: hex, escaped, array, xxd, hexdump-c, hexdump.
+    /// Guessed when missing.
+    #[arg(short, long)]
+    from: Option<SourceFormat>,
+
+    /// Treat input as raw binary instead of text
+    #[arg(short, long, conflicts_with = "from")]
+    binary: bool,
+
+    /// Output language: rust, c, python, go, js. Raw bytes when missing.
+    #[arg(short, long)]
+    to: Option<Language>,
+
+    /// Variable name in generated code
+    #[arg(short, long, default_value = "data")]
+    name: String,
+
+    /// Bytes per line in generated code
+    #[arg(short = 'w', long, default_value_t = 12)]
+    per_line: usize,
+
+    /// Turn debugging on
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    debug: u8,
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let args = Args::parse();
+    let log_level = match args.debug {
+        0 => "info",
+        1 => "debug",
+        _ => "trace",
+    };
+    env_logger::Builder::from_env(Env::default().default_filter_or(log_level))
+        .format_timestamp(None)
+        .init();
+
+    let input = match &args.in_file {
+        Some(name) => std::fs::read(name).wrap_err_with(|| format!("Failed to read {name:?}"))?,
+        None => {
+            let mut buf = Vec::new();
+            std::io::stdin().read_to_end(&mut buf)?;
+            buf
+        }
+    };
+
+    let data = if args.binary {
+        input
+    } else {
+        let text = String::from_utf8(input).wrap_err("Input is not UTF-8 text")?;
+        let format = match args.from {
+            Some(f) => f,
+            None => {
+                let f = SourceFormat::detect(&text);
+                info!("Guessed format {f}");
+                f
+            }
+        };
+        match byte_literals::parse_as(&text, format) {
+            Ok(data) => data,
+            Err(e) => {
+                error!("{format}: {e}");
+                return Ok(());
+            }
+        }
+    };
+
+    let output = match args.to {
+        Some(language) => {
+            let options = EmitOptions {
+                name: args.name,
+                per_line: args.per_line,
+            };
+            byte_literals::emit(&data, language, &options).into_bytes()
+        }
+        None => data,
+    };
+
+    match &args.out_file {
+        Some(name) => {
+            std::fs::write(name, output).wrap_err_with(|| format!("Failed to write {name:?}"))?
+        }
+        None => std::io::stdout().write_all(&output)?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use clap::Parser;
+use env_logger::Env;
+use eyre::{Result, WrapErr};
+
+use sekurak_hex_gynvael::files::File;
+use sekurak_hex_gynvael::inspector;
+use sekurak_hex_gynvael::literal::parse_int;
+
+#[derive(Parser)]
+#[command(name = "inspect")]
+#[command(author = "Pyth0n")]
+#[command(version = "1.0")]
+#[command(about = "Show bytes at an offset decoded as every supported type", long_about = None)]
+struct Args {
+    /// Input file
+    in_file: String,
+
+    /// Offset of the inspected bytes (e.g. 4096, 0x1_000, $1000, 1000h)
+    #[arg(short, long, default_value_t = 0, value_parser = parse_int::<u64>)]
+    offset: u64,
+
+    /// Turn debugging on
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    debug: u8,
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let args = Args::parse();
+    let log_level = match args.debug {
+        0 => "info",
+        1 => "debug",
+        _ => "trace",
+    };
+    env_logger::Builder::from_env(Env::default().default_filter_or(log_level))
+        .format_timestamp(None)
+        .init();
+
+    let mut file = File::open(&args.in_file)
+        .wrap_err_with(|| format!("Failed to open {:?}", args.in_file))?;
+    let inspection = inspector::inspect(&mut file, args.offset)?;
+    print!("{inspection}");
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use clap::Parser;
+use env_logger::Env;
+use eyre::{eyre, Result};
+use log::error;
+
+use sekurak_hex_gynvael::literal::Literal;
+use sekurak_hex_gynvael::number_view::{NumberView, WIDTHS};
+
+#[derive(Parser)]
+#[command(name = "numview")]
+#[command(author = "Pyth0n")]
+#[command(version = "1.0")]
+#[command(about = "Show a number under several readings", long_about = None)]
+struct Args {
+    /// Value: decimal (optionally negative), 0x.., 0o.., 0b.., $.. or ..h,
+    /// `_` allowed, optional type suffix like `u8` or `i64`
+    #[arg(allow_hyphen_values = true)]
+    value: Literal,
+
+    /// Width in bits (8, 16, 32, 64, 128); taken from the type suffix or
+    /// i32 like in Rust by default
+    #[arg(short, long)]
+    width: Option<u32>,
+
+    /// Turn debugging on
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    debug: u8,
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let args = Args::parse();
+    let log_level = match args.debug {
+        0 => "info",
+        1 => "debug",
+        _ => "trace",
+    };
+    env_logger::Builder::from_env(Env::default().default_filter_or(log_level))
+        .format_timestamp(None)
+        .init();
+
+    let width = args
+        .width
+        .or(args.value.suffix.map(|s| s.bits))
+        .unwrap_or(32);
+    if !WIDTHS.contains(&width) {
+        return Err(eyre!("Unsupported width {width}"));
+    }
+
+    let bits = match bits_for_width(&args.value, width) {
+        Ok(bits) => bits,
+        Err(e) => {
+            error!("{e}");
+            return Ok(());
+        }
+    };
+
+    // Unwrap, bo szerokość została sprawdzona wyżej
+    print!("{}", NumberView::new(bits, width).unwrap());
+    Ok(())
+}
+
+/// Bity liczby w typie o podanej szerokości; wartość musi się zmieścić ze
+/// znakiem albo bez
+fn bits_for_width(value: &Literal, width: u32) -> Result<u128> {
+    if value.negative {
+        let signed: i128 = value.value()?;
+        let min = -1i128 << (width - 1);
+        if signed < min {
+            return Err(eyre!("Value does not fit in i{width}"));
+        }
+        Ok(signed as u128 & (u128::MAX >> (128 - width)))
+    } else {
+        let unsigned: u128 = value.value()?;
+        if unsigned > u128::MAX >> (128 - width) {
+            return Err(eyre!("Value does not fit in u{width}"));
+        }
+        Ok(unsigned)
+    }
+}
@@ -0,0 +1,82 @@
+use clap::Parser;
+use env_logger::Env;
+use eyre::Result;
+use log::{error, info};
+
+use sekurak_hex_gynvael::image_codec as ic;
+use sekurak_hex_gynvael::image_codec::bmp::WriteOptions;
+use sekurak_hex_gynvael::image_codec::raw::{RawFormat, RawOptions};
+use sekurak_hex_gynvael::image_codec::ColorRGB8;
+use sekurak_hex_gynvael::literal::parse_int;
+
+#[derive(Parser)]
+#[command(name = "raw2bmp")]
+#[command(author = "Pyth0n")]
+#[command(version = "1.0")]
+#[command(about = "Convert raw pixel data to BMP", long_about = None)]
+struct Args {
+    /// Raw pixel file
+    in_file: String,
+
+    /// Output BMP file
+    out_file: String,
+
+    /// Image width in pixels
+    #[arg(short = 'W', long, value_parser = parse_int::<usize>)]
+    width: usize,
+
+    /// Image height in pixels
+    #[arg(short = 'H', long, value_parser = parse_int::<usize>)]
+    height: usize,
+
+    /// Channel order: rgb, bgr, rgba, argb, gray8, index8
+    #[arg(short, long, default_value_t = RawFormat::RGB)]
+    format: RawFormat,
+
+    /// Channels are stored as separate planes
+    #[arg(short, long)]
+    planar: bool,
+
+    /// Rows are padded to a multiple of this many bytes
+    #[arg(short, long, default_value_t = 1, value_parser = parse_int::<usize>)]
+    align: usize,
+
+    /// Turn debugging on
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    debug: u8,
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let args = Args::parse();
+    let log_level = match args.debug {
+        0 => "info",
+        1 => "debug",
+        _ => "trace",
+    };
+    env_logger::Builder::from_env(Env::default().default_filter_or(log_level))
+        .format_timestamp(None)
+        .init();
+
+    let options = RawOptions {
+        format: args.format,
+        planar: args.planar,
+        row_align: args.align,
+    };
+    let mut img = match ic::raw::read_raw(&args.in_file, args.width, args.height, &options) {
+        Ok(img) => img,
+        Err(e) => {
+            error!("{e}");
+            return Ok(());
+        }
+    };
+
+    // Bez palety indeksy zapisywane są jako odcienie szarości
+    if options.format == RawFormat::INDEX8 {
+        img.palette = Some((0..=255).map(|i| ColorRGB8(i, i, i)).collect());
+    }
+    ic::bmp::write_bmp(&args.out_file, &img, &WriteOptions::default())?;
+    info!("Written {}", args.out_file);
+
+    Ok(())
+}
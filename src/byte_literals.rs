@@ -0,0 +1,723 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::basen::{hex_decode, DecodeErrorKind};
+use crate::literal::parse_int;
+
+/// Tekstowe formy ciągów bajtów rozpoznawane przy wczytywaniu
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SourceFormat {
+    /// Goły hex: `4a9c`, `4a 9c`, `4a:9c`
+    Hex,
+    /// Sekwencje `\x4a\x9c`, także w cudzysłowach i jako `b'..'` z Pythona
+    Escaped,
+    /// Lista liczb, np. tablica C/Rust/Go/JS albo `0x4a, 0x9c`
+    Array,
+    /// Wynik `xxd`
+    Xxd,
+    /// Wynik `hexdump -C`
+    HexdumpCanonical,
+    /// Wynik `hexdump` bez opcji (16-bitowe słowa little endian)
+    Hexdump,
+}
+
+impl SourceFormat {
+    pub const ALL: [SourceFormat; 6] = [
+        SourceFormat::Hex,
+        SourceFormat::Escaped,
+        SourceFormat::Array,
+        SourceFormat::Xxd,
+        SourceFormat::HexdumpCanonical,
+        SourceFormat::Hexdump,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SourceFormat::Hex => "hex",
+            SourceFormat::Escaped => "escaped",
+            SourceFormat::Array => "array",
+            SourceFormat::Xxd => "xxd",
+            SourceFormat::HexdumpCanonical => "hexdump-c",
+            SourceFormat::Hexdump => "hexdump",
+        }
+    }
+
+    /// Zgaduje format tekstu
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::byte_literals::SourceFormat;
+    /// assert_eq!(SourceFormat::Hex, SourceFormat::detect("4a 9c"));
+    /// assert_eq!(SourceFormat::Escaped, SourceFormat::detect(r"\x4a\x9c"));
+    /// assert_eq!(SourceFormat::Escaped, SourceFormat::detect("b'J\\x9c'"));
+    /// assert_eq!(SourceFormat::Escaped, SourceFormat::detect("d = (\n    b'a,b'\n)"));
+    /// assert_eq!(SourceFormat::Array, SourceFormat::detect("0x4a, 0x9c"));
+    /// assert_eq!(SourceFormat::Array, SourceFormat::detect("char x[] = {74, 156};"));
+    /// assert_eq!(SourceFormat::Xxd, SourceFormat::detect("00000000: 4a9c  J."));
+    /// assert_eq!(SourceFormat::HexdumpCanonical, SourceFormat::detect("00000000  4a 9c  |J.|"));
+    /// assert_eq!(SourceFormat::Hexdump, SourceFormat::detect("0000000 9c4a\n0000002"));
+    /// ```
+    pub fn detect(tekst: &str) -> SourceFormat {
+        let linie: Vec<&str> = tekst
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .collect();
+        let poczatek = tekst.trim_start();
+
+        // Napis bajtowy Pythona, także w przypisaniu i nawiasach (wynik `emit`)
+        let napis_bajtowy = tekst.match_indices('b').any(|(i, _)| {
+            tekst[i + 1..].starts_with(['\'', '"'])
+                && tekst[..i]
+                    .chars()
+                    .next_back()
+                    .is_none_or(|c| c.is_whitespace() || "(=+,".contains(c))
+        });
+        if poczatek.starts_with(['\'', '"']) || napis_bajtowy || tekst.contains("\\x") {
+            return SourceFormat::Escaped;
+        }
+        fn pierwsze<'a>(l: &&'a str) -> &'a str {
+            l.split_whitespace().next().unwrap_or("")
+        }
+        if !linie.is_empty()
+            && linie.iter().all(|l| {
+                *l == "*"
+                    || pierwsze(l)
+                        .strip_suffix(':')
+                        .is_some_and(|o| !o.is_empty() && o.chars().all(|c| c.is_ascii_hexdigit()))
+            })
+        {
+            return SourceFormat::Xxd;
+        }
+        let offset = |l: &&str, dlugosc| {
+            let o = pierwsze(l);
+            o == "*" || (o.len() == dlugosc && o.chars().all(|c| c.is_ascii_hexdigit()))
+        };
+        if linie.iter().any(|l| l.contains('|')) && linie.iter().all(|l| offset(l, 8)) {
+            return SourceFormat::HexdumpCanonical;
+        }
+        if !linie.is_empty()
+            && linie.iter().all(|l| {
+                offset(l, 7)
+                    && l.split_whitespace()
+                        .skip(1)
+                        .all(|s| s.len() == 4 && s.chars().all(|c| c.is_ascii_hexdigit()))
+            })
+        {
+            return SourceFormat::Hexdump;
+        }
+        if tekst.contains(['{', '[', ',']) || tekst.contains("0x") || tekst.contains("0X") {
+            return SourceFormat::Array;
+        }
+        SourceFormat::Hex
+    }
+}
+
+impl FromStr for SourceFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SourceFormat::ALL
+            .into_iter()
+            .find(|f| f.name() == s.to_ascii_lowercase())
+            .ok_or_else(|| format!("Unknown format {s:?}"))
+    }
+}
+
+impl fmt::Display for SourceFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Rodzaj błędu wczytywania
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseErrorKind {
+    /// Znak nienależący do formatu
+    InvalidCharacter(char),
+    /// Nieparzysta liczba cyfr szesnastkowych
+    InvalidLength,
+    /// Niepoprawna sekwencja po `\`
+    InvalidEscape,
+    /// Element tablicy, który nie jest bajtem
+    InvalidToken(String),
+    /// Niezamknięty napis albo nawias
+    Unterminated,
+    /// Powtórzenie `*` do offsetu, który nie jest wielokrotnością długości
+    /// powtarzanej linii albo przekracza [`MAX_DUMP_SIZE`]
+    InvalidRepeat { offset: usize },
+}
+
+/// Błąd wczytywania z numerem linii (od 1)
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl ParseError {
+    fn new(line: usize, kind: ParseErrorKind) -> Self {
+        ParseError { line, kind }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Line {}: ", self.line)?;
+        match &self.kind {
+            ParseErrorKind::InvalidCharacter(c) => write!(f, "invalid character {c:?}"),
+            ParseErrorKind::InvalidLength => write!(f, "odd number of hex digits"),
+            ParseErrorKind::InvalidEscape => write!(f, "invalid escape sequence"),
+            ParseErrorKind::InvalidToken(t) => write!(f, "{t:?} is not a byte"),
+            ParseErrorKind::Unterminated => write!(f, "unterminated literal"),
+            ParseErrorKind::InvalidRepeat { offset } => {
+                write!(f, "cannot repeat previous line up to offset {offset:#x}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Wczytuje bajty z tekstu, zgadując format ([`SourceFormat::detect`])
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::byte_literals::parse_bytes;
+/// let oczekiwane = vec![0x4a, 0x9c, 0x28];
+/// assert_eq!(Ok(oczekiwane.clone()), parse_bytes("4a9c28"));
+/// assert_eq!(Ok(oczekiwane.clone()), parse_bytes("4a 9c 28"));
+/// assert_eq!(Ok(oczekiwane.clone()), parse_bytes(r"\x4a\x9c\x28"));
+/// assert_eq!(Ok(oczekiwane.clone()), parse_bytes("0x4a, 0x9c, 0x28"));
+/// assert_eq!(Ok(oczekiwane.clone()), parse_bytes("b'J\\x9c('"));
+/// assert_eq!(Ok(oczekiwane.clone()), parse_bytes("unsigned char d[3] = { 0x4a, 156, '(' };"));
+/// assert_eq!(Ok(oczekiwane.clone()), parse_bytes("let d: [u8; 3] = [0x4a, 0x9c, 0x28];"));
+/// assert_eq!(Ok(oczekiwane.clone()), parse_bytes("d := []byte{0x4a, 0x9c, 0x28}"));
+/// assert_eq!(Ok(oczekiwane.clone()), parse_bytes("new Uint8Array([74, -100, 40])"));
+/// assert_eq!(Ok(oczekiwane.clone()), parse_bytes("00000000: 4a9c 28                                J.("));
+/// assert_eq!(
+///     Ok(oczekiwane.clone()),
+///     parse_bytes("00000000  4a 9c 28                                          |J.(|\n00000003\n")
+/// );
+/// assert_eq!(Ok(oczekiwane), parse_bytes("0000000 9c4a 0028\n0000003\n"));
+/// ```
+///
+/// Znaki komentarzy, przecinki i nawiasy w literałach znakowych i napisach są
+/// zwykłymi znakami:
+///
+/// ```
+/// use sekurak_hex_gynvael::byte_literals::parse_bytes;
+/// assert_eq!(Ok(vec![b'#', 0x41]), parse_bytes("char s[] = {'#', 0x41};"));
+/// assert_eq!(Ok(vec![b'/', b'*', 0x41]), parse_bytes("{'/', '*', 0x41} // '/'"));
+/// assert_eq!(Ok(vec![0x41]), parse_bytes("const char *s = \"//\"; char d[] = {0x41};"));
+/// assert_eq!(Ok(vec![b',', 0x41]), parse_bytes("{ ',', 0x41 }"));
+/// assert_eq!(Ok(vec![b'}', b'\'', 0x41]), parse_bytes(r"{'}', '\'', 0x41}"));
+/// ```
+///
+/// Linia `*` w zrzucie powtarza poprzednią linię do następnego offsetu:
+///
+/// ```
+/// use sekurak_hex_gynvael::byte_literals::{parse_bytes, ParseError, ParseErrorKind};
+/// let zrzut = "00000000: 0102  ..\n*\n00000006: 03  .\n";
+/// assert_eq!(Ok(vec![1, 2, 1, 2, 1, 2, 3]), parse_bytes(zrzut));
+///
+/// let zrzut = "00000000: 0102  ..\n*\nffffffff: 00  .\n";
+/// let blad = ParseErrorKind::InvalidRepeat { offset: 0xffff_ffff };
+/// assert_eq!(Err(ParseError { line: 3, kind: blad }), parse_bytes(zrzut));
+/// let zrzut = "00000000: 0102  ..\n*\n20000000: 00  .\n";
+/// assert!(parse_bytes(zrzut).is_err());
+/// ```
+pub fn parse_bytes(tekst: &str) -> Result<Vec<u8>, ParseError> {
+    parse_as(tekst, SourceFormat::detect(tekst))
+}
+
+/// Wczytuje bajty z tekstu w podanym formacie
+pub fn parse_as(tekst: &str, format: SourceFormat) -> Result<Vec<u8>, ParseError> {
+    match format {
+        SourceFormat::Hex => {
+            let mut wynik = Vec::new();
+            for (nr, linia) in tekst.lines().enumerate() {
+                wynik.extend(hex_z_linii(linia, nr + 1)?);
+            }
+            Ok(wynik)
+        }
+        SourceFormat::Escaped => parse_escaped(tekst),
+        SourceFormat::Array => parse_array(tekst),
+        SourceFormat::Xxd => parse_dump(tekst, |linia| {
+            let (offset, reszta) = linia.split_once(':')?;
+            // Kolumna ASCII zaczyna się po dwóch spacjach
+            let hex = reszta.trim_start().split("  ").next().unwrap_or("");
+            Some((offset.trim(), hex.to_string(), false))
+        }),
+        SourceFormat::HexdumpCanonical => parse_dump(tekst, |linia| {
+            let linia = linia.split('|').next().unwrap_or("");
+            let (offset, hex) = linia.split_once(char::is_whitespace).unwrap_or((linia, ""));
+            Some((offset, hex.to_string(), false))
+        }),
+        SourceFormat::Hexdump => parse_dump(tekst, |linia| {
+            let (offset, hex) = linia.split_once(char::is_whitespace).unwrap_or((linia, ""));
+            Some((offset, hex.to_string(), true))
+        }),
+    }
+}
+
+fn hex_z_linii(linia: &str, nr: usize) -> Result<Vec<u8>, ParseError> {
+    hex_decode(linia).map_err(|e| {
+        let kind = match e.kind {
+            DecodeErrorKind::InvalidCharacter(c) => ParseErrorKind::InvalidCharacter(c),
+            _ => ParseErrorKind::InvalidLength,
+        };
+        ParseError::new(nr, kind)
+    })
+}
+
+/// Największa długość danych odtwarzanych z powtórzeń `*` w zrzutach
+pub const MAX_DUMP_SIZE: usize = 1 << 28;
+
+/// Wspólna obsługa zrzutów z offsetami w pierwszej kolumnie
+///
+/// Linia `*` oznacza powtórzenie poprzedniej linii aż do następnego offsetu.
+/// Linia z samym offsetem na końcu podaje długość danych.
+fn parse_dump<F>(tekst: &str, rozbij: F) -> Result<Vec<u8>, ParseError>
+where
+    F: Fn(&str) -> Option<(&str, String, bool)>,
+{
+    let mut wynik: Vec<u8> = Vec::new();
+    let mut poprzednia: Vec<u8> = Vec::new();
+    let mut powtorzenie = false;
+
+    for (nr, linia) in tekst.lines().enumerate() {
+        let nr = nr + 1;
+        let linia = linia.trim();
+        if linia.is_empty() {
+            continue;
+        }
+        if linia == "*" {
+            powtorzenie = true;
+            continue;
+        }
+        let blad = |kind| ParseError::new(nr, kind);
+        let (offset, hex, slowa) =
+            rozbij(linia).ok_or_else(|| blad(ParseErrorKind::InvalidToken(linia.to_string())))?;
+        let offset = usize::from_str_radix(offset, 16)
+            .map_err(|_| blad(ParseErrorKind::InvalidToken(offset.to_string())))?;
+
+        if powtorzenie && !poprzednia.is_empty() {
+            let luka = offset.saturating_sub(wynik.len());
+            if offset > MAX_DUMP_SIZE || !luka.is_multiple_of(poprzednia.len()) {
+                return Err(blad(ParseErrorKind::InvalidRepeat { offset }));
+            }
+            for _ in 0..luka / poprzednia.len() {
+                wynik.extend_from_slice(&poprzednia);
+            }
+            powtorzenie = false;
+        }
+        wynik.truncate(offset);
+
+        let mut bajty = hex_z_linii(&hex, nr)?;
+        if slowa {
+            // hexdump bez opcji pokazuje 16-bitowe słowa little endian
+            for para in bajty.chunks_exact_mut(2) {
+                para.swap(0, 1);
+            }
+        }
+        if !bajty.is_empty() {
+            wynik.extend_from_slice(&bajty);
+            poprzednia = std::mem::take(&mut bajty);
+        }
+    }
+    Ok(wynik)
+}
+
+/// Napisy z sekwencjami `\x..`: w cudzysłowach (C, Python `b'..'`, sklejane
+/// jak w tych językach; tekst poza cudzysłowami jest pomijany) albo gołe,
+/// gdzie pomijane są tylko białe znaki
+fn parse_escaped(tekst: &str) -> Result<Vec<u8>, ParseError> {
+    let mut wynik = Vec::new();
+    let mut znaki = tekst.chars().peekable();
+    let mut nr = 1;
+    let w_cudzyslowach = tekst.contains(['\'', '"']);
+    // Znak zamykający bieżący napis
+    let mut napis: Option<char> = None;
+
+    while let Some(znak) = znaki.next() {
+        if znak == '\n' {
+            nr += 1;
+        }
+        match (napis, znak) {
+            (_, '\\') => {
+                if let Some(bajt) = sekwencja(&mut znaki, &mut nr)? {
+                    wynik.push(bajt);
+                }
+            }
+            (Some(koniec), z) if z == koniec => napis = None,
+            (Some(_), '\n') => return Err(ParseError::new(nr, ParseErrorKind::Unterminated)),
+            (Some(_), z) => {
+                let mut bufor = [0u8; 4];
+                wynik.extend_from_slice(z.encode_utf8(&mut bufor).as_bytes());
+            }
+            (None, '\'' | '"') => napis = Some(znak),
+            (None, z) if z.is_whitespace() => {}
+            // Poza napisami jest tylko otoczka: przypisanie, przedrostek b'',
+            // nawiasy i operatory sklejania
+            (None, _) if w_cudzyslowach => {}
+            (None, z) => return Err(ParseError::new(nr, ParseErrorKind::InvalidCharacter(z))),
+        }
+    }
+    if napis.is_some() {
+        return Err(ParseError::new(nr, ParseErrorKind::Unterminated));
+    }
+    Ok(wynik)
+}
+
+/// Sekwencja po `\`; None dla kontynuacji linii
+fn sekwencja<I>(
+    znaki: &mut std::iter::Peekable<I>,
+    nr: &mut usize,
+) -> Result<Option<u8>, ParseError>
+where
+    I: Iterator<Item = char>,
+{
+    let blad = |nr| Err(ParseError::new(nr, ParseErrorKind::InvalidEscape));
+    let Some(znak) = znaki.next() else {
+        return blad(*nr);
+    };
+    let bajt = match znak {
+        'x' | 'X' => {
+            let cyfry: String = (0..2).filter_map(|_| znaki.next()).collect();
+            match u8::from_str_radix(&cyfry, 16) {
+                Ok(b) if cyfry.len() == 2 => b,
+                _ => return blad(*nr),
+            }
+        }
+        '0'..='7' => {
+            // Ósemkowo, do trzech cyfr (C, Python)
+            let mut wartosc = znak.to_digit(8).unwrap();
+            for _ in 0..2 {
+                match znaki.peek().and_then(|c| c.to_digit(8)) {
+                    Some(c) => {
+                        wartosc = wartosc * 8 + c;
+                        znaki.next();
+                    }
+                    None => break,
+                }
+            }
+            match u8::try_from(wartosc) {
+                Ok(b) => b,
+                Err(_) => return blad(*nr),
+            }
+        }
+        'n' => b'\n',
+        'r' => b'\r',
+        't' => b'\t',
+        'a' => 0x07,
+        'b' => 0x08,
+        'f' => 0x0C,
+        'v' => 0x0B,
+        '\\' | '\'' | '"' => znak as u8,
+        '\n' => {
+            *nr += 1;
+            return Ok(None);
+        }
+        _ => return blad(*nr),
+    };
+    Ok(Some(bajt))
+}
+
+/// Lista liczb; jeśli są nawiasy, liczy się tylko zawartość ostatniej pary
+/// (w `unsigned char d[3] = {..}` rozmiar tablicy jest pomijany)
+fn parse_array(tekst: &str) -> Result<Vec<u8>, ParseError> {
+    let tekst = bez_komentarzy(tekst);
+    let (poczatek, koniec) = ostatnie_nawiasy(&tekst)?;
+    let zawartosc = &tekst[poczatek..koniec];
+    let linia_poczatku = tekst[..poczatek].matches('\n').count() + 1;
+
+    // Przecinki poza literałami znakowymi
+    let mut napisy = Napisy::default();
+    let mut kawalki = Vec::new();
+    let mut poczatek_kawalka = 0;
+    for (i, z) in zawartosc.char_indices() {
+        if !napisy.krok(z) && z == ',' {
+            kawalki.push((poczatek_kawalka, &zawartosc[poczatek_kawalka..i]));
+            poczatek_kawalka = i + 1;
+        }
+    }
+    kawalki.push((poczatek_kawalka, &zawartosc[poczatek_kawalka..]));
+
+    let mut wynik = Vec::new();
+    for (poczatek_kawalka, kawalek) in kawalki {
+        let token = kawalek.trim();
+        if token.is_empty() {
+            // Przecinek na końcu listy
+            continue;
+        }
+        let przed_tokenem = poczatek_kawalka + kawalek.find(token).unwrap();
+        let nr_tokenu = linia_poczatku + zawartosc[..przed_tokenem].matches('\n').count();
+        let bajt = if token.starts_with('\'') {
+            match parse_escaped(token)?.as_slice() {
+                [b] => Some(*b),
+                _ => None,
+            }
+        } else {
+            parse_int::<u8>(token)
+                .or_else(|_| parse_int::<i8>(token).map(|b| b as u8))
+                .ok()
+        };
+        let bajt = bajt.ok_or_else(|| {
+            ParseError::new(nr_tokenu, ParseErrorKind::InvalidToken(token.to_string()))
+        })?;
+        wynik.push(bajt);
+    }
+    Ok(wynik)
+}
+
+/// Zakres zawartości ostatniej pary nawiasów `{}` albo `[]` (bez nich); cały
+/// tekst, jeśli nawiasów nie ma
+fn ostatnie_nawiasy(tekst: &str) -> Result<(usize, usize), ParseError> {
+    let mut napisy = Napisy::default();
+    let nawiasy: Vec<(usize, char)> = tekst
+        .char_indices()
+        .filter(|(_, z)| !napisy.krok(*z) && "{}[]".contains(*z))
+        .collect();
+    let Some(ostatni) = nawiasy.iter().rposition(|(_, z)| "}]".contains(*z)) else {
+        return Ok((0, tekst.len()));
+    };
+    let koniec = nawiasy[ostatni].0;
+    let mut glebokosc = 0;
+    for (i, z) in nawiasy[..=ostatni].iter().rev().copied() {
+        match z {
+            '}' | ']' => glebokosc += 1,
+            '{' | '[' => {
+                glebokosc -= 1;
+                if glebokosc == 0 {
+                    return Ok((i + 1, koniec));
+                }
+            }
+            _ => {}
+        }
+    }
+    let nr = tekst[..koniec].matches('\n').count() + 1;
+    Err(ParseError::new(nr, ParseErrorKind::Unterminated))
+}
+
+/// Śledzi, czy kolejne znaki należą do literału w `'..'` albo `"..."`
+#[derive(Default)]
+struct Napisy {
+    /// Znak zamykający bieżący literał
+    napis: Option<char>,
+    /// Poprzedni znak literału to `\`
+    escape: bool,
+}
+
+impl Napisy {
+    /// Przetwarza kolejny znak; true, jeśli należy do literału (razem z
+    /// cudzysłowami)
+    fn krok(&mut self, z: char) -> bool {
+        match self.napis {
+            Some(_) if self.escape => self.escape = false,
+            Some(_) if z == '\\' => self.escape = true,
+            // Literał niezamknięty do końca linii kończy się razem z nią
+            Some(koniec) if z == koniec || z == '\n' => self.napis = None,
+            Some(_) => {}
+            None if z == '\'' || z == '"' => self.napis = Some(z),
+            None => return false,
+        }
+        true
+    }
+}
+
+/// Usuwa komentarze `//` i `/* */` oraz `#` (Python), zachowując nowe linie i
+/// literały znakowe
+fn bez_komentarzy(tekst: &str) -> String {
+    let mut wynik = String::with_capacity(tekst.len());
+    let mut znaki = tekst.chars().peekable();
+    let mut napisy = Napisy::default();
+    while let Some(z) = znaki.next() {
+        if napisy.krok(z) {
+            wynik.push(z);
+            continue;
+        }
+        match (z, znaki.peek()) {
+            ('/', Some('/')) | ('#', _) => {
+                while znaki.peek().is_some_and(|c| *c != '\n') {
+                    znaki.next();
+                }
+            }
+            ('/', Some('*')) => {
+                znaki.next();
+                let mut poprzedni = ' ';
+                for c in znaki.by_ref() {
+                    if c == '\n' {
+                        wynik.push('\n');
+                    }
+                    if poprzedni == '*' && c == '/' {
+                        break;
+                    }
+                    poprzedni = c;
+                }
+            }
+            _ => wynik.push(z),
+        }
+    }
+    wynik
+}
+
+/// Języki, w których można zapisać bufor
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Language {
+    Rust,
+    C,
+    Python,
+    Go,
+    JavaScript,
+}
+
+impl Language {
+    pub const ALL: [Language; 5] = [
+        Language::Rust,
+        Language::C,
+        Language::Python,
+        Language::Go,
+        Language::JavaScript,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::Rust => "rust",
+            Language::C => "c",
+            Language::Python => "python",
+            Language::Go => "go",
+            Language::JavaScript => "js",
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Language::ALL
+            .into_iter()
+            .find(|l| l.name() == s.to_ascii_lowercase())
+            .ok_or_else(|| format!("Unknown language {s:?}"))
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Opcje zapisu bufora jako kodu
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmitOptions {
+    /// Nazwa zmiennej (w Ruście zamieniana na wielkie litery)
+    pub name: String,
+    /// Liczba bajtów w linii
+    pub per_line: usize,
+}
+
+impl Default for EmitOptions {
+    fn default() -> Self {
+        EmitOptions {
+            name: "data".into(),
+            per_line: 12,
+        }
+    }
+}
+
+/// Zapisuje bufor jako tablicę w kodzie źródłowym
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::byte_literals::{emit, parse_bytes, EmitOptions, Language};
+/// let opcje = EmitOptions { name: "flaga".into(), per_line: 2 };
+/// let dane = [0x4a, 0x9c, 0x28];
+///
+/// assert_eq!(
+///     "const FLAGA: [u8; 3] = [\n    0x4a, 0x9c,\n    0x28,\n];\n",
+///     emit(&dane, Language::Rust, &opcje)
+/// );
+/// assert_eq!(
+///     "unsigned char flaga[3] = {\n    0x4a, 0x9c,\n    0x28\n};\n",
+///     emit(&dane, Language::C, &opcje)
+/// );
+/// assert_eq!("flaga = (\n    b'J\\x9c'\n    b'('\n)\n", emit(&dane, Language::Python, &opcje));
+/// assert_eq!(
+///     "var flaga = []byte{\n\t0x4a, 0x9c,\n\t0x28,\n}\n",
+///     emit(&dane, Language::Go, &opcje)
+/// );
+/// assert_eq!(
+///     "const flaga = new Uint8Array([\n  0x4a, 0x9c,\n  0x28,\n]);\n",
+///     emit(&dane, Language::JavaScript, &opcje)
+/// );
+///
+/// // Każdą postać da się wczytać z powrotem, także bez sekwencji `\x`
+/// for dane in [&dane[..], b"ab", b"a,b", b"{'x'} # [0x41]", b""] {
+///     for jezyk in Language::ALL {
+///         assert_eq!(Ok(dane.to_vec()), parse_bytes(&emit(dane, jezyk, &opcje)), "{jezyk}");
+///     }
+/// }
+/// ```
+pub fn emit(dane: &[u8], jezyk: Language, opcje: &EmitOptions) -> String {
+    let nazwa = &opcje.name;
+    let n = dane.len();
+    let (naglowek, wciecie, stopka, przecinek_na_koncu) = match jezyk {
+        Language::Rust => (
+            format!("const {}: [u8; {n}] = [", nazwa.to_uppercase()),
+            "    ",
+            "];",
+            true,
+        ),
+        Language::C => (
+            format!("unsigned char {nazwa}[{n}] = {{"),
+            "    ",
+            "};",
+            false,
+        ),
+        Language::Go => (format!("var {nazwa} = []byte{{"), "\t", "}", true),
+        Language::JavaScript => (
+            format!("const {nazwa} = new Uint8Array(["),
+            "  ",
+            "]);",
+            true,
+        ),
+        Language::Python if dane.is_empty() => return format!("{nazwa} = b''\n"),
+        Language::Python => (format!("{nazwa} = ("), "    ", ")", false),
+    };
+
+    let mut wynik = naglowek + "\n";
+    let linie: Vec<&[u8]> = dane.chunks(opcje.per_line.max(1)).collect();
+    for (i, linia) in linie.iter().enumerate() {
+        wynik += wciecie;
+        if jezyk == Language::Python {
+            wynik += &python_bytes(linia);
+        } else {
+            let ostatnia = i + 1 == linie.len();
+            let bajty: Vec<String> = linia.iter().map(|b| format!("0x{b:02x}")).collect();
+            wynik += &bajty.join(", ");
+            if !ostatnia || przecinek_na_koncu {
+                wynik += ",";
+            }
+        }
+        wynik += "\n";
+    }
+    wynik + stopka + "\n"
+}
+
+fn python_bytes(dane: &[u8]) -> String {
+    let mut wynik = String::from("b'");
+    for b in dane {
+        match b {
+            b'\\' => wynik += "\\\\",
+            b'\'' => wynik += "\\'",
+            0x20..=0x7E => wynik.push(*b as char),
+            _ => wynik += &format!("\\x{b:02x}"),
+        }
+    }
+    wynik + "'"
+}
@@ -0,0 +1,258 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Największa obsługiwana szerokość liczby w bajtach
+pub const MAX_WIDTH: usize = 16;
+
+/// Błędy opisu kolejności bajtów i konwersji
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ByteOrderError {
+    /// Szerokość spoza zakresu 1..=16
+    InvalidWidth(usize),
+    /// Litera spoza zakresu dla danej szerokości
+    InvalidLetter(char),
+    /// Litera występuje w opisie więcej niż raz
+    DuplicateLetter(char),
+    /// Za mało bajtów na wejściu
+    Truncated,
+    /// Wartość nie mieści się w podanej szerokości
+    Overflow,
+}
+
+impl fmt::Display for ByteOrderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ByteOrderError::InvalidWidth(w) => write!(f, "Invalid width {w}, expected 1-16 bytes"),
+            ByteOrderError::InvalidLetter(c) => write!(f, "Invalid byte letter {c:?}"),
+            ByteOrderError::DuplicateLetter(c) => write!(f, "Byte letter {c:?} used twice"),
+            ByteOrderError::Truncated => write!(f, "Not enough input bytes"),
+            ByteOrderError::Overflow => write!(f, "Value does not fit in the given width"),
+        }
+    }
+}
+
+impl std::error::Error for ByteOrderError {}
+
+/// Dowolna permutacja bajtów liczby o szerokości od 1 do 16 bajtów
+///
+/// Kolejność opisuje się literami: `A` to najstarszy bajt, `B` następny itd.
+/// Napis podaje, w jakiej kolejności bajty leżą w pamięci. Dla liczby
+/// 0x0A0B0C0D:
+///
+/// * `ABCD` - big endian: `0A 0B 0C 0D`
+/// * `DCBA` - little endian: `0D 0C 0B 0A`
+/// * `BADC` - PDP-11 ("middle endian"): `0B 0A 0D 0C`
+/// * `CDAB` - zamienione słowa 16-bitowe: `0C 0D 0A 0B`
+///
+/// Szerokość nie musi być potęgą dwójki, więc `CBA` to 24-bitowy little endian.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::byte_order::ByteOrder;
+/// let pdp: ByteOrder = "BADC".parse().unwrap();
+/// assert_eq!(Ok(0x0A0B_0C0D), pdp.decode_unsigned(&[0x0B, 0x0A, 0x0D, 0x0C]));
+/// assert_eq!(Ok(vec![0x0B, 0x0A, 0x0D, 0x0C]), pdp.encode_unsigned(0x0A0B_0C0D));
+///
+/// // 64 bity z zamienionymi 32-bitowymi połówkami
+/// let word_swap: ByteOrder = "EFGHABCD".parse().unwrap();
+/// assert_eq!(
+///     Ok(0x1122_3344_5566_7788),
+///     word_swap.decode_unsigned(&[0x55, 0x66, 0x77, 0x88, 0x11, 0x22, 0x33, 0x44])
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ByteOrder {
+    /// `kolejnosc[i]` to numer bajtu (0 = najstarszy) leżącego w pamięci na
+    /// pozycji `i`
+    kolejnosc: [u8; MAX_WIDTH],
+    szerokosc: usize,
+}
+
+impl ByteOrder {
+    /// Big endian o podanej szerokości
+    pub fn big_endian(szerokosc: usize) -> Result<Self, ByteOrderError> {
+        Self::sprawdz_szerokosc(szerokosc)?;
+        let mut kolejnosc = [0u8; MAX_WIDTH];
+        for (i, k) in kolejnosc.iter_mut().enumerate().take(szerokosc) {
+            *k = i as u8;
+        }
+        Ok(ByteOrder {
+            kolejnosc,
+            szerokosc,
+        })
+    }
+
+    /// Little endian o podanej szerokości
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::byte_order::ByteOrder;
+    /// // 48-bitowy adres MAC zapisany jako little endian
+    /// let le48 = ByteOrder::little_endian(6).unwrap();
+    /// assert_eq!("FEDCBA", le48.to_string());
+    /// assert_eq!(Ok(0x0011_2233_4455), le48.decode_unsigned(&[0x55, 0x44, 0x33, 0x22, 0x11, 0x00]));
+    /// ```
+    pub fn little_endian(szerokosc: usize) -> Result<Self, ByteOrderError> {
+        let mut wynik = Self::big_endian(szerokosc)?;
+        wynik.kolejnosc[..szerokosc].reverse();
+        Ok(wynik)
+    }
+
+    /// Szerokość w bajtach
+    pub fn width(&self) -> usize {
+        self.szerokosc
+    }
+
+    /// Permutacja odwrotna: przestawia bajty tak, jak ta kolejność cofa
+    /// przestawienie (`to_big_endian` jednej to `from_big_endian` drugiej)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::byte_order::ByteOrder;
+    /// let o: ByteOrder = "CADB".parse().unwrap();
+    /// assert_eq!("BDAC", o.inverse().to_string());
+    /// let bajty = [1, 2, 3, 4];
+    /// assert_eq!(o.to_big_endian(&bajty), o.inverse().from_big_endian(&bajty));
+    /// assert_eq!(o, o.inverse().inverse());
+    /// ```
+    pub fn inverse(&self) -> Self {
+        let mut wynik = *self;
+        for (pozycja, bajt) in self.kolejnosc[..self.szerokosc].iter().enumerate() {
+            wynik.kolejnosc[*bajt as usize] = pozycja as u8;
+        }
+        wynik
+    }
+
+    /// Przestawia bajty z tej kolejności na big endian
+    ///
+    /// returns: Result<Vec<u8>, ByteOrderError> dokładnie `width()` bajtów
+    pub fn to_big_endian(&self, bajty: &[u8]) -> Result<Vec<u8>, ByteOrderError> {
+        let bajty = bajty
+            .get(..self.szerokosc)
+            .ok_or(ByteOrderError::Truncated)?;
+        let mut wynik = vec![0u8; self.szerokosc];
+        for (bajt, numer) in bajty.iter().zip(&self.kolejnosc) {
+            wynik[*numer as usize] = *bajt;
+        }
+        Ok(wynik)
+    }
+
+    /// Przestawia bajty z big endian na tę kolejność
+    pub fn from_big_endian(&self, bajty: &[u8]) -> Result<Vec<u8>, ByteOrderError> {
+        let bajty = bajty
+            .get(..self.szerokosc)
+            .ok_or(ByteOrderError::Truncated)?;
+        Ok(self.kolejnosc[..self.szerokosc]
+            .iter()
+            .map(|numer| bajty[*numer as usize])
+            .collect())
+    }
+
+    /// Odczytuje liczbę bez znaku z pierwszych `width()` bajtów
+    pub fn decode_unsigned(&self, bajty: &[u8]) -> Result<u128, ByteOrderError> {
+        Ok(self
+            .to_big_endian(bajty)?
+            .iter()
+            .fold(0, |acc, b| acc << 8 | *b as u128))
+    }
+
+    /// Odczytuje liczbę ze znakiem (U2) z pierwszych `width()` bajtów
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::byte_order::ByteOrder;
+    /// let le24 = ByteOrder::little_endian(3).unwrap();
+    /// assert_eq!(Ok(-2), le24.decode_signed(&[0xFE, 0xFF, 0xFF]));
+    /// assert_eq!(Ok(0x7FFFFF), le24.decode_signed(&[0xFF, 0xFF, 0x7F]));
+    /// ```
+    pub fn decode_signed(&self, bajty: &[u8]) -> Result<i128, ByteOrderError> {
+        let przesuniecie = 128 - 8 * self.szerokosc as u32;
+        let surowe = self.decode_unsigned(bajty)?;
+        // Rozszerzenie znaku przez przesunięcie arytmetyczne
+        Ok(((surowe << przesuniecie) as i128) >> przesuniecie)
+    }
+
+    /// Zapisuje liczbę bez znaku na `width()` bajtach
+    pub fn encode_unsigned(&self, liczba: u128) -> Result<Vec<u8>, ByteOrderError> {
+        let be = liczba.to_be_bytes();
+        let (nadmiar, bajty) = be.split_at(MAX_WIDTH - self.szerokosc);
+        if nadmiar.iter().any(|b| *b != 0) {
+            return Err(ByteOrderError::Overflow);
+        }
+        self.from_big_endian(bajty)
+    }
+
+    /// Zapisuje liczbę ze znakiem (U2) na `width()` bajtach
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::byte_order::{ByteOrder, ByteOrderError};
+    /// let le24 = ByteOrder::little_endian(3).unwrap();
+    /// assert_eq!(Ok(vec![0xFE, 0xFF, 0xFF]), le24.encode_signed(-2));
+    /// assert_eq!(Err(ByteOrderError::Overflow), le24.encode_signed(0x800000));
+    /// assert_eq!(Err(ByteOrderError::Overflow), le24.encode_signed(-0x800001));
+    /// ```
+    pub fn encode_signed(&self, liczba: i128) -> Result<Vec<u8>, ByteOrderError> {
+        let przesuniecie = 128 - 8 * self.szerokosc as u32;
+        if (liczba << przesuniecie) >> przesuniecie != liczba {
+            return Err(ByteOrderError::Overflow);
+        }
+        let be = liczba.to_be_bytes();
+        self.from_big_endian(&be[MAX_WIDTH - self.szerokosc..])
+    }
+
+    fn sprawdz_szerokosc(szerokosc: usize) -> Result<(), ByteOrderError> {
+        if (1..=MAX_WIDTH).contains(&szerokosc) {
+            Ok(())
+        } else {
+            Err(ByteOrderError::InvalidWidth(szerokosc))
+        }
+    }
+}
+
+impl FromStr for ByteOrder {
+    type Err = ByteOrderError;
+
+    /// Parsuje opis typu `BADC` (wielkość liter bez znaczenia)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::byte_order::{ByteOrder, ByteOrderError};
+    /// assert!("badc".parse::<ByteOrder>().is_ok());
+    /// assert_eq!(Err(ByteOrderError::DuplicateLetter('A')), "ABA".parse::<ByteOrder>());
+    /// assert_eq!(Err(ByteOrderError::InvalidLetter('E')), "ABCE".parse::<ByteOrder>());
+    /// assert_eq!(Err(ByteOrderError::InvalidWidth(0)), "".parse::<ByteOrder>());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let litery: Vec<char> = s.chars().map(|c| c.to_ascii_uppercase()).collect();
+        let mut wynik = Self::big_endian(litery.len())?;
+        let mut uzyte = [false; MAX_WIDTH];
+        for (pozycja, litera) in litery.iter().enumerate() {
+            let numer = (*litera as u32).wrapping_sub('A' as u32) as usize;
+            if numer >= litery.len() {
+                return Err(ByteOrderError::InvalidLetter(*litera));
+            }
+            if uzyte[numer] {
+                return Err(ByteOrderError::DuplicateLetter(*litera));
+            }
+            uzyte[numer] = true;
+            wynik.kolejnosc[pozycja] = numer as u8;
+        }
+        Ok(wynik)
+    }
+}
+
+impl fmt::Display for ByteOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for numer in &self.kolejnosc[..self.szerokosc] {
+            write!(f, "{}", (b'A' + numer) as char)?;
+        }
+        Ok(())
+    }
+}
@@ -1,3 +1,39 @@
+use std::fmt;
+
+use num::rational::Ratio;
+use num::{BigInt, BigUint, Integer, One, PrimInt, Signed, Unsigned, Zero};
+
+use crate::masks::{ekstrakcja, wstawienie};
+
+/// Błędy dekodowania liczb o zmiennej długości (LEB128, VLQ)
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum VarintError {
+    /// Dane skończyły się przed bajtem z wyzerowanym najstarszym bitem
+    Truncated,
+    /// Wartość nie mieści się w typie docelowym
+    Overflow,
+    /// Liczba zakodowana na większej liczbie bajtów niż to konieczne
+    NonCanonical,
+    /// Za zakodowaną liczbą są jeszcze dane
+    TrailingData,
+    /// Bajty nie pasują do struktury kodowania (np. zły bajt kontynuacji)
+    Malformed,
+}
+
+impl fmt::Display for VarintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VarintError::Truncated => write!(f, "Truncated varint"),
+            VarintError::Overflow => write!(f, "Varint overflows target type"),
+            VarintError::NonCanonical => write!(f, "Non-canonical (over-long) varint"),
+            VarintError::TrailingData => write!(f, "Trailing data after varint"),
+            VarintError::Malformed => write!(f, "Malformed varint"),
+        }
+    }
+}
+
+impl std::error::Error for VarintError {}
+
 pub fn leb128(bajty: &[u8]) -> Option<u128> {
     // Pusta tablica
     if bajty.is_empty() {
@@ -17,11 +53,12 @@ pub fn leb128(bajty: &[u8]) -> Option<u128> {
     // użyj prawego złożenia (right fold, rfold), aby zagregować wektor do liczby.
     // Prawego, bo mamy little endian i musimy składać w odwrotnej kolejności.
     // Więcej szczegółów of foldach na https://pl.wikipedia.org/wiki/Fold
-    Some(
-        bajty
-            .iter()
-            .rfold(0_u128, |acc, v| (acc << 7) | (*v & 0x7F) as u128),
-    )
+    //
+    // Wersja `try_` przerywa składanie na pierwszym None - tu gdy przesunięcie
+    // o 7 bitów zgubiłoby zapalone najstarsze bity (przepełnienie u128).
+    bajty.iter().try_rfold(0_u128, |acc, v| {
+        (acc >> 121 == 0).then_some((acc << 7) | (*v & 0x7F) as u128)
+    })
 }
 
 pub fn vlq128(bajty: &[u8]) -> Option<u128> {
@@ -42,12 +79,10 @@ pub fn vlq128(bajty: &[u8]) -> Option<u128> {
 
     // użyj lewego złożenia (left fold, lfold, fold), aby zagregować wektor do
     // liczby. Lewego, bo mamy big endian i musimy składać naturalnej
-    // kolejności.
-    Some(
-        bajty
-            .iter()
-            .fold(0_u128, |acc, v| (acc << 7) | (*v & 0x7F) as u128),
-    )
+    // kolejności. Przepełnienie u128 przerywa składanie, jak w `leb128`.
+    bajty.iter().try_fold(0_u128, |acc, v| {
+        (acc >> 121 == 0).then_some((acc << 7) | (*v & 0x7F) as u128)
+    })
 }
 
 pub fn leb128v(data: &[u8]) -> Vec<u128> {
@@ -97,3 +132,765 @@ pub fn vlq128v(data: &[u8]) -> Vec<u128> {
         .filter_map(|bajty| vlq128(bajty))
         .collect::<Vec<_>>()
 }
+
+/// Dekoduje jedną liczbę LEB128 z początku bufora
+///
+/// W odróżnieniu od [`leb128`] nie wymaga, żeby bufor zawierał dokładnie jedną
+/// liczbę - dane za nią są ignorowane, a funkcja zwraca ile bajtów zużyła.
+/// Pozwala to przechodzić po formatach, w których liczby LEB128 są przeplatane
+/// innymi polami.
+///
+/// # Arguments
+///
+/// * `bajty`: dane wejściowe, zaczynające się od zakodowanej liczby
+///
+/// returns: Result<(N, usize), VarintError> zdekodowana wartość i liczba
+/// zużytych bajtów
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::conversions::{decode_leb128, VarintError};
+/// assert_eq!(Ok((8443_u32, 2)), decode_leb128(&[0xFB, 0x41, 0xE4]));
+/// assert_eq!(Ok((0_u8, 1)), decode_leb128(&[0x00]));
+/// // Brak bajtu kończącego
+/// assert_eq!(Err(VarintError::Truncated), decode_leb128::<u64>(&[0xE4, 0x95]));
+/// // 0x100 nie mieści się w u8
+/// assert_eq!(Err(VarintError::Overflow), decode_leb128::<u8>(&[0x80, 0x02]));
+/// // Zbędny zerowy bajt na końcu
+/// assert_eq!(Err(VarintError::NonCanonical), decode_leb128::<u32>(&[0x81, 0x00]));
+/// ```
+pub fn decode_leb128<N: PrimInt + Unsigned>(bajty: &[u8]) -> Result<(N, usize), VarintError> {
+    // Ile bitów ma typ docelowy
+    let rozmiar = N::zero().count_zeros() as usize;
+    let mut wynik = N::zero();
+
+    for (i, bajt) in bajty.iter().enumerate() {
+        let grupa = *bajt & 0x7F;
+        let przesuniecie = 7 * i;
+
+        if grupa != 0 {
+            // Sprawdź, czy żaden bit grupy nie wystaje poza typ docelowy
+            if przesuniecie >= rozmiar
+                || (rozmiar - przesuniecie < 7 && grupa >> (rozmiar - przesuniecie) != 0)
+            {
+                return Err(VarintError::Overflow);
+            }
+            // Unwrap, bo u8 mieści się w każdym typie całkowitym bez znaku
+            wynik = wynik | (N::from(grupa).unwrap() << przesuniecie);
+        }
+
+        if *bajt & 0x80 == 0 {
+            // Ostatnia grupa zerowa dokłada tylko zera - poprawne kodowanie jest
+            // krótsze. Wyjątkiem jest sama wartość 0.
+            if i > 0 && grupa == 0 {
+                return Err(VarintError::NonCanonical);
+            }
+            return Ok((wynik, i + 1));
+        }
+    }
+
+    Err(VarintError::Truncated)
+}
+
+/// Dekoduje jedną liczbę VLQ z początku bufora
+///
+/// Odpowiednik [`decode_leb128`] dla kodowania big endian.
+///
+/// # Arguments
+///
+/// * `bajty`: dane wejściowe, zaczynające się od zakodowanej liczby
+///
+/// returns: Result<(N, usize), VarintError> zdekodowana wartość i liczba
+/// zużytych bajtów
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::conversions::{decode_vlq128, VarintError};
+/// assert_eq!(Ok((0x3FFF_u16, 2)), decode_vlq128(&[0xFF, 0x7F, 0x81]));
+/// assert_eq!(Err(VarintError::Truncated), decode_vlq128::<u32>(&[0x81, 0x80]));
+/// assert_eq!(Err(VarintError::Overflow), decode_vlq128::<u8>(&[0x82, 0x00]));
+/// // Zbędny zerowy bajt na początku
+/// assert_eq!(Err(VarintError::NonCanonical), decode_vlq128::<u32>(&[0x80, 0x7F]));
+/// ```
+pub fn decode_vlq128<N: PrimInt + Unsigned>(bajty: &[u8]) -> Result<(N, usize), VarintError> {
+    // Ile bitów ma typ docelowy
+    let rozmiar = N::zero().count_zeros() as usize;
+    let mut wynik = N::zero();
+
+    // Pierwsza grupa zerowa z kontynuacją dokłada tylko zera z przodu
+    if bajty.len() > 1 && bajty[0] == 0x80 {
+        return Err(VarintError::NonCanonical);
+    }
+
+    for (i, bajt) in bajty.iter().enumerate() {
+        // Przesunięcie o 7 bitów w lewo nie może zgubić zapalonych bitów
+        if wynik >> (rozmiar - 7) != N::zero() {
+            return Err(VarintError::Overflow);
+        }
+        // Unwrap, bo u8 mieści się w każdym typie całkowitym bez znaku
+        wynik = (wynik << 7) | N::from(*bajt & 0x7F).unwrap();
+
+        if *bajt & 0x80 == 0 {
+            return Ok((wynik, i + 1));
+        }
+    }
+
+    Err(VarintError::Truncated)
+}
+
+/// Dekoduje bufor zawierający dokładnie jedną liczbę LEB128 do wybranego typu
+///
+/// # Arguments
+///
+/// * `bajty`: zakodowana liczba
+///
+/// returns: Result<N, VarintError> wartość albo błąd, m.in. gdy nie mieści się
+/// w typie `N`
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::conversions::{leb128_checked, VarintError};
+/// let u32_max = [0xFF, 0xFF, 0xFF, 0xFF, 0x0F];
+/// assert_eq!(Ok(u32::MAX), leb128_checked::<u32>(&u32_max));
+/// assert_eq!(Ok(u32::MAX as u64), leb128_checked::<u64>(&u32_max));
+/// // Jeden bit za dużo
+/// let u32_max_1 = [0x80, 0x80, 0x80, 0x80, 0x10];
+/// assert_eq!(Err(VarintError::Overflow), leb128_checked::<u32>(&u32_max_1));
+/// // 20 bajtów to więcej niż 128 bitów
+/// assert_eq!(
+///     Err(VarintError::Overflow),
+///     leb128_checked::<u128>(&[[0xFF; 19].as_slice(), &[0x01]].concat())
+/// );
+/// assert_eq!(Err(VarintError::TrailingData), leb128_checked::<u32>(&[0x01, 0x02]));
+/// ```
+pub fn leb128_checked<N: PrimInt + Unsigned>(bajty: &[u8]) -> Result<N, VarintError> {
+    let (wartosc, dlugosc) = decode_leb128(bajty)?;
+    if dlugosc != bajty.len() {
+        return Err(VarintError::TrailingData);
+    }
+    Ok(wartosc)
+}
+
+/// Dekoduje bufor zawierający dokładnie jedną liczbę VLQ do wybranego typu
+///
+/// # Arguments
+///
+/// * `bajty`: zakodowana liczba
+///
+/// returns: Result<N, VarintError> wartość albo błąd, m.in. gdy nie mieści się
+/// w typie `N`
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::conversions::{vlq128_checked, VarintError};
+/// assert_eq!(Ok(0x0FFF_FFFF_u32), vlq128_checked(&[0xFF, 0xFF, 0xFF, 0x7F]));
+/// assert_eq!(Err(VarintError::Overflow), vlq128_checked::<u16>(&[0xFF, 0xFF, 0x7F]));
+/// ```
+pub fn vlq128_checked<N: PrimInt + Unsigned>(bajty: &[u8]) -> Result<N, VarintError> {
+    let (wartosc, dlugosc) = decode_vlq128(bajty)?;
+    if dlugosc != bajty.len() {
+        return Err(VarintError::TrailingData);
+    }
+    Ok(wartosc)
+}
+
+/// Dekoduje jedną liczbę LEB128 dowolnej długości z początku bufora
+///
+/// Wynik to liczba o dowolnej precyzji, więc nie ma przepełnień - przydatne dla
+/// danych z fuzzera albo długich identyfikatorów.
+///
+/// # Arguments
+///
+/// * `bajty`: dane wejściowe, zaczynające się od zakodowanej liczby
+///
+/// returns: Result<(BigUint, usize), VarintError> zdekodowana wartość i
+/// liczba zużytych bajtów
+///
+/// # Examples
+///
+/// ```
+/// use num::BigUint;
+/// use sekurak_hex_gynvael::conversions::decode_leb128_big;
+/// // 2^140 = 20 grup po 7 bitów, ostatnia to 0b1
+/// let dane = [[0x80; 20].as_slice(), &[0x01]].concat();
+/// let (wartosc, dlugosc) = decode_leb128_big(&dane).unwrap();
+/// assert_eq!(BigUint::from(1_u8) << 140_usize, wartosc);
+/// assert_eq!(21, dlugosc);
+/// ```
+pub fn decode_leb128_big(bajty: &[u8]) -> Result<(BigUint, usize), VarintError> {
+    let grupy = grupy_varint(bajty)?;
+
+    // Ostatnia (najstarsza) grupa zerowa to zbędne wypełnienie
+    if grupy.len() > 1 && grupy.last() == Some(&0) {
+        return Err(VarintError::NonCanonical);
+    }
+
+    // Grupy to po prostu cyfry w systemie o podstawie 128, od najmłodszej.
+    // Unwrap, bo każda grupa jest mniejsza od 128.
+    let wartosc = BigUint::from_radix_le(&grupy, 128).unwrap();
+    Ok((wartosc, grupy.len()))
+}
+
+/// Dekoduje jedną liczbę VLQ dowolnej długości z początku bufora
+///
+/// Tak kodowane są m.in. kolejne składowe identyfikatorów OID w ASN.1.
+///
+/// # Arguments
+///
+/// * `bajty`: dane wejściowe, zaczynające się od zakodowanej liczby
+///
+/// returns: Result<(BigUint, usize), VarintError> zdekodowana wartość i
+/// liczba zużytych bajtów
+///
+/// # Examples
+///
+/// ```
+/// use num::BigUint;
+/// use sekurak_hex_gynvael::conversions::decode_vlq128_big;
+/// // Składowa 113549 z OID 1.2.840.113549 (RSA)
+/// let (wartosc, dlugosc) = decode_vlq128_big(&[0x86, 0xF7, 0x0D, 0x01]).unwrap();
+/// assert_eq!(BigUint::from(113549_u32), wartosc);
+/// assert_eq!(3, dlugosc);
+/// ```
+pub fn decode_vlq128_big(bajty: &[u8]) -> Result<(BigUint, usize), VarintError> {
+    let grupy = grupy_varint(bajty)?;
+
+    // Pierwsza (najstarsza) grupa zerowa to zbędne wypełnienie
+    if grupy.len() > 1 && grupy[0] == 0 {
+        return Err(VarintError::NonCanonical);
+    }
+
+    // Grupy to cyfry w systemie o podstawie 128, od najstarszej.
+    let wartosc = BigUint::from_radix_be(&grupy, 128).unwrap();
+    Ok((wartosc, grupy.len()))
+}
+
+/// Wycina 7-bitowe grupy pierwszej liczby z bufora (do bajtu z wyzerowanym
+/// najstarszym bitem włącznie)
+fn grupy_varint(bajty: &[u8]) -> Result<Vec<u8>, VarintError> {
+    let dlugosc = bajty
+        .iter()
+        .position(|b| *b & 0x80 == 0)
+        .ok_or(VarintError::Truncated)?;
+
+    Ok(bajty[..=dlugosc].iter().map(|b| *b & 0x7F).collect())
+}
+
+/// Koduje liczbę do postaci LEB128 (najkrótszej możliwej)
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::conversions::encode_leb128;
+/// assert_eq!(vec![0xFB, 0x41], encode_leb128(8443));
+/// assert_eq!(vec![0x00], encode_leb128(0));
+/// ```
+pub fn encode_leb128(mut liczba: u128) -> Vec<u8> {
+    let mut wynik = Vec::new();
+    loop {
+        let grupa = (liczba & 0x7F) as u8;
+        liczba >>= 7;
+        if liczba == 0 {
+            wynik.push(grupa);
+            return wynik;
+        }
+        wynik.push(grupa | 0x80);
+    }
+}
+
+/// Koduje liczbę do postaci VLQ (najkrótszej możliwej)
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::conversions::encode_vlq128;
+/// assert_eq!(vec![0xFF, 0x7F], encode_vlq128(0x3FFF));
+/// assert_eq!(vec![0x81, 0x80, 0x80, 0x00], encode_vlq128(0x0020_0000));
+/// ```
+pub fn encode_vlq128(liczba: u128) -> Vec<u8> {
+    // Te same grupy co w LEB128, tylko w odwrotnej kolejności i z bitem
+    // kontynuacji na wszystkich poza ostatnią
+    let mut wynik = encode_leb128(liczba);
+    wynik.reverse();
+    let ostatni = wynik.len() - 1;
+    for (i, bajt) in wynik.iter_mut().enumerate() {
+        if i == ostatni {
+            *bajt &= 0x7F;
+        } else {
+            *bajt |= 0x80;
+        }
+    }
+    wynik
+}
+
+/// Tryb zaokrąglania przy konwersji do liczb stałoprzecinkowych
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Rounding {
+    /// Do najbliższej, połówki od zera (jak `f64::round`)
+    Nearest,
+    /// Do najbliższej, połówki do parzystej ("bankierskie")
+    NearestEven,
+    /// Obcięcie części ułamkowej
+    TowardZero,
+    /// W dół (do minus nieskończoności)
+    Floor,
+    /// W górę (do plus nieskończoności)
+    Ceil,
+}
+
+/// Co zrobić z wartością spoza zakresu formatu stałoprzecinkowego
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum OnOverflow {
+    /// Przycięcie do najmniejszej/największej wartości
+    Saturate,
+    /// Obcięcie starszych bitów, jak przy rzutowaniu `as`
+    Wrap,
+    /// Zwrócenie błędu
+    Fail,
+}
+
+/// Błędy konwersji do liczb stałoprzecinkowych
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FixedPointError {
+    /// Wartość spoza zakresu formatu
+    Overflow,
+    /// Konwersja NaN
+    NotANumber,
+}
+
+impl fmt::Display for FixedPointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FixedPointError::Overflow => write!(f, "Value out of fixed-point range"),
+            FixedPointError::NotANumber => write!(f, "NaN has no fixed-point value"),
+        }
+    }
+}
+
+impl std::error::Error for FixedPointError {}
+
+/// Format stałoprzecinkowy Qm.n
+///
+/// `m` to liczba bitów części całkowitej (w formatach ze znakiem razem z bitem
+/// znaku), `n` to liczba bitów części ułamkowej. Całość ma m+n bitów, najwyżej
+/// 64. W tej notacji Q15 to `QFormat::q(1, 15)`, a Q16.16 to `QFormat::q(16, 16)`.
+///
+/// Surowe wartości są przekazywane jako `u64` z bitami na najmłodszych
+/// pozycjach (starsze bity są ignorowane).
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::conversions::{OnOverflow, QFormat, Rounding};
+/// let q15 = QFormat::q(1, 15);
+/// assert_eq!(-1.0, q15.to_f64(0x8000));
+/// assert_eq!(0.5, q15.to_f64(0x4000));
+/// assert_eq!(Ok(0x7FFF), q15.from_f64(1.0, Rounding::Nearest, OnOverflow::Saturate));
+///
+/// let q16_16 = QFormat::q(16, 16);
+/// assert_eq!(Ok(0x0001_8000), q16_16.from_f64(1.5, Rounding::Nearest, OnOverflow::Fail));
+/// assert_eq!(Ok(0xFFFE_8000), q16_16.from_f64(-1.5, Rounding::Nearest, OnOverflow::Fail));
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct QFormat {
+    pub integer_bits: u32,
+    pub fraction_bits: u32,
+    pub signed: bool,
+}
+
+impl QFormat {
+    /// Format ze znakiem
+    pub fn q(integer_bits: u32, fraction_bits: u32) -> Self {
+        assert!(integer_bits + fraction_bits <= 64, "Fixed-point format wider than 64 bits");
+        QFormat {
+            integer_bits,
+            fraction_bits,
+            signed: true,
+        }
+    }
+
+    /// Format bez znaku
+    pub fn uq(integer_bits: u32, fraction_bits: u32) -> Self {
+        QFormat {
+            signed: false,
+            ..Self::q(integer_bits, fraction_bits)
+        }
+    }
+
+    /// Całkowita liczba bitów
+    pub fn width(&self) -> u32 {
+        self.integer_bits + self.fraction_bits
+    }
+
+    /// Najmniejsza surowa wartość (jako liczba ze znakiem)
+    fn min(&self) -> i128 {
+        if self.signed && self.width() > 0 {
+            -(1 << (self.width() - 1))
+        } else {
+            0
+        }
+    }
+
+    /// Największa surowa wartość
+    fn max(&self) -> i128 {
+        if self.signed && self.width() > 0 {
+            (1 << (self.width() - 1)) - 1
+        } else {
+            (1 << self.width()) - 1
+        }
+    }
+
+    /// Surowe bity jako liczba całkowita (z rozszerzeniem znaku)
+    fn surowe_na_calkowita(&self, surowe: u64) -> i128 {
+        let szerokosc = self.width();
+        if szerokosc == 0 {
+            return 0;
+        }
+        let surowe = surowe as i128 & ((1 << szerokosc) - 1);
+        if self.signed && surowe >> (szerokosc - 1) == 1 {
+            surowe - (1 << szerokosc)
+        } else {
+            surowe
+        }
+    }
+
+    /// Liczba całkowita na surowe bity, z obsługą przepełnienia
+    fn calkowita_na_surowe(&self, liczba: i128, tryb: OnOverflow) -> Result<u64, FixedPointError> {
+        let liczba = if liczba < self.min() || liczba > self.max() {
+            match tryb {
+                OnOverflow::Saturate => liczba.clamp(self.min(), self.max()),
+                OnOverflow::Wrap => liczba,
+                OnOverflow::Fail => return Err(FixedPointError::Overflow),
+            }
+        } else {
+            liczba
+        };
+        // Obcięcie do szerokości formatu; format zerowej szerokości ma tylko 0
+        let maska = u128::MAX.checked_shr(128 - self.width()).unwrap_or(0);
+        Ok((liczba as u128 & maska) as u64)
+    }
+
+    /// Wartość surowych bitów jako `f64` (dokładna, jeśli format ma do 53 bitów)
+    pub fn to_f64(&self, surowe: u64) -> f64 {
+        self.surowe_na_calkowita(surowe) as f64 / 2f64.powi(self.fraction_bits as i32)
+    }
+
+    /// Zamienia `f64` na surowe bity formatu
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::conversions::{FixedPointError, OnOverflow, QFormat, Rounding};
+    /// let q4_4 = QFormat::q(4, 4);
+    /// // 2.53125 * 16 = 40.5
+    /// assert_eq!(Ok(41), q4_4.from_f64(2.53125, Rounding::Nearest, OnOverflow::Fail));
+    /// assert_eq!(Ok(40), q4_4.from_f64(2.53125, Rounding::NearestEven, OnOverflow::Fail));
+    /// assert_eq!(Ok(40), q4_4.from_f64(2.53125, Rounding::Floor, OnOverflow::Fail));
+    /// assert_eq!(Ok(0x80), q4_4.from_f64(-100.0, Rounding::Floor, OnOverflow::Saturate));
+    /// assert_eq!(Err(FixedPointError::Overflow), q4_4.from_f64(8.0, Rounding::Floor, OnOverflow::Fail));
+    /// // 8.0 to 128 = 0x80, czyli -8.0 po zawinięciu
+    /// assert_eq!(Ok(0x80), q4_4.from_f64(8.0, Rounding::Floor, OnOverflow::Wrap));
+    /// assert_eq!(Err(FixedPointError::NotANumber), q4_4.from_f64(f64::NAN, Rounding::Floor, OnOverflow::Saturate));
+    ///
+    /// // Format zerowej szerokości ma tylko wartość 0
+    /// assert_eq!(Ok(0), QFormat::uq(0, 0).from_f64(1.0, Rounding::Floor, OnOverflow::Saturate));
+    /// ```
+    pub fn from_f64(
+        &self,
+        liczba: f64,
+        zaokraglenie: Rounding,
+        tryb: OnOverflow,
+    ) -> Result<u64, FixedPointError> {
+        if liczba.is_nan() {
+            return Err(FixedPointError::NotANumber);
+        }
+        // Mnożenie przez potęgę dwójki jest dokładne
+        let przeskalowana = liczba * 2f64.powi(self.fraction_bits as i32);
+        let zaokraglona = match zaokraglenie {
+            Rounding::Nearest => przeskalowana.round(),
+            Rounding::NearestEven => przeskalowana.round_ties_even(),
+            Rounding::TowardZero => przeskalowana.trunc(),
+            Rounding::Floor => przeskalowana.floor(),
+            Rounding::Ceil => przeskalowana.ceil(),
+        };
+        // Rzutowanie `as` na i128 nasyca, więc nieskończoności też wypadają poza
+        // zakres formatu
+        self.calkowita_na_surowe(zaokraglona as i128, tryb)
+    }
+
+    /// Dokładna wartość surowych bitów jako ułamek zwykły
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num::rational::Ratio;
+    /// use sekurak_hex_gynvael::conversions::QFormat;
+    /// assert_eq!(Ratio::new(-3, 4), QFormat::q(1, 15).to_ratio(0xA000));
+    /// ```
+    pub fn to_ratio(&self, surowe: u64) -> Ratio<i128> {
+        Ratio::new(self.surowe_na_calkowita(surowe), 1 << self.fraction_bits)
+    }
+
+    /// Zamienia ułamek zwykły na surowe bity formatu
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use num::rational::Ratio;
+    /// use sekurak_hex_gynvael::conversions::{FixedPointError, OnOverflow, QFormat, Rounding};
+    /// let q1_15 = QFormat::q(1, 15);
+    /// let jedna_trzecia = Ratio::new(1, 3);
+    /// // 32768 / 3 = 10922.67
+    /// assert_eq!(Ok(10923), q1_15.from_ratio(jedna_trzecia, Rounding::Nearest, OnOverflow::Fail));
+    /// assert_eq!(Ok(10922), q1_15.from_ratio(jedna_trzecia, Rounding::TowardZero, OnOverflow::Fail));
+    /// // -32768 / 3 = -10922.67
+    /// let minus = -jedna_trzecia;
+    /// assert_eq!(Ok(0x10000 - 10923), q1_15.from_ratio(minus, Rounding::Floor, OnOverflow::Fail));
+    /// assert_eq!(Ok(0x10000 - 10922), q1_15.from_ratio(minus, Rounding::Ceil, OnOverflow::Fail));
+    ///
+    /// // Przepełnienie przy skalowaniu też podlega trybowi
+    /// let q4_60 = QFormat::q(4, 60);
+    /// let duza = Ratio::new(1 << 70, 1);
+    /// assert_eq!(Ok(0x7FFF_FFFF_FFFF_FFFF), q4_60.from_ratio(duza, Rounding::Floor, OnOverflow::Saturate));
+    /// assert_eq!(Ok(0x8000_0000_0000_0000), q4_60.from_ratio(-duza, Rounding::Floor, OnOverflow::Saturate));
+    /// assert_eq!(Ok(0), q4_60.from_ratio(duza, Rounding::Floor, OnOverflow::Wrap));
+    /// let prawie = Ratio::new((1 << 70) + 3, 1 << 60);
+    /// assert_eq!(Ok(3), q4_60.from_ratio(prawie, Rounding::Floor, OnOverflow::Wrap));
+    /// assert_eq!(Err(FixedPointError::Overflow), q4_60.from_ratio(duza, Rounding::Floor, OnOverflow::Fail));
+    /// ```
+    pub fn from_ratio(
+        &self,
+        liczba: Ratio<i128>,
+        zaokraglenie: Rounding,
+        tryb: OnOverflow,
+    ) -> Result<u64, FixedPointError> {
+        // Po przeskalowaniu wynik nie musi mieścić się w i128, więc liczymy na
+        // BigInt; Ratio trzyma mianownik zawsze dodatni
+        let licznik = BigInt::from(*liczba.numer()) << self.fraction_bits;
+        let mianownik = BigInt::from(*liczba.denom());
+
+        let (podloga, reszta) = licznik.div_mod_floor(&mianownik);
+        let polowa = (&reszta * 2u32).cmp(&mianownik);
+        let zaokraglona = match zaokraglenie {
+            _ if reszta.is_zero() => podloga,
+            Rounding::Floor => podloga,
+            Rounding::Ceil => podloga + 1u32,
+            Rounding::TowardZero if licznik.is_negative() => podloga + 1u32,
+            Rounding::TowardZero => podloga,
+            Rounding::Nearest | Rounding::NearestEven if polowa.is_gt() => podloga + 1u32,
+            Rounding::Nearest | Rounding::NearestEven if polowa.is_lt() => podloga,
+            // Dokładnie połowa
+            Rounding::Nearest if licznik.is_positive() => podloga + 1u32,
+            Rounding::Nearest => podloga,
+            Rounding::NearestEven if podloga.is_odd() => podloga + 1u32,
+            Rounding::NearestEven => podloga,
+        };
+
+        // Wartość spoza i128 na pewno nie mieści się w formacie
+        let zaokraglona = match i128::try_from(&zaokraglona) {
+            Ok(zaokraglona) => zaokraglona,
+            Err(_) => match tryb {
+                OnOverflow::Saturate if zaokraglona.is_negative() => i128::MIN,
+                OnOverflow::Saturate => i128::MAX,
+                // Młodsze 64 bity wystarczą do obcięcia do szerokości formatu
+                OnOverflow::Wrap => i128::try_from(zaokraglona.mod_floor(&(BigInt::one() << 64)))
+                    .expect("Value below 2^64"),
+                OnOverflow::Fail => return Err(FixedPointError::Overflow),
+            },
+        };
+        self.calkowita_na_surowe(zaokraglona, tryb)
+    }
+}
+
+/// Błędy konwersji BCD
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BcdError {
+    /// Półbajt o wartości większej niż 9 (albo niezerowa starsza połówka w
+    /// rozpakowanym BCD); `offset` to numer bajtu
+    InvalidNibble { offset: usize, nibble: u8 },
+    /// Liczba nie mieści się w podanej liczbie cyfr albo w `u128`
+    Overflow,
+}
+
+impl fmt::Display for BcdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BcdError::InvalidNibble { offset, nibble } => {
+                write!(f, "Invalid BCD nibble 0x{nibble:x} in byte {offset}")
+            }
+            BcdError::Overflow => write!(f, "Value does not fit"),
+        }
+    }
+}
+
+impl std::error::Error for BcdError {}
+
+/// Kolejność cyfr w bajcie spakowanego BCD
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum NibbleOrder {
+    /// Pierwsza cyfra w starszej połówce (typowe BCD, rejestry RTC)
+    HighFirst,
+    /// Pierwsza cyfra w młodszej połówce (np. ICCID na kartach SIM)
+    LowFirst,
+}
+
+/// Rozpakowuje spakowane BCD (dwie cyfry na bajt) do listy cyfr, od
+/// najstarszej
+///
+/// # Arguments
+///
+/// * `bajty`: dane BCD
+/// * `kolejnosc`: która połówka bajtu zawiera pierwszą cyfrę
+///
+/// returns: Result<Vec<u8>, BcdError> cyfry albo informacja o niepoprawnym
+/// półbajcie
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::conversions::{bcd_packed_digits, BcdError, NibbleOrder};
+/// assert_eq!(Ok(vec![1, 2, 3, 4]), bcd_packed_digits(&[0x12, 0x34], NibbleOrder::HighFirst));
+/// assert_eq!(Ok(vec![8, 9, 4, 8]), bcd_packed_digits(&[0x98, 0x84], NibbleOrder::LowFirst));
+/// assert_eq!(
+///     Err(BcdError::InvalidNibble { offset: 1, nibble: 0xA }),
+///     bcd_packed_digits(&[0x12, 0x3A], NibbleOrder::HighFirst)
+/// );
+/// ```
+pub fn bcd_packed_digits(bajty: &[u8], kolejnosc: NibbleOrder) -> Result<Vec<u8>, BcdError> {
+    let mut cyfry = Vec::with_capacity(bajty.len() * 2);
+    for (offset, bajt) in bajty.iter().enumerate() {
+        let starsza = ekstrakcja(*bajt, 4 ..= 7);
+        let mlodsza = ekstrakcja(*bajt, 0 ..= 3);
+        let para = match kolejnosc {
+            NibbleOrder::HighFirst => [starsza, mlodsza],
+            NibbleOrder::LowFirst => [mlodsza, starsza],
+        };
+        for nibble in para {
+            if nibble > 9 {
+                return Err(BcdError::InvalidNibble { offset, nibble });
+            }
+            cyfry.push(nibble);
+        }
+    }
+    Ok(cyfry)
+}
+
+/// Dekoduje spakowane BCD (big endian, pierwsza cyfra w starszej połówce) do
+/// liczby
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::conversions::{bcd_packed_decode, BcdError};
+/// // Rejestr sekund RTC: 0x59 to 59 sekund
+/// assert_eq!(Ok(59), bcd_packed_decode(&[0x59]));
+/// assert_eq!(Ok(20231003), bcd_packed_decode(&[0x20, 0x23, 0x10, 0x03]));
+/// assert_eq!(Err(BcdError::Overflow), bcd_packed_decode(&[0x99; 20]));
+/// ```
+pub fn bcd_packed_decode(bajty: &[u8]) -> Result<u128, BcdError> {
+    cyfry_na_liczbe(&bcd_packed_digits(bajty, NibbleOrder::HighFirst)?)
+}
+
+/// Koduje liczbę w spakowanym BCD na podanej liczbie cyfr. Nieparzysta
+/// liczba cyfr jest dopełniana zerem z przodu.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::conversions::{bcd_packed_encode, BcdError, NibbleOrder};
+/// assert_eq!(Ok(vec![0x01, 0x23]), bcd_packed_encode(123, 3, NibbleOrder::HighFirst));
+/// assert_eq!(Ok(vec![0x10, 0x32]), bcd_packed_encode(123, 4, NibbleOrder::LowFirst));
+/// assert_eq!(Err(BcdError::Overflow), bcd_packed_encode(1234, 3, NibbleOrder::HighFirst));
+/// ```
+pub fn bcd_packed_encode(
+    liczba: u128,
+    ile_cyfr: usize,
+    kolejnosc: NibbleOrder,
+) -> Result<Vec<u8>, BcdError> {
+    let mut cyfry = liczba_na_cyfry(liczba, ile_cyfr)?;
+    if cyfry.len() % 2 == 1 {
+        cyfry.insert(0, 0);
+    }
+    Ok(cyfry
+        .chunks(2)
+        .map(|para| {
+            let (starsza, mlodsza) = match kolejnosc {
+                NibbleOrder::HighFirst => (para[0], para[1]),
+                NibbleOrder::LowFirst => (para[1], para[0]),
+            };
+            wstawienie(mlodsza, starsza, 4 ..= 7)
+        })
+        .collect())
+}
+
+/// Dekoduje rozpakowane BCD (jedna cyfra na bajt, starsza połówka zerowa)
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::conversions::{bcd_unpacked_decode, BcdError};
+/// assert_eq!(Ok(1984), bcd_unpacked_decode(&[1, 9, 8, 4]));
+/// assert_eq!(
+///     Err(BcdError::InvalidNibble { offset: 2, nibble: 0x3 }),
+///     bcd_unpacked_decode(&[1, 9, 0x38, 4])
+/// );
+/// ```
+pub fn bcd_unpacked_decode(bajty: &[u8]) -> Result<u128, BcdError> {
+    let mut cyfry = Vec::with_capacity(bajty.len());
+    for (offset, bajt) in bajty.iter().enumerate() {
+        let starsza = ekstrakcja(*bajt, 4 ..= 7);
+        let mlodsza = ekstrakcja(*bajt, 0 ..= 3);
+        if starsza != 0 {
+            return Err(BcdError::InvalidNibble {
+                offset,
+                nibble: starsza,
+            });
+        }
+        if mlodsza > 9 {
+            return Err(BcdError::InvalidNibble {
+                offset,
+                nibble: mlodsza,
+            });
+        }
+        cyfry.push(mlodsza);
+    }
+    cyfry_na_liczbe(&cyfry)
+}
+
+/// Koduje liczbę w rozpakowanym BCD na podanej liczbie cyfr
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::conversions::bcd_unpacked_encode;
+/// assert_eq!(Ok(vec![0, 0, 4, 2]), bcd_unpacked_encode(42, 4));
+/// assert!(bcd_unpacked_encode(42, 1).is_err());
+/// ```
+pub fn bcd_unpacked_encode(liczba: u128, ile_cyfr: usize) -> Result<Vec<u8>, BcdError> {
+    liczba_na_cyfry(liczba, ile_cyfr)
+}
+
+fn cyfry_na_liczbe(cyfry: &[u8]) -> Result<u128, BcdError> {
+    cyfry.iter().try_fold(0_u128, |acc, c| {
+        acc.checked_mul(10)
+            .and_then(|acc| acc.checked_add(*c as u128))
+            .ok_or(BcdError::Overflow)
+    })
+}
+
+/// Cyfry dziesiętne liczby od najstarszej, dopełnione zerami do `ile_cyfr`
+fn liczba_na_cyfry(mut liczba: u128, ile_cyfr: usize) -> Result<Vec<u8>, BcdError> {
+    let mut cyfry = vec![0u8; ile_cyfr];
+    for cyfra in cyfry.iter_mut().rev() {
+        *cyfra = (liczba % 10) as u8;
+        liczba /= 10;
+    }
+    if liczba != 0 {
+        return Err(BcdError::Overflow);
+    }
+    Ok(cyfry)
+}
@@ -1,4 +1,8 @@
+use crate::byte_order::ByteOrder;
+use crate::conversions;
+use crate::floats::{FloatFormat, FloatParts};
 use eyre::Result;
+use num::{PrimInt, Unsigned};
 use std::path::Path;
 use std::{
     fs, io,
@@ -120,6 +124,120 @@ impl File {
         Ok(i64::from_le_bytes(buf))
     }
 
+    pub fn read_f32be(&mut self) -> Result<f32> {
+        let mut buf = [0u8; 4];
+        self.f.read_exact(buf.as_mut())?;
+        Ok(f32::from_be_bytes(buf))
+    }
+
+    pub fn read_f32le(&mut self) -> Result<f32> {
+        let mut buf = [0u8; 4];
+        self.f.read_exact(buf.as_mut())?;
+        Ok(f32::from_le_bytes(buf))
+    }
+
+    pub fn read_f64be(&mut self) -> Result<f64> {
+        let mut buf = [0u8; 8];
+        self.f.read_exact(buf.as_mut())?;
+        Ok(f64::from_be_bytes(buf))
+    }
+
+    pub fn read_f64le(&mut self) -> Result<f64> {
+        let mut buf = [0u8; 8];
+        self.f.read_exact(buf.as_mut())?;
+        Ok(f64::from_le_bytes(buf))
+    }
+
+    /// Czyta liczbę zmiennoprzecinkową w dowolnym formacie (big endian) i
+    /// rozkłada ją na pola
+    pub fn read_float_be(&mut self, format: FloatFormat) -> Result<FloatParts> {
+        let buf = self.read_as_vec(format.size())?;
+        // Unwrap, bo rozmiar bufora pochodzi z formatu
+        Ok(FloatParts::from_be_bytes(format, &buf).unwrap())
+    }
+
+    /// Czyta liczbę zmiennoprzecinkową w dowolnym formacie (little endian) i
+    /// rozkłada ją na pola
+    pub fn read_float_le(&mut self, format: FloatFormat) -> Result<FloatParts> {
+        let buf = self.read_as_vec(format.size())?;
+        // Unwrap, bo rozmiar bufora pochodzi z formatu
+        Ok(FloatParts::from_le_bytes(format, &buf).unwrap())
+    }
+
+    /// Czyta dokładnie jedną liczbę LEB128 (do bajtu z wyzerowanym najstarszym
+    /// bitem)
+    ///
+    /// Czyta najwyżej o jeden bajt więcej, niż potrzeba dla typu `N`; dłuższy
+    /// ciąg bajtów z kontynuacją to [`conversions::VarintError::Overflow`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::conversions::VarintError;
+    /// use sekurak_hex_gynvael::files::File;
+    /// let plik = std::env::temp_dir().join("read_leb128_doc.bin");
+    /// let mut dane = vec![0xE5, 0x8E, 0x26];
+    /// dane.extend([0x80; 1000]);
+    /// std::fs::write(&plik, &dane).unwrap();
+    ///
+    /// let mut f = File::open(&plik).unwrap();
+    /// assert_eq!(624485, f.read_leb128::<u32>().unwrap());
+    /// let e = f.read_leb128::<u32>().unwrap_err();
+    /// assert_eq!(Some(&VarintError::Overflow), e.downcast_ref());
+    /// // Przerwane po 6 bajtach (5 dla u32 i jeden zapasowy)
+    /// assert_eq!(3 + 6, f.tell().unwrap());
+    /// ```
+    pub fn read_leb128<N: PrimInt + Unsigned>(&mut self) -> Result<N> {
+        let bajty = self.read_varint_bytes(N::zero().count_zeros() as usize)?;
+        let (wartosc, _) = conversions::decode_leb128(&bajty)?;
+        Ok(wartosc)
+    }
+
+    /// Czyta dokładnie jedną liczbę VLQ (do bajtu z wyzerowanym najstarszym
+    /// bitem), z tym samym limitem długości co [`File::read_leb128`]
+    pub fn read_vlq128<N: PrimInt + Unsigned>(&mut self) -> Result<N> {
+        let bajty = self.read_varint_bytes(N::zero().count_zeros() as usize)?;
+        let (wartosc, _) = conversions::decode_vlq128(&bajty)?;
+        Ok(wartosc)
+    }
+
+    /// Czyta bajty jednej liczby o szerokości `bits`; najwyżej
+    /// `ceil(bits / 7) + 1`, jak dekodery z `conversions`
+    fn read_varint_bytes(&mut self, bits: usize) -> Result<Vec<u8>> {
+        let limit = bits.div_ceil(7) + 1;
+        let mut bajty = Vec::with_capacity(limit);
+        while bajty.len() < limit {
+            let bajt = self.read_u8()?;
+            bajty.push(bajt);
+            if bajt & 0x80 == 0 {
+                return Ok(bajty);
+            }
+        }
+        Err(conversions::VarintError::Overflow.into())
+    }
+
+    /// Czyta liczbę bez znaku o dowolnej szerokości i kolejności bajtów
+    pub fn read_uint(&mut self, kolejnosc: &ByteOrder) -> Result<u128> {
+        let buf = self.read_as_vec(kolejnosc.width())?;
+        Ok(kolejnosc.decode_unsigned(&buf)?)
+    }
+
+    /// Czyta liczbę ze znakiem (U2) o dowolnej szerokości i kolejności bajtów
+    pub fn read_int(&mut self, kolejnosc: &ByteOrder) -> Result<i128> {
+        let buf = self.read_as_vec(kolejnosc.width())?;
+        Ok(kolejnosc.decode_signed(&buf)?)
+    }
+
+    /// Zapisuje liczbę bez znaku o dowolnej szerokości i kolejności bajtów
+    pub fn write_uint(&mut self, kolejnosc: &ByteOrder, liczba: u128) -> Result<()> {
+        self.write_exact(&kolejnosc.encode_unsigned(liczba)?)
+    }
+
+    /// Zapisuje liczbę ze znakiem (U2) o dowolnej szerokości i kolejności bajtów
+    pub fn write_int(&mut self, kolejnosc: &ByteOrder, liczba: i128) -> Result<()> {
+        self.write_exact(&kolejnosc.encode_signed(liczba)?)
+    }
+
     pub fn read_to_end(&mut self) -> Result<Vec<u8>> {
         let mut buf = Vec::with_capacity(1500);
         self.f.read_to_end(&mut buf)?;
@@ -143,11 +261,22 @@ impl File {
         Ok(buf)
     }
 
+    /// Czyta do `size` bajtów; mniej tylko na końcu pliku
+    ///
+    /// Bufor rośnie razem z przeczytanymi danymi, więc `size` może pochodzić z
+    /// niezaufanego nagłówka.
+    pub fn read_at_most(&mut self, size: usize) -> Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        (&mut self.f).take(size as u64).read_to_end(&mut buf)?;
+        Ok(buf)
+    }
+
     pub fn write_exact(&mut self, data: &[u8]) -> Result<()> {
         self.f.write_all(data)?;
         Ok(())
     }
 }
+#[allow(non_snake_case)]
 pub mod LE {
     use super::File;
     use eyre::Result;
@@ -202,8 +331,21 @@ pub mod LE {
             self.read_i64le()
         }
     }
+
+    impl BinReader<f32> for File {
+        fn binread(&mut self) -> Result<f32> {
+            self.read_f32le()
+        }
+    }
+
+    impl BinReader<f64> for File {
+        fn binread(&mut self) -> Result<f64> {
+            self.read_f64le()
+        }
+    }
 }
 
+#[allow(non_snake_case)]
 pub mod BE {
     use super::File;
     use eyre::Result;
@@ -258,4 +400,16 @@ pub mod BE {
             self.read_i64be()
         }
     }
+
+    impl BinReader<f32> for File {
+        fn binread(&mut self) -> Result<f32> {
+            self.read_f32be()
+        }
+    }
+
+    impl BinReader<f64> for File {
+        fn binread(&mut self) -> Result<f64> {
+            self.read_f64be()
+        }
+    }
 }
@@ -0,0 +1,318 @@
+use std::fmt;
+
+use crate::masks::{ekstrakcja, wstawienie};
+
+/// Binarne formaty liczb zmiennoprzecinkowych
+///
+/// Wszystkie mają ten sam układ bitów: znak na najstarszym bicie, pod nim
+/// wykładnik, a na najmłodszych bitach mantysa. x87 ma w mantysie jawny bit
+/// części całkowitej (bit 63), pozostałe formaty mają go domyślnie.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FloatFormat {
+    /// IEEE 754 half precision
+    Binary16,
+    /// "Brain float" - obcięty `binary32`
+    BFloat16,
+    /// IEEE 754 single precision (`f32`)
+    Binary32,
+    /// IEEE 754 double precision (`f64`)
+    Binary64,
+    /// 80-bitowy format rozszerzony koprocesora x87 (`long double`)
+    X87Extended,
+}
+
+impl FloatFormat {
+    /// Rozmiar w bajtach
+    pub fn size(&self) -> usize {
+        (1 + self.exponent_bits() + self.mantissa_bits()) as usize / 8
+    }
+
+    /// Liczba bitów wykładnika
+    pub fn exponent_bits(&self) -> u32 {
+        match self {
+            FloatFormat::Binary16 => 5,
+            FloatFormat::BFloat16 | FloatFormat::Binary32 => 8,
+            FloatFormat::Binary64 => 11,
+            FloatFormat::X87Extended => 15,
+        }
+    }
+
+    /// Liczba bitów mantysy (dla x87 razem z jawnym bitem całkowitym)
+    pub fn mantissa_bits(&self) -> u32 {
+        match self {
+            FloatFormat::Binary16 => 10,
+            FloatFormat::BFloat16 => 7,
+            FloatFormat::Binary32 => 23,
+            FloatFormat::Binary64 => 52,
+            FloatFormat::X87Extended => 64,
+        }
+    }
+
+    /// Przesunięcie (bias) wykładnika
+    pub fn bias(&self) -> i32 {
+        (1 << (self.exponent_bits() - 1)) - 1
+    }
+
+    /// Czy bit części całkowitej jest zapisany jawnie
+    pub fn explicit_integer_bit(&self) -> bool {
+        *self == FloatFormat::X87Extended
+    }
+
+    fn max_exponent(&self) -> u32 {
+        (1 << self.exponent_bits()) - 1
+    }
+
+    /// Bity ułamka, czyli mantysy bez jawnego bitu całkowitego
+    fn fraction_bits(&self) -> u32 {
+        if self.explicit_integer_bit() {
+            self.mantissa_bits() - 1
+        } else {
+            self.mantissa_bits()
+        }
+    }
+}
+
+/// Klasa wartości zmiennoprzecinkowej
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FloatClass {
+    Zero,
+    Subnormal,
+    Normal,
+    Infinite,
+    /// NaN; `payload` to bity ułamka bez bitu "quiet"
+    NaN {
+        quiet: bool,
+        payload: u64,
+    },
+    /// Kombinacje x87 odrzucane przez procesory od 80387 (unnormal,
+    /// pseudo-NaN, pseudo-nieskończoność, pseudo-denormal)
+    Invalid,
+}
+
+/// Liczba zmiennoprzecinkowa rozłożona na pola
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::floats::{FloatClass, FloatFormat, FloatParts};
+/// // 1.0 w half precision to 0x3C00, zapisane little endian
+/// let jeden = FloatParts::from_le_bytes(FloatFormat::Binary16, &[0x00, 0x3C]).unwrap();
+/// assert!(!jeden.sign);
+/// assert_eq!(15, jeden.exponent);
+/// assert_eq!(0, jeden.mantissa);
+/// assert_eq!(FloatClass::Normal, jeden.class());
+/// assert_eq!(1.0, jeden.to_f64());
+///
+/// // Składanie z powrotem z pól
+/// let minus_dwa = FloatParts { sign: true, exponent: 16, ..jeden };
+/// assert_eq!(vec![0xC0, 0x00], minus_dwa.to_be_bytes());
+/// assert_eq!(-2.0, minus_dwa.to_f64());
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct FloatParts {
+    pub format: FloatFormat,
+    /// Bit znaku (true - ujemna)
+    pub sign: bool,
+    /// Surowy (przesunięty o bias) wykładnik
+    pub exponent: u32,
+    /// Surowe pole mantysy
+    pub mantissa: u64,
+}
+
+impl FloatParts {
+    /// Rozkłada surowe bity (wyrównane do najmłodszego bitu) na pola
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::floats::{FloatClass, FloatFormat, FloatParts};
+    /// let nan = FloatParts::from_bits(FloatFormat::Binary32, 0x7FC0_0001);
+    /// assert_eq!(FloatClass::NaN { quiet: true, payload: 1 }, nan.class());
+    /// let snan = FloatParts::from_bits(FloatFormat::Binary16, 0x7C01);
+    /// assert_eq!(FloatClass::NaN { quiet: false, payload: 1 }, snan.class());
+    /// let sub = FloatParts::from_bits(FloatFormat::Binary16, 0x0001);
+    /// assert_eq!(FloatClass::Subnormal, sub.class());
+    /// assert_eq!(2f64.powi(-24), sub.to_f64());
+    /// ```
+    pub fn from_bits(format: FloatFormat, bity: u128) -> Self {
+        let m = format.mantissa_bits() as usize;
+        let e = format.exponent_bits() as usize;
+        FloatParts {
+            format,
+            sign: ekstrakcja(bity, m + e..=m + e) == 1,
+            exponent: ekstrakcja(bity, m..=m + e - 1) as u32,
+            mantissa: ekstrakcja(bity, 0..=m - 1) as u64,
+        }
+    }
+
+    /// Składa pola z powrotem w surowe bity
+    pub fn to_bits(&self) -> u128 {
+        let m = self.format.mantissa_bits() as usize;
+        let e = self.format.exponent_bits() as usize;
+        let bity = wstawienie(0_u128, self.mantissa as u128, 0..=m - 1);
+        let bity = wstawienie(bity, self.exponent as u128, m..=m + e - 1);
+        wstawienie(bity, self.sign as u128, m + e..=m + e)
+    }
+
+    /// Rozkłada liczbę zapisaną little endian
+    ///
+    /// returns: None, gdy liczba bajtów nie zgadza się z formatem
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::floats::{FloatFormat, FloatParts};
+    /// // 1.0 jako x87: mantysa 0x8000_0000_0000_0000, wykładnik 0x3FFF
+    /// let dane = [0, 0, 0, 0, 0, 0, 0, 0x80, 0xFF, 0x3F];
+    /// let jeden = FloatParts::from_le_bytes(FloatFormat::X87Extended, &dane).unwrap();
+    /// assert_eq!(0x3FFF, jeden.exponent);
+    /// assert_eq!(1.0, jeden.to_f64());
+    /// assert_eq!(dane.to_vec(), jeden.to_le_bytes());
+    /// ```
+    pub fn from_le_bytes(format: FloatFormat, bajty: &[u8]) -> Option<Self> {
+        if bajty.len() != format.size() {
+            return None;
+        }
+        let bity = bajty.iter().rfold(0_u128, |acc, b| (acc << 8) | *b as u128);
+        Some(Self::from_bits(format, bity))
+    }
+
+    /// Rozkłada liczbę zapisaną big endian
+    pub fn from_be_bytes(format: FloatFormat, bajty: &[u8]) -> Option<Self> {
+        if bajty.len() != format.size() {
+            return None;
+        }
+        let bity = bajty.iter().fold(0_u128, |acc, b| (acc << 8) | *b as u128);
+        Some(Self::from_bits(format, bity))
+    }
+
+    /// Bajty liczby little endian
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        self.to_bits().to_le_bytes()[..self.format.size()].to_vec()
+    }
+
+    /// Bajty liczby big endian
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        let mut bajty = self.to_le_bytes();
+        bajty.reverse();
+        bajty
+    }
+
+    /// Rozkłada natywny `f32`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::floats::FloatParts;
+    /// let liczba = FloatParts::from_f32(-0.1);
+    /// assert_eq!(-0.1_f32 as f64, liczba.to_f64());
+    /// assert_eq!(-4, liczba.unbiased_exponent());
+    /// assert_eq!(f32::MIN_POSITIVE as f64, FloatParts::from_f32(f32::MIN_POSITIVE).to_f64());
+    /// ```
+    pub fn from_f32(liczba: f32) -> Self {
+        Self::from_bits(FloatFormat::Binary32, liczba.to_bits() as u128)
+    }
+
+    /// Rozkłada natywny `f64`
+    pub fn from_f64(liczba: f64) -> Self {
+        Self::from_bits(FloatFormat::Binary64, liczba.to_bits() as u128)
+    }
+
+    /// Klasyfikuje wartość
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::floats::{FloatClass, FloatFormat, FloatParts};
+    /// let bf16 = |b| FloatParts::from_bits(FloatFormat::BFloat16, b).class();
+    /// assert_eq!(FloatClass::Zero, bf16(0x8000));
+    /// assert_eq!(FloatClass::Infinite, bf16(0xFF80));
+    /// assert_eq!(FloatClass::Normal, bf16(0x3F80));
+    /// // x87 z wykładnikiem normalnym, ale bez bitu całkowitego (unnormal)
+    /// let unnormal = FloatParts::from_bits(FloatFormat::X87Extended, 0x3FFF_4000_0000_0000_0000);
+    /// assert_eq!(FloatClass::Invalid, unnormal.class());
+    /// ```
+    pub fn class(&self) -> FloatClass {
+        let ulamek_bity = self.format.fraction_bits();
+        let ulamek = self.mantissa & ((1 << ulamek_bity) - 1);
+        let bit_calkowity = self.format.explicit_integer_bit() && self.mantissa >> ulamek_bity == 1;
+
+        if self.exponent == 0 {
+            if bit_calkowity {
+                FloatClass::Invalid
+            } else if ulamek == 0 {
+                FloatClass::Zero
+            } else {
+                FloatClass::Subnormal
+            }
+        } else if self.format.explicit_integer_bit() && !bit_calkowity {
+            FloatClass::Invalid
+        } else if self.exponent == self.format.max_exponent() {
+            if ulamek == 0 {
+                FloatClass::Infinite
+            } else {
+                let bit_quiet = ulamek_bity - 1;
+                FloatClass::NaN {
+                    quiet: (ulamek >> bit_quiet) & 1 == 1,
+                    payload: ulamek & ((1 << bit_quiet) - 1),
+                }
+            }
+        } else {
+            FloatClass::Normal
+        }
+    }
+
+    /// Wykładnik bez przesunięcia (dla liczb subnormalnych taki, jak dla
+    /// najmniejszych normalnych)
+    pub fn unbiased_exponent(&self) -> i32 {
+        self.exponent.max(1) as i32 - self.format.bias()
+    }
+
+    /// Wartość jako `f64`. Dla formatów węższych od `binary64` jest dokładna,
+    /// x87 jest zaokrąglany (w tym do nieskończoności albo zera). NaN traci
+    /// payload.
+    pub fn to_f64(&self) -> f64 {
+        let znak = if self.sign { -1.0 } else { 1.0 };
+        let wartosc = match self.class() {
+            FloatClass::Zero => 0.0,
+            FloatClass::Infinite => f64::INFINITY,
+            FloatClass::NaN { .. } | FloatClass::Invalid => f64::NAN,
+            FloatClass::Normal | FloatClass::Subnormal => {
+                let mut mantysa = self.mantissa as f64;
+                // Domyślna jedynka przed przecinkiem
+                if !self.format.explicit_integer_bit() && self.exponent != 0 {
+                    mantysa += 2f64.powi(self.format.mantissa_bits() as i32);
+                }
+                skaluj(
+                    mantysa,
+                    self.unbiased_exponent() - self.format.fraction_bits() as i32,
+                )
+            }
+        };
+        znak * wartosc
+    }
+}
+
+/// Mnoży przez 2^wykladnik w dwóch krokach, żeby pośredni wynik nie wyszedł
+/// poza zakres `f64` (x87 ma dużo szerszy zakres wykładnika)
+fn skaluj(liczba: f64, wykladnik: i32) -> f64 {
+    let polowa = wykladnik / 2;
+    liczba * 2f64.powi(polowa) * 2f64.powi(wykladnik - polowa)
+}
+
+impl fmt::Display for FloatParts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cyfry_m = self.format.mantissa_bits().div_ceil(4) as usize;
+        write!(
+            f,
+            "{:?}: sign={} exponent=0x{:x} ({}) mantissa=0x{:0cyfry_m$x} {:?} = {:e}",
+            self.format,
+            if self.sign { '-' } else { '+' },
+            self.exponent,
+            self.unbiased_exponent(),
+            self.mantissa,
+            self.class(),
+            self.to_f64()
+        )
+    }
+}
@@ -0,0 +1,327 @@
+use std::fmt;
+
+use crate::byte_order::ByteOrder;
+use crate::conversions::decode_leb128;
+
+/// Sposób interpretacji fragmentu danych
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Layout {
+    /// Szereg liczb całkowitych o szerokości 2, 4 lub 8 bajtów
+    Int {
+        width: usize,
+        little_endian: bool,
+        signed: bool,
+    },
+    /// Szereg `f32` (szerokość 4) albo `f64` (szerokość 8)
+    Float { width: usize, little_endian: bool },
+    /// Strumień liczb LEB128
+    Leb128,
+}
+
+impl Layout {
+    /// Wszystkie sprawdzane interpretacje
+    pub fn all() -> Vec<Layout> {
+        let mut wynik = Vec::new();
+        for width in [2, 4, 8] {
+            for little_endian in [true, false] {
+                for signed in [false, true] {
+                    wynik.push(Layout::Int {
+                        width,
+                        little_endian,
+                        signed,
+                    });
+                }
+            }
+        }
+        for width in [4, 8] {
+            for little_endian in [true, false] {
+                wynik.push(Layout::Float {
+                    width,
+                    little_endian,
+                });
+            }
+        }
+        wynik.push(Layout::Leb128);
+        wynik
+    }
+
+    /// Dekoduje dane jako szereg wartości. Niepełny ogon jest pomijany.
+    ///
+    /// returns: Option<Vec<f64>> None, jeśli dane nie dają się tak odczytać
+    /// (np. urwany LEB128)
+    pub fn decode(&self, dane: &[u8]) -> Option<Vec<f64>> {
+        match *self {
+            Layout::Int {
+                width,
+                little_endian,
+                signed,
+            } => {
+                let kolejnosc = kolejnosc(width, little_endian);
+                Some(
+                    dane.chunks_exact(width)
+                        .map(|c| match signed {
+                            true => kolejnosc.decode_signed(c).unwrap() as f64,
+                            false => kolejnosc.decode_unsigned(c).unwrap() as f64,
+                        })
+                        .collect(),
+                )
+            }
+            Layout::Float {
+                width,
+                little_endian,
+            } => {
+                let kolejnosc = kolejnosc(width, little_endian);
+                Some(
+                    dane.chunks_exact(width)
+                        .map(|c| {
+                            let bity = kolejnosc.decode_unsigned(c).unwrap();
+                            match width {
+                                4 => f32::from_bits(bity as u32) as f64,
+                                _ => f64::from_bits(bity as u64),
+                            }
+                        })
+                        .collect(),
+                )
+            }
+            Layout::Leb128 => {
+                let mut wynik = Vec::new();
+                let mut reszta = dane;
+                while !reszta.is_empty() {
+                    let (wartosc, uzyte) = decode_leb128::<u64>(reszta).ok()?;
+                    wynik.push(wartosc as f64);
+                    reszta = &reszta[uzyte..];
+                }
+                Some(wynik)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let koncowka = |le: bool| if le { "LE" } else { "BE" };
+        match self {
+            Layout::Int {
+                width,
+                little_endian,
+                signed,
+            } => {
+                let znak = if *signed { 'i' } else { 'u' };
+                write!(f, "{znak}{} {}", width * 8, koncowka(*little_endian))
+            }
+            Layout::Float {
+                width,
+                little_endian,
+            } => write!(f, "f{} {}", width * 8, koncowka(*little_endian)),
+            Layout::Leb128 => write!(f, "LEB128"),
+        }
+    }
+}
+
+/// Oceniona interpretacja
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candidate {
+    pub layout: Layout,
+    /// Bezwzględna ocena wiarygodności w zakresie 0..=1
+    pub score: f64,
+    /// Udział w puli wszystkich kandydatów (suma po kandydatach daje 1)
+    pub confidence: f64,
+    /// Liczba odczytanych wartości
+    pub values: usize,
+}
+
+impl fmt::Display for Candidate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<8} confidence {:5.1}% (score {:.3}, {} values)",
+            self.layout.to_string(),
+            self.confidence * 100.0,
+            self.score,
+            self.values
+        )
+    }
+}
+
+/// "Temperatura" przy zamianie ocen na pewność - im mniejsza, tym bardziej
+/// zwycięzca odstaje od reszty
+const TEMPERATURA: f64 = 0.05;
+
+/// Ocenia wszystkie interpretacje fragmentu danych i zwraca je od
+/// najbardziej prawdopodobnej
+///
+/// Ocena bierze pod uwagę gładkość szeregu (małe różnice między kolejnymi
+/// wartościami), monotoniczność, wiarygodność zakresu (małe liczby całkowite,
+/// skończone floaty o rozsądnym wykładniku) i to, czy długość danych jest
+/// wielokrotnością szerokości. Ocena jest lekko obniżana dla interpretacji
+/// dających mało wartości, a te z mniej niż dwiema są pomijane.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::guess::{analyse, Layout};
+/// // Licznik u16 big endian
+/// let dane: Vec<u8> = (1000u16..1010).flat_map(|x| x.to_be_bytes()).collect();
+/// let wynik = analyse(&dane);
+/// assert_eq!(Layout::Int { width: 2, little_endian: false, signed: false }, wynik[0].layout);
+///
+/// // Ujemne próbki i16 little endian
+/// let dane: Vec<u8> = [-5i16, -3, 0, 4, 9, 3, -2].iter().flat_map(|x| x.to_le_bytes()).collect();
+/// assert_eq!(
+///     Layout::Int { width: 2, little_endian: true, signed: true },
+///     analyse(&dane)[0].layout
+/// );
+///
+/// // Pomiary f32 little endian
+/// let dane: Vec<u8> = [20.5f32, 20.7, 21.0, 20.9].iter().flat_map(|x| x.to_le_bytes()).collect();
+/// assert_eq!(Layout::Float { width: 4, little_endian: true }, analyse(&dane)[0].layout);
+///
+/// // Strumień LEB128 z wartościami wielobajtowymi
+/// let dane = [0xE5, 0x8E, 0x26, 0xE6, 0x8E, 0x26, 0xE7, 0x8E, 0x26];
+/// assert_eq!(Layout::Leb128, analyse(&dane)[0].layout);
+/// ```
+pub fn analyse(dane: &[u8]) -> Vec<Candidate> {
+    let mut wynik: Vec<Candidate> = Layout::all()
+        .into_iter()
+        .filter_map(|layout| {
+            let wartosci = layout.decode(dane)?;
+            if wartosci.len() < 2 {
+                return None;
+            }
+            Some(Candidate {
+                layout,
+                // Interpretacja dająca więcej wartości to mocniejszy dowód
+                score: ocena(&layout, &wartosci, dane) * (1.0 - 0.5 / wartosci.len() as f64),
+                confidence: 0.0,
+                values: wartosci.len(),
+            })
+        })
+        .collect();
+
+    // Softmax po ocenach
+    let najlepsza = wynik.iter().map(|c| c.score).fold(0.0, f64::max);
+    let wagi: Vec<f64> = wynik
+        .iter()
+        .map(|c| ((c.score - najlepsza) / TEMPERATURA).exp())
+        .collect();
+    let suma: f64 = wagi.iter().sum();
+    for (kandydat, waga) in wynik.iter_mut().zip(wagi) {
+        kandydat.confidence = waga / suma;
+    }
+
+    wynik.sort_by(|a, b| b.score.total_cmp(&a.score));
+    wynik
+}
+
+fn kolejnosc(szerokosc: usize, little_endian: bool) -> ByteOrder {
+    // Unwrap, bo szerokości pochodzą z `Layout::all`
+    if little_endian {
+        ByteOrder::little_endian(szerokosc).unwrap()
+    } else {
+        ByteOrder::big_endian(szerokosc).unwrap()
+    }
+}
+
+fn ocena(layout: &Layout, wartosci: &[f64], dane: &[u8]) -> f64 {
+    match *layout {
+        Layout::Int { width, signed, .. } => {
+            let bity = (width * 8) as f64;
+            let ocena = 0.35 * gladkosc(wartosci, bity)
+                + 0.35 * malosc(wartosci, bity)
+                + 0.2 * monotonicznosc(wartosci)
+                + 0.1 * wyrownanie(dane.len(), width);
+            // Bez ujemnych wartości wersja ze znakiem niczego nie wnosi
+            if signed && wartosci.iter().all(|v| *v >= 0.0) {
+                ocena * 0.97
+            } else {
+                ocena
+            }
+        }
+        Layout::Float { width, .. } => {
+            let skonczone: Vec<f64> = wartosci.iter().copied().filter(|v| v.is_finite()).collect();
+            let wiarygodne = wartosci
+                .iter()
+                .filter(|v| **v == 0.0 || (1e-9..=1e9).contains(&v.abs()))
+                .count() as f64
+                / wartosci.len() as f64;
+            0.5 * wiarygodne
+                + 0.3 * gladkosc_wzgledna(&skonczone)
+                + 0.1 * monotonicznosc(&skonczone)
+                + 0.1 * wyrownanie(dane.len(), width)
+        }
+        Layout::Leb128 => {
+            // Strumień samych jednobajtowych wartości to równie dobrze zwykłe
+            // u8, więc liczą się głównie wartości wielobajtowe
+            let wielobajtowe = dane
+                .windows(2)
+                .filter(|p| p[0] & 0x80 != 0 && p[1] & 0x80 == 0)
+                .count();
+            0.4 * wielobajtowe as f64 / wartosci.len() as f64
+                + 0.3 * gladkosc(wartosci, 32.0)
+                + 0.3 * monotonicznosc(wartosci)
+        }
+    }
+}
+
+/// 1 dla stałego szeregu, 0 dla skoków na całą szerokość typu (skala
+/// logarytmiczna)
+fn gladkosc(wartosci: &[f64], bity: f64) -> f64 {
+    let srednia = wartosci
+        .windows(2)
+        .map(|p| (1.0 + (p[1] - p[0]).abs()).log2())
+        .sum::<f64>()
+        / (wartosci.len() - 1) as f64;
+    (1.0 - srednia / bity).clamp(0.0, 1.0)
+}
+
+/// Gładkość dla floatów: różnice względem wielkości sąsiednich wartości
+fn gladkosc_wzgledna(wartosci: &[f64]) -> f64 {
+    if wartosci.len() < 2 {
+        return 0.0;
+    }
+    let srednia = wartosci
+        .windows(2)
+        .map(|p| {
+            let skala = p[0].abs() + p[1].abs();
+            if skala == 0.0 {
+                0.0
+            } else {
+                ((p[1] - p[0]).abs() / skala).min(1.0)
+            }
+        })
+        .sum::<f64>()
+        / (wartosci.len() - 1) as f64;
+    1.0 - srednia
+}
+
+/// 1 dla wartości bliskich zeru, 0 dla wartości na granicy typu
+fn malosc(wartosci: &[f64], bity: f64) -> f64 {
+    let srednia =
+        wartosci.iter().map(|v| (1.0 + v.abs()).log2()).sum::<f64>() / wartosci.len() as f64;
+    (1.0 - srednia / bity).clamp(0.0, 1.0)
+}
+
+/// Udział kroków zgodnych z dominującym kierunkiem (stałe kroki pasują do
+/// obu)
+fn monotonicznosc(wartosci: &[f64]) -> f64 {
+    if wartosci.len() < 2 {
+        return 0.0;
+    }
+    let (mut rosnace, mut malejace, mut stale) = (0, 0, 0);
+    for p in wartosci.windows(2) {
+        match p[1].total_cmp(&p[0]) {
+            std::cmp::Ordering::Greater => rosnace += 1,
+            std::cmp::Ordering::Less => malejace += 1,
+            std::cmp::Ordering::Equal => stale += 1,
+        }
+    }
+    (rosnace.max(malejace) + stale) as f64 / (wartosci.len() - 1) as f64
+}
+
+fn wyrownanie(dlugosc: usize, szerokosc: usize) -> f64 {
+    if dlugosc.is_multiple_of(szerokosc) {
+        1.0
+    } else {
+        0.5
+    }
+}
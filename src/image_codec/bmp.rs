@@ -1,16 +1,29 @@
 #![allow(non_snake_case)]
+#![allow(clippy::upper_case_acronyms)]
 
 use crate::files;
 use crate::files::LE::BinReader;
+use crate::masks::{ekstrakcja, wstawienie};
 use color_eyre::eyre::Result;
 use eyre::{eyre, WrapErr};
 use log::debug;
 use std::io::SeekFrom;
+use std::ops::RangeInclusive;
 
 // Wciągnięcie wszystkich struktur z modułu nadrzędnego
 use super::*;
 
-const BM_MAGIC: u16 = 0x4D42;
+pub(crate) const BM_MAGIC: u16 = 0x4D42;
+pub(crate) const BITMAP_FILE_HEADER_SIZE: u64 = 14;
+
+pub(crate) const BI_RGB: u32 = 0;
+pub(crate) const BI_RLE8: u32 = 1;
+pub(crate) const BI_RLE4: u32 = 2;
+pub(crate) const BI_BITFIELDS: u32 = 3;
+pub(crate) const BI_ALPHABITFIELDS: u32 = 6;
+
+/// Domyślne maski dla 16 bitów bez BI_BITFIELDS (X1R5G5B5)
+const RGB555_MASKS: [u32; 4] = [0x7C00, 0x03E0, 0x001F, 0];
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
@@ -31,27 +44,183 @@ impl Validate for BitmapFileHeader {
     }
 }
 
+/// Nagłówek OS/2 1.x (12 bajtów); paleta składa się z 3-bajtowych RGBTRIPLE
 #[repr(C)]
-#[derive(Copy, Clone, Debug)]
-struct BitmapInfoHeader {
-    biSize: u32,
-    biWidth: i32,
-    biHeight: i32,
-    biPlanes: u16,
-    biBitCount: u16,
-    biCompression: u32,
-    biSizeImage: u32,
-    biXPelsPerMeter: i32,
-    biYPelsPerMeter: i32,
-    biClrUsed: u32,
-    biClrImportant: u32,
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BitmapCoreHeader {
+    pub bcSize: u32,
+    pub bcWidth: u16,
+    pub bcHeight: u16,
+    pub bcPlanes: u16,
+    pub bcBitCount: u16,
 }
 
-impl Validate for BitmapInfoHeader {
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BitmapInfoHeader {
+    pub biSize: u32,
+    pub biWidth: i32,
+    pub biHeight: i32,
+    pub biPlanes: u16,
+    pub biBitCount: u16,
+    pub biCompression: u32,
+    pub biSizeImage: u32,
+    pub biXPelsPerMeter: i32,
+    pub biYPelsPerMeter: i32,
+    pub biClrUsed: u32,
+    pub biClrImportant: u32,
+}
+
+/// Punkty końcowe przestrzeni kalibrowanej (CIEXYZTRIPLE) - współrzędne X, Y, Z
+/// kolejno dla czerwieni, zieleni i błękitu w formacie stałoprzecinkowym 2.30
+pub type CieXyzTriple = [[i32; 3]; 3];
+
+/// Nagłówek BITMAPV4HEADER (108 bajtów)
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BitmapV4Header {
+    pub info: BitmapInfoHeader,
+    pub bV4RedMask: u32,
+    pub bV4GreenMask: u32,
+    pub bV4BlueMask: u32,
+    pub bV4AlphaMask: u32,
+    pub bV4CSType: u32,
+    pub bV4Endpoints: CieXyzTriple,
+    pub bV4GammaRed: u32,
+    pub bV4GammaGreen: u32,
+    pub bV4GammaBlue: u32,
+}
+
+/// Nagłówek BITMAPV5HEADER (124 bajty)
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BitmapV5Header {
+    pub v4: BitmapV4Header,
+    pub bV5Intent: u32,
+    /// Przesunięcie profilu ICC liczone od początku nagłówka informacyjnego
+    pub bV5ProfileData: u32,
+    pub bV5ProfileSize: u32,
+    pub bV5Reserved: u32,
+}
+
+/// Nagłówek informacyjny w jednej z obsługiwanych wersji, rozpoznanej po
+/// rozmiarze
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum InfoHeader {
+    Core(BitmapCoreHeader),
+    Info(BitmapInfoHeader),
+    V4(BitmapV4Header),
+    V5(BitmapV5Header),
+}
+
+impl InfoHeader {
+    /// Wspólna część nagłówków; dla OS/2 pola nieobecne w pliku są zerowe
+    pub fn info(&self) -> BitmapInfoHeader {
+        match self {
+            InfoHeader::Core(core) => BitmapInfoHeader {
+                biSize: core.bcSize,
+                biWidth: core.bcWidth as i32,
+                biHeight: core.bcHeight as i32,
+                biPlanes: core.bcPlanes,
+                biBitCount: core.bcBitCount,
+                biCompression: BI_RGB,
+                biSizeImage: 0,
+                biXPelsPerMeter: 0,
+                biYPelsPerMeter: 0,
+                biClrUsed: 0,
+                biClrImportant: 0,
+            },
+            InfoHeader::Info(info) => *info,
+            InfoHeader::V4(v4) => v4.info,
+            InfoHeader::V5(v5) => v5.v4.info,
+        }
+    }
+
+    fn v4(&self) -> Option<&BitmapV4Header> {
+        match self {
+            InfoHeader::V4(v4) => Some(v4),
+            InfoHeader::V5(v5) => Some(&v5.v4),
+            _ => None,
+        }
+    }
+
+    /// Maski kanałów R, G, B, A zapisane w nagłówku (tylko V4 i V5)
+    pub fn masks(&self) -> Option<[u32; 4]> {
+        self.v4().map(|v4| {
+            [
+                v4.bV4RedMask,
+                v4.bV4GreenMask,
+                v4.bV4BlueMask,
+                v4.bV4AlphaMask,
+            ]
+        })
+    }
+
+    /// Przestrzeń barw (tylko V4 i V5)
+    pub fn color_space(&self) -> Option<ColorSpace> {
+        self.v4().map(|v4| ColorSpace::from(v4.bV4CSType))
+    }
+
+    /// Rozmiar pojedynczego wpisu palety
+    pub fn palette_entry_size(&self) -> usize {
+        match self {
+            InfoHeader::Core(_) => 3,
+            _ => 4,
+        }
+    }
+}
+
+impl Validate for InfoHeader {
     fn validate(&self) -> Result<()> {
-        if self.biSize != 40 {
-            return Err(eyre!("Invalid BIH size"));
+        if let InfoHeader::Core(core) = self {
+            if ![1u16, 4, 8, 24].contains(&core.bcBitCount) {
+                return Err(eyre!("Invalid bit depth for OS/2 bitmap"));
+            }
         }
+        self.info().validate()
+    }
+}
+
+/// Przestrzeń barw z pola bV4CSType / bV5CSType
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ColorSpace {
+    /// LCS_CALIBRATED_RGB - barwy opisane punktami końcowymi i gammą
+    CalibratedRgb,
+    /// LCS_sRGB
+    Srgb,
+    /// LCS_WINDOWS_COLOR_SPACE - domyślna przestrzeń systemu
+    WindowsColorSpace,
+    /// PROFILE_LINKED - profil ICC w osobnym pliku, w pliku BMP jest jego nazwa
+    LinkedProfile,
+    /// PROFILE_EMBEDDED - profil ICC zapisany w pliku
+    EmbeddedProfile,
+    Unknown(u32),
+}
+
+impl From<u32> for ColorSpace {
+    fn from(cs_type: u32) -> Self {
+        match &cs_type.to_be_bytes() {
+            [0, 0, 0, 0] => ColorSpace::CalibratedRgb,
+            b"sRGB" => ColorSpace::Srgb,
+            b"Win " => ColorSpace::WindowsColorSpace,
+            b"LINK" => ColorSpace::LinkedProfile,
+            b"MBED" => ColorSpace::EmbeddedProfile,
+            _ => ColorSpace::Unknown(cs_type),
+        }
+    }
+}
+
+/// Metadane pliku BMP nieprzenoszone do [`Image`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BmpMetadata {
+    pub header: InfoHeader,
+    pub color_space: Option<ColorSpace>,
+    /// Profil ICC (dla [`ColorSpace::LinkedProfile`] - nazwa pliku profilu)
+    pub icc_profile: Option<Vec<u8>>,
+}
+
+impl Validate for BitmapInfoHeader {
+    fn validate(&self) -> Result<()> {
         if self.biWidth < 0 {
             return Err(eyre!("Negative image width"));
         }
@@ -63,7 +232,7 @@ impl Validate for BitmapInfoHeader {
             return Err(eyre!("Height overflow"));
         }
 
-        if ![1u16, 2, 4, 8, 16, 24].contains(&self.biBitCount) {
+        if ![1u16, 2, 4, 8, 16, 24, 32].contains(&self.biBitCount) {
             return Err(eyre!("Invalid bit depth"));
         }
 
@@ -75,62 +244,436 @@ impl Validate for BitmapInfoHeader {
             return Err(eyre!("Important > Used pallete"));
         }
 
-        if self.biCompression != 0 {
-            return Err(eyre!("Unsupported palette"));
+        match (self.biCompression, self.biBitCount) {
+            (BI_RGB, _) => {}
+            (BI_RLE8, 8) | (BI_RLE4, 4) if self.biHeight < 0 => {
+                return Err(eyre!("RLE bitmaps cannot be top-down"))
+            }
+            (BI_RLE8, 8) | (BI_RLE4, 4) => {}
+            (BI_RLE8 | BI_RLE4, _) => return Err(eyre!("RLE compression mismatches bit depth")),
+            (BI_BITFIELDS | BI_ALPHABITFIELDS, 16 | 32) => {}
+            (BI_BITFIELDS | BI_ALPHABITFIELDS, _) => {
+                return Err(eyre!("Bit fields require 16 or 32-bit pixels"))
+            }
+            _ => return Err(eyre!("Unsupported compression")),
         }
 
         Ok(())
     }
 }
 
+/// Kanały pikseli 16 i 32-bitowych opisane maskami (BI_BITFIELDS)
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::image_codec::bmp::BitFields;
+/// use sekurak_hex_gynvael::image_codec::{ColorRGB8, Pixel};
+/// // R5G6B5
+/// let pola = BitFields::new([0xF800, 0x07E0, 0x001F, 0]).unwrap();
+/// assert_eq!(Pixel::RGB8(ColorRGB8(255, 0, 0)), pola.decode(0xF800));
+/// assert_eq!(Pixel::RGB8(ColorRGB8(0, 130, 255)), pola.decode(0x041F));
+///
+/// // A2R10G10B10
+/// let pola = BitFields::new([0x3FF0_0000, 0x000F_FC00, 0x0000_03FF, 0xC000_0000]).unwrap();
+/// assert_eq!(Pixel::RGBA8(ColorRGB8(255, 0, 128), 170), pola.decode(0xBFF0_0201));
+///
+/// assert!(BitFields::new([0xF800, 0x0FE0, 0x001F, 0]).is_err()); // nakładające się
+/// assert!(BitFields::new([0xF800, 0, 0x001F, 0]).is_err()); // pusta
+/// assert!(BitFields::new([0xF0F0, 0x0F00, 0x000F, 0]).is_err()); // nieciągła
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BitFields {
+    pub red: RangeInclusive<usize>,
+    pub green: RangeInclusive<usize>,
+    pub blue: RangeInclusive<usize>,
+    pub alpha: Option<RangeInclusive<usize>>,
+}
+
+impl BitFields {
+    /// Tworzy opis kanałów z masek R, G, B, A (maska alfa może być zerowa)
+    pub fn new(masks: [u32; 4]) -> Result<Self> {
+        for (i, a) in masks.iter().enumerate() {
+            for b in &masks[i + 1..] {
+                if a & b != 0 {
+                    return Err(eyre!("Overlapping channel masks {a:#x} and {b:#x}"));
+                }
+            }
+        }
+        let [red, green, blue, alpha] = masks;
+        Ok(BitFields {
+            red: zakres_maski(red)?.ok_or(eyre!("Empty red mask"))?,
+            green: zakres_maski(green)?.ok_or(eyre!("Empty green mask"))?,
+            blue: zakres_maski(blue)?.ok_or(eyre!("Empty blue mask"))?,
+            alpha: zakres_maski(alpha)?,
+        })
+    }
+
+    /// Sprawdza, czy maski mieszczą się w pikselu o podanej liczbie bitów
+    pub fn fits(&self, bits: u16) -> bool {
+        [&self.red, &self.green, &self.blue]
+            .into_iter()
+            .chain(&self.alpha)
+            .all(|zakres| *zakres.end() < bits as usize)
+    }
+
+    /// Dekoduje piksel, skalując kanały do 8 bitów
+    pub fn decode(&self, piksel: u32) -> Pixel {
+        let kanal = |zakres: &RangeInclusive<usize>| {
+            let max = (1u64 << (zakres.end() - zakres.start() + 1)) - 1;
+            let wartosc = ekstrakcja(piksel, zakres.clone()) as u64;
+            ((wartosc * 255 + max / 2) / max) as u8
+        };
+        let kolor = ColorRGB8(kanal(&self.red), kanal(&self.green), kanal(&self.blue));
+        match &self.alpha {
+            Some(alfa) => Pixel::RGBA8(kolor, kanal(alfa)),
+            None => Pixel::RGB8(kolor),
+        }
+    }
+}
+
+/// Zakres bitów ciągłej maski; `None` dla maski zerowej
+fn zakres_maski(maska: u32) -> Result<Option<RangeInclusive<usize>>> {
+    if maska == 0 {
+        return Ok(None);
+    }
+    let najmlodszy = maska.trailing_zeros() as usize;
+    let najstarszy = 31 - maska.leading_zeros() as usize;
+    if maska.count_ones() as usize != najstarszy - najmlodszy + 1 {
+        return Err(eyre!("Non-contiguous channel mask {maska:#x}"));
+    }
+    Ok(Some(najmlodszy..=najstarszy))
+}
+
+/// Opcje odczytu BMP
+#[derive(Clone, Debug, Default)]
+pub struct ReadOptions {
+    /// Indeks palety dla pikseli pominiętych w RLE (skoki, wcześniejsze końce
+    /// linii i obrazu)
+    pub rle_fill: u8,
+    /// Rozwinięcie palety: obrazy z paletą (1, 2, 4 i 8-bitowe, także RLE)
+    /// zwracane są jako kolory RGB8 bez palety. Domyślnie wszystkie zwracane są
+    /// jako indeksy ([`Pixel::INDEX8`]) razem z paletą.
+    pub expand_palette: bool,
+}
+
 struct BMP {
     file: files::File,
 }
 
+/// Czyta plik BMP (nagłówek OS/2, BITMAPINFOHEADER, V4 lub V5; bez kompresji,
+/// RLE lub z maskami kanałów) z domyślnymi opcjami
+///
+/// Linie w zwróconym obrazie są zawsze od górnej, niezależnie od tego, czy
+/// plik zapisano od dołu (dodatnia wysokość) czy od góry (ujemna).
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::image_codec::bmp::read_bmp;
+/// use sekurak_hex_gynvael::image_codec::{ColorRGB8, Pixel};
+/// let rgb = |r, g, b| Pixel::RGB8(ColorRGB8(r, g, b));
+/// let oczekiwane = vec![
+///     vec![rgb(255, 0, 0), rgb(0, 255, 0), rgb(0, 0, 255)],
+///     vec![rgb(255, 255, 255), rgb(0, 0, 0), rgb(0x12, 0x34, 0x56)],
+/// ];
+///
+/// let img = read_bmp("data/bmp/rgb24.bmp").unwrap();
+/// assert_eq!((3, 2), (img.width(), img.height()));
+/// assert_eq!(oczekiwane, img.to_frame());
+/// assert_eq!(oczekiwane, read_bmp("data/bmp/rgb24_topdown.bmp").unwrap().to_frame());
+/// assert_eq!(oczekiwane, read_bmp("data/bmp/bgrx32.bmp").unwrap().to_frame());
+/// // Inne wersje nagłówka: OS/2 z paletą RGBTRIPLE, V4 z maskami, V5 z profilem ICC
+/// let core = read_bmp("data/bmp/core12.bmp").unwrap().expand_palette().unwrap();
+/// assert_eq!(oczekiwane, core.to_frame());
+/// assert_eq!(oczekiwane, read_bmp("data/bmp/v4_bitfields.bmp").unwrap().to_frame());
+/// assert_eq!(oczekiwane, read_bmp("data/bmp/v5_icc.bmp").unwrap().to_frame());
+///
+/// let img = read_bmp("data/bmp/bgra32.bmp").unwrap();
+/// assert_eq!(Pixel::RGBA8(ColorRGB8(255, 0, 0), 255), img.pixels.get_pixel(0, 0));
+/// assert_eq!(Pixel::RGBA8(ColorRGB8(0, 0, 255), 0), img.pixels.get_pixel(2, 0));
+/// assert_eq!(Pixel::RGBA8(ColorRGB8(0x12, 0x34, 0x56), 32), img.pixels.get_pixel(2, 1));
+/// ```
+///
+/// Obrazy z paletą (1, 2, 4 i 8-bitowe, także RLE) zwracane są jako indeksy
+/// razem z paletą, chyba że ustawiono [`ReadOptions::expand_palette`]:
+///
+/// ```
+/// use sekurak_hex_gynvael::image_codec::bmp::read_bmp;
+/// use sekurak_hex_gynvael::image_codec::{ColorRGB8, PixelFormat};
+/// let indeksy = |img: &sekurak_hex_gynvael::image_codec::Image| {
+///     assert_eq!(PixelFormat::INDEX8, img.pixels.format());
+///     img.pixels.rows().map(|l| l.to_vec()).collect::<Vec<_>>()
+/// };
+///
+/// let img = read_bmp("data/bmp/index1.bmp").unwrap();
+/// assert_eq!((5, 3), (img.width(), img.height()));
+/// assert_eq!(vec![vec![1, 0, 1, 0, 1], vec![0, 1, 1, 0, 0], vec![1; 5]], indeksy(&img));
+/// assert_eq!(Some(vec![ColorRGB8(0, 0, 0), ColorRGB8(255, 255, 255)]), img.palette);
+///
+/// let img = read_bmp("data/bmp/index2.bmp").unwrap();
+/// assert_eq!(vec![vec![0, 1, 2, 3, 3, 2, 1], vec![3, 0, 0, 1, 2, 0, 3]], indeksy(&img));
+/// assert_eq!(4, img.palette.unwrap().len());
+///
+/// let img = read_bmp("data/bmp/index4.bmp").unwrap();
+/// assert_eq!(vec![vec![0, 15, 7], vec![8, 1, 14], vec![2, 13, 0]], indeksy(&img));
+/// assert_eq!(ColorRGB8(255, 0, 15), img.palette.unwrap()[15]);
+/// ```
+///
+/// Piksele 16-bitowe i opisane maskami kanałów skalowane są do 8 bitów:
+///
+/// ```
+/// use sekurak_hex_gynvael::image_codec::bmp::read_bmp;
+/// use sekurak_hex_gynvael::image_codec::{ColorRGB8, Pixel};
+/// let rgb = |r, g, b| Pixel::RGB8(ColorRGB8(r, g, b));
+///
+/// let img = read_bmp("data/bmp/rgb555.bmp").unwrap();
+/// assert_eq!(vec![rgb(255, 0, 0), rgb(0, 255, 0), rgb(0, 0, 255)], img.to_frame()[0]);
+/// assert_eq!(rgb(16, 49, 82), img.pixels.get_pixel(2, 1));
+///
+/// let img = read_bmp("data/bmp/rgb565.bmp").unwrap();
+/// assert_eq!(vec![rgb(255, 255, 255), rgb(0, 0, 0), rgb(16, 49, 82)], img.to_frame()[1]);
+///
+/// let img = read_bmp("data/bmp/rgba32_bitfields.bmp").unwrap();
+/// assert_eq!(Pixel::RGBA8(ColorRGB8(0, 255, 0), 128), img.pixels.get_pixel(1, 0));
+/// assert_eq!(Pixel::RGBA8(ColorRGB8(0x12, 0x34, 0x56), 32), img.pixels.get_pixel(2, 1));
+/// ```
 pub fn read_bmp(in_file_name: &str) -> Result<Image> {
-    let mut bmp = BMP {
-        file: files::File::open(in_file_name)
-            .wrap_err_with(|| format!("Failed to open {in_file_name:?}"))?,
-    };
-
-    let bfh = bmp.read_bfh()?;
-    bfh.validate()?;
-    debug!("BFH={bfh:?}");
+    read_bmp_with(in_file_name, &ReadOptions::default())
+}
 
-    let bih = bmp.read_bih()?;
-    bih.validate()?;
-    debug!("BIH={bih:?}");
+/// Czyta plik BMP z podanymi opcjami
+///
+/// Obrazy z paletą domyślnie zwracane są jako indeksy razem z paletą,
+/// niezależnie od głębi i kompresji (zob. [`ReadOptions`]).
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::image_codec::bmp::{read_bmp, read_bmp_with, ReadOptions};
+/// use sekurak_hex_gynvael::image_codec::{ColorRGB8, Pixel, PixelFormat};
+/// let opcje = ReadOptions { expand_palette: true, ..Default::default() };
+/// let img = read_bmp_with("data/bmp/rle8.bmp", &opcje).unwrap();
+/// let [k, r, g, b] = [(0, 0, 0), (255, 0, 0), (0, 255, 0), (0, 0, 255)]
+///     .map(|(r, g, b)| Pixel::RGB8(ColorRGB8(r, g, b)));
+/// assert_eq!((7, 3), (img.width(), img.height()));
+/// assert_eq!(None, img.palette);
+/// assert_eq!(vec![r, k, k, k, k, k, k], img.to_frame()[0]);
+/// assert_eq!(vec![b, b, k, k, k, g, g], img.to_frame()[1]);
+/// assert_eq!(vec![r, r, r, r, g, b, g], img.to_frame()[2]);
+///
+/// // Pominięte piksele w innym kolorze
+/// let opcje = ReadOptions { rle_fill: 3, expand_palette: true };
+/// let img = read_bmp_with("data/bmp/rle8.bmp", &opcje).unwrap();
+/// assert_eq!(vec![b, b, b, b, b, g, g], img.to_frame()[1]);
+///
+/// // Zerowy biSizeImage: dane RLE do końca pliku
+/// let mut dane = std::fs::read("data/bmp/rle8.bmp").unwrap();
+/// dane[34..38].fill(0);
+/// let plik = std::env::temp_dir().join("read_bmp_with_rle8_size0.bmp");
+/// std::fs::write(&plik, dane).unwrap();
+/// let bez_rozmiaru = read_bmp_with(plik.to_str().unwrap(), &ReadOptions::default()).unwrap();
+/// assert_eq!(read_bmp("data/bmp/rle8.bmp").unwrap(), bez_rozmiaru);
+///
+/// // biSizeImage większy niż plik
+/// let mut dane = std::fs::read("data/bmp/rle8.bmp").unwrap();
+/// dane[34..38].copy_from_slice(&0xFFFF_FFF0u32.to_le_bytes());
+/// std::fs::write(&plik, &dane).unwrap();
+/// let e = read_bmp(plik.to_str().unwrap()).unwrap_err();
+/// assert!(e.to_string().starts_with("RLE data truncated"), "{e}");
+///
+/// // Domyślnie indeksy zamiast kolorów, dla każdej głębi
+/// let opcje = ReadOptions::default();
+/// let img = read_bmp_with("data/bmp/rle8.bmp", &opcje).unwrap();
+/// assert_eq!(vec![1, 0, 0, 0, 0, 0, 0].into_iter().map(Pixel::INDEX8).collect::<Vec<_>>(), img.to_frame()[0]);
+/// assert_eq!(Some(ColorRGB8(0, 0, 255)), img.palette.map(|p| p[3]));
+///
+/// let img = read_bmp_with("data/bmp/rle4.bmp", &opcje).unwrap();
+/// let indeksy = [[3, 4, 5, 6, 7], [1, 2, 1, 2, 1]].map(|l| l.map(Pixel::INDEX8).to_vec());
+/// assert_eq!(indeksy.to_vec(), img.to_frame());
+///
+/// let rozwin = ReadOptions { expand_palette: true, ..Default::default() };
+/// for zrodlo in ["sing_scape", "bmp/index1", "bmp/index2", "bmp/index4", "bmp/rle4"] {
+///     let plik = format!("data/{zrodlo}.bmp");
+///     let img = read_bmp_with(&plik, &opcje).unwrap();
+///     assert_eq!(PixelFormat::INDEX8, img.pixels.format(), "{zrodlo}");
+///     let rozwiniety = read_bmp_with(&plik, &rozwin).unwrap();
+///     assert_eq!(PixelFormat::RGB8, rozwiniety.pixels.format(), "{zrodlo}");
+///     assert_eq!(img.expand_palette().unwrap(), rozwiniety, "{zrodlo}");
+/// }
+///
+/// let e = read_bmp_with("data/bmp/rle8_overrun.bmp", &opcje).unwrap_err();
+/// assert_eq!("RLE run of 6 pixels at offset 0 overruns row 0 (x=0, width 4)", e.to_string());
+/// ```
+pub fn read_bmp_with(in_file_name: &str, options: &ReadOptions) -> Result<Image> {
+    let (mut bmp, bfh, header) = BMP::open(in_file_name)?;
+    let bih = header.info();
 
-    let pal = if bih.biBitCount == 8 {
+    let pal = if bih.biBitCount <= 8 {
+        let max = 1 << bih.biBitCount;
         let colors = match bih.biClrUsed {
-            0 => 256,
-            n if n <= 256 => n,
+            0 => max,
+            n if n <= max => n,
             _ => return Err(eyre!("Invalid palette size")),
         };
 
-        Some(bmp.read_palette(colors)?)
+        Some(bmp.read_palette(colors, header.palette_entry_size())?)
     } else {
         None
     };
     //debug!("PAL={pal:?}");
 
-    let pixels = if let Some(pal) = pal {
-        bmp.read_pixels_pal(bih.biWidth, bih.biHeight, &pal, bfh.bfOffBits as u64)?
-    } else {
-        unimplemented!()
+    let (pixels, palette) = match (bih.biBitCount, pal) {
+        (bits, Some(pal)) if [BI_RLE8, BI_RLE4].contains(&bih.biCompression) => {
+            let pixels = bmp.read_pixels_rle(
+                bih.biWidth,
+                bih.biHeight,
+                bits,
+                &pal,
+                bfh.bfOffBits as u64,
+                bih.biSizeImage,
+                options.rle_fill,
+                options.expand_palette,
+            )?;
+            (pixels, (!options.expand_palette).then_some(pal))
+        }
+        (8, Some(pal)) => {
+            let rozwin = options.expand_palette;
+            let pos = bfh.bfOffBits as u64;
+            let pixels = bmp.read_pixels_pal(bih.biWidth, bih.biHeight, &pal, pos, rozwin)?;
+            (pixels, (!rozwin).then_some(pal))
+        }
+        (bits @ (1 | 2 | 4), Some(pal)) => {
+            let rozwin = options.expand_palette;
+            let pos = bfh.bfOffBits as u64;
+            let pixels = bmp.read_pixels_idx(bih.biWidth, bih.biHeight, bits, &pal, pos, rozwin)?;
+            (pixels, (!rozwin).then_some(pal))
+        }
+        (16 | 32, _) if bih.biBitCount == 16 || bih.biCompression != BI_RGB => {
+            let masks = match (bih.biCompression, header.masks()) {
+                (BI_RGB, _) => RGB555_MASKS,
+                (_, Some(masks)) => masks,
+                (BI_BITFIELDS, None) => bmp.read_masks(3)?,
+                _ => bmp.read_masks(4)?,
+            };
+            let pola = BitFields::new(masks)?;
+            if !pola.fits(bih.biBitCount) {
+                return Err(eyre!("Channel masks exceed {} bits", bih.biBitCount));
+            }
+            debug!("MASKS={pola:?}");
+            (
+                bmp.read_pixels_bitfields(
+                    bih.biWidth,
+                    bih.biHeight,
+                    bih.biBitCount,
+                    &pola,
+                    bfh.bfOffBits as u64,
+                )?,
+                None,
+            )
+        }
+        (24 | 32, _) => (
+            bmp.read_pixels_rgb(
+                bih.biWidth,
+                bih.biHeight,
+                bih.biBitCount,
+                bfh.bfOffBits as u64,
+            )?,
+            None,
+        ),
+        (n, _) => return Err(eyre!("Unsupported bit depth {n}")),
     };
 
     //   debug!("PIX={pixels:?}");
 
-    Ok(Image {
-        x_size: bih.biWidth as usize,
-        y_size: bih.biHeight as usize,
-        palette: None,
-        pixels,
+    Ok(Image { palette, pixels })
+}
+
+/// Czyta nagłówek informacyjny i metadane pliku BMP (przestrzeń barw, profil
+/// ICC)
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::image_codec::bmp::{read_bmp_metadata, ColorSpace, InfoHeader};
+/// let meta = read_bmp_metadata("data/bmp/v5_icc.bmp").unwrap();
+/// assert!(matches!(meta.header, InfoHeader::V5(_)));
+/// assert_eq!(Some(ColorSpace::EmbeddedProfile), meta.color_space);
+/// assert_eq!(Some(b"fake ICC profile".to_vec()), meta.icc_profile);
+///
+/// let meta = read_bmp_metadata("data/bmp/v4_bitfields.bmp").unwrap();
+/// assert_eq!(Some(ColorSpace::Srgb), meta.color_space);
+/// assert_eq!(Some([0xFF, 0xFF00, 0xFF0000, 0]), meta.header.masks());
+/// assert_eq!(None, meta.icc_profile);
+///
+/// let meta = read_bmp_metadata("data/bmp/core12.bmp").unwrap();
+/// assert!(matches!(meta.header, InfoHeader::Core(_)));
+/// assert_eq!(None, meta.color_space);
+///
+/// // Rozmiar profilu większy niż plik
+/// let mut dane = std::fs::read("data/bmp/v5_icc.bmp").unwrap();
+/// dane[130..134].copy_from_slice(&0xFFFF_FFF0u32.to_le_bytes()); // bV5ProfileSize
+/// let plik = std::env::temp_dir().join("read_bmp_metadata_icc.bmp");
+/// std::fs::write(&plik, dane).unwrap();
+/// let e = read_bmp_metadata(plik.to_str().unwrap()).unwrap_err();
+/// assert_eq!("Truncated ICC profile: 16 of 4294967280 bytes", e.to_string());
+///
+/// let e = read_bmp_metadata("data/bmp/unknown64.bmp").unwrap_err();
+/// assert_eq!("Unknown BMP header size 64 (supported: 12, 40, 108, 124)", e.to_string());
+/// ```
+pub fn read_bmp_metadata(in_file_name: &str) -> Result<BmpMetadata> {
+    let (mut bmp, _, header) = BMP::open(in_file_name)?;
+    let icc_profile = match header {
+        InfoHeader::V5(v5)
+            if matches!(
+                header.color_space(),
+                Some(ColorSpace::EmbeddedProfile | ColorSpace::LinkedProfile)
+            ) =>
+        {
+            let f = &mut bmp.file;
+            f.seek(SeekFrom::Start(
+                BITMAP_FILE_HEADER_SIZE + v5.bV5ProfileData as u64,
+            ))?;
+            // Rozmiar z nagłówka nie jest zaufany - bufor rośnie z danymi
+            let profil = f.read_at_most(v5.bV5ProfileSize as usize)?;
+            if profil.len() < v5.bV5ProfileSize as usize {
+                return Err(eyre!(
+                    "Truncated ICC profile: {} of {} bytes",
+                    profil.len(),
+                    v5.bV5ProfileSize
+                ));
+            }
+            Some(profil)
+        }
+        _ => None,
+    };
+
+    Ok(BmpMetadata {
+        header,
+        color_space: header.color_space(),
+        icc_profile,
     })
 }
 
 impl BMP {
+    /// Otwiera plik, czyta i sprawdza oba nagłówki
+    fn open(in_file_name: &str) -> Result<(BMP, BitmapFileHeader, InfoHeader)> {
+        let mut bmp = BMP {
+            file: files::File::open(in_file_name)
+                .wrap_err_with(|| format!("Failed to open {in_file_name:?}"))?,
+        };
+
+        let bfh = bmp.read_bfh()?;
+        bfh.validate()?;
+        debug!("BFH={bfh:?}");
+
+        let header = bmp.read_header()?;
+        header.validate()?;
+        debug!("BIH={header:?}");
+
+        Ok((bmp, bfh, header))
+    }
+
     pub fn read_bfh(&mut self) -> Result<BitmapFileHeader> {
         let f = &mut self.file;
         Ok(BitmapFileHeader {
@@ -142,10 +685,50 @@ impl BMP {
         })
     }
 
-    pub fn read_bih(&mut self) -> Result<BitmapInfoHeader> {
+    /// Czyta nagłówek informacyjny w wersji wskazanej przez jego rozmiar
+    pub fn read_header(&mut self) -> Result<InfoHeader> {
+        let f = &mut self.file;
+        let size: u32 = f.binread()?;
+        if size == 12 {
+            return Ok(InfoHeader::Core(BitmapCoreHeader {
+                bcSize: size,
+                bcWidth: f.binread()?,
+                bcHeight: f.binread()?,
+                bcPlanes: f.binread()?,
+                bcBitCount: f.binread()?,
+            }));
+        }
+        if ![40, 108, 124].contains(&size) {
+            return Err(eyre!(
+                "Unknown BMP header size {size} (supported: 12, 40, 108, 124)"
+            ));
+        }
+
+        let info = self.read_bih(size)?;
+        if size == 40 {
+            return Ok(InfoHeader::Info(info));
+        }
+
+        let v4 = self.read_v4(info)?;
+        if size == 108 {
+            return Ok(InfoHeader::V4(v4));
+        }
+
+        let f = &mut self.file;
+        Ok(InfoHeader::V5(BitmapV5Header {
+            v4,
+            bV5Intent: f.binread()?,
+            bV5ProfileData: f.binread()?,
+            bV5ProfileSize: f.binread()?,
+            bV5Reserved: f.binread()?,
+        }))
+    }
+
+    /// Czyta pola BITMAPINFOHEADER następujące po rozmiarze
+    fn read_bih(&mut self, size: u32) -> Result<BitmapInfoHeader> {
         let f = &mut self.file;
         Ok(BitmapInfoHeader {
-            biSize: f.binread()?,
+            biSize: size,
             biWidth: f.binread()?,
             biHeight: f.binread()?,
             biPlanes: f.binread()?,
@@ -159,49 +742,679 @@ impl BMP {
         })
     }
 
-    pub fn read_palette(&mut self, len: u32) -> Result<Palette> {
+    /// Czyta pola dodane w BITMAPV4HEADER
+    fn read_v4(&mut self, info: BitmapInfoHeader) -> Result<BitmapV4Header> {
         let f = &mut self.file;
-        let data = f.read_as_vec(len as usize * 4)?;
+        let (bV4RedMask, bV4GreenMask, bV4BlueMask, bV4AlphaMask, bV4CSType) = (
+            f.binread()?,
+            f.binread()?,
+            f.binread()?,
+            f.binread()?,
+            f.binread()?,
+        );
+        let mut bV4Endpoints = CieXyzTriple::default();
+        for xyz in bV4Endpoints.iter_mut().flatten() {
+            *xyz = f.binread()?;
+        }
+        Ok(BitmapV4Header {
+            info,
+            bV4RedMask,
+            bV4GreenMask,
+            bV4BlueMask,
+            bV4AlphaMask,
+            bV4CSType,
+            bV4Endpoints,
+            bV4GammaRed: f.binread()?,
+            bV4GammaGreen: f.binread()?,
+            bV4GammaBlue: f.binread()?,
+        })
+    }
+
+    /// Czyta paletę o wpisach BGR (3 bajty) lub BGRX (4 bajty)
+    pub fn read_palette(&mut self, len: u32, entry_size: usize) -> Result<Palette> {
+        let f = &mut self.file;
+        let data = f.read_as_vec(len as usize * entry_size)?;
         let mut pal = Vec::with_capacity(len as usize);
 
-        for (i, quad) in data.chunks(4).enumerate() {
+        for quad in data.chunks(entry_size) {
             let (R, G, B) = (quad[2], quad[1], quad[0]);
-            if i < 128 {
-                pal.push(ColorRGB8(R, G, B));
-            } else {
-                pal.push(ColorRGB8(255 - R, 255 - G, 255 - B))
-            }
+            pal.push(ColorRGB8(R, G, B));
         }
 
         Ok(pal)
     }
 
-    pub fn read_pixels_pal(&mut self, xs: i32, ys: i32, pal: &Palette, pos: u64) -> Result<Frame> {
+    /// Czyta maski kanałów zapisane zaraz za nagłówkiem; brakujące są zerowe
+    pub fn read_masks(&mut self, count: usize) -> Result<[u32; 4]> {
+        let f = &mut self.file;
+        let mut masks = [0; 4];
+        for mask in masks.iter_mut().take(count) {
+            *mask = f.binread()?;
+        }
+        Ok(masks)
+    }
+
+    /// Czyta wszystkie linie obrazu (z wyrównaniem do 4 bajtów) i zwraca je
+    /// od górnej, bez bajtów wyrównania
+    fn read_rows(&mut self, xs: i32, ys: i32, bits: u16, pos: u64) -> Result<Vec<Vec<u8>>> {
         let f = &mut self.file;
         f.seek(SeekFrom::Start(pos))?;
+        // Dodatnia wysokość oznacza obraz zapisany od dołu
         let (rev, ys) = if ys < 0 { (false, -ys) } else { (true, ys) };
-        let mut bitmap = Vec::with_capacity(ys as usize);
-        for y in 0..ys {
-            let line = f.read_as_vec(xs as usize)?;
-            if xs % 4 != 0 {
-                f.read_as_vec(xs as usize % 4)?;
+        let used = (xs as usize * bits as usize).div_ceil(8);
+        let mut rows = Vec::with_capacity(ys as usize);
+        for _ in 0..ys {
+            let mut row = f.read_as_vec(stride(xs, bits))?;
+            row.truncate(used);
+            rows.push(row);
+        }
+
+        if rev {
+            rows.reverse()
+        }
+
+        Ok(rows)
+    }
+
+    /// Czyta piksele 8-bitowe jako kolory z palety (`expand`) albo indeksy
+    pub fn read_pixels_pal(
+        &mut self,
+        xs: i32,
+        ys: i32,
+        pal: &Palette,
+        pos: u64,
+        expand: bool,
+    ) -> Result<ImageBuffer<Pixel>> {
+        let rows = self.read_rows(xs, ys, 8, pos)?;
+        kolory_z_palety(rows, xs as usize, pal, expand)
+    }
+
+    /// Czyta i rozpakowuje piksele RLE8 lub RLE4
+    ///
+    /// `size` to rozmiar skompresowanych danych; zero oznacza "do końca pliku".
+    /// Z `expand` zwracane są kolory z palety zamiast indeksów.
+    #[allow(clippy::too_many_arguments)]
+    pub fn read_pixels_rle(
+        &mut self,
+        xs: i32,
+        ys: i32,
+        bits: u16,
+        pal: &Palette,
+        pos: u64,
+        size: u32,
+        fill: u8,
+        expand: bool,
+    ) -> Result<ImageBuffer<Pixel>> {
+        let f = &mut self.file;
+        f.seek(SeekFrom::Start(pos))?;
+        let data = match size {
+            0 => f.read_to_end()?,
+            n => {
+                let data = f.read_at_most(n as usize)?;
+                if data.len() < n as usize {
+                    return Err(eyre!("RLE data truncated: {} of {n} bytes", data.len()));
+                }
+                data
             }
+        };
+        let rows = decode_rle(&data, xs as usize, ys as usize, bits, fill)?;
+        kolory_z_palety(rows, xs as usize, pal, expand)
+    }
 
-            if line.iter().any(|idx| *idx as usize >= pal.len()) {
-                return Err(eyre!("Color byond palette"));
+    /// Czyta piksele 1, 2 lub 4-bitowe jako kolory z palety (`expand`) albo
+    /// indeksy
+    ///
+    /// Piksele są upakowane od najstarszych bitów bajtu, np. dla 4 bitów
+    /// pierwszy piksel to starsza połówka bajtu.
+    pub fn read_pixels_idx(
+        &mut self,
+        xs: i32,
+        ys: i32,
+        bits: u16,
+        pal: &Palette,
+        pos: u64,
+        expand: bool,
+    ) -> Result<ImageBuffer<Pixel>> {
+        let rows = self
+            .read_rows(xs, ys, bits, pos)?
+            .iter()
+            .map(|line| rozpakuj(line, xs as usize, bits))
+            .collect();
+        kolory_z_palety(rows, xs as usize, pal, expand)
+    }
+
+    /// Czyta piksele 16 lub 32-bitowe (little endian) opisane maskami kanałów
+    pub fn read_pixels_bitfields(
+        &mut self,
+        xs: i32,
+        ys: i32,
+        bits: u16,
+        pola: &BitFields,
+        pos: u64,
+    ) -> Result<ImageBuffer<Pixel>> {
+        let bytes = bits as usize / 8;
+        let rows = self.read_rows(xs, ys, bits, pos)?;
+        let format = match pola.alpha {
+            Some(_) => PixelFormat::RGBA8,
+            None => PixelFormat::RGB8,
+        };
+        let mut bitmap = ImageBuffer::new(xs as usize, rows.len(), format);
+        for (y, line) in rows.iter().enumerate() {
+            for (x, px) in line.chunks_exact(bytes).enumerate() {
+                let piksel = px.iter().rev().fold(0u32, |acc, b| (acc << 8) | *b as u32);
+                bitmap.put_pixel(x, y, pola.decode(piksel));
             }
-            let scanline = line
+        }
+
+        Ok(bitmap)
+    }
+
+    /// Czyta piksele BGR (24 bity) albo BGRX/BGRA (32 bity)
+    ///
+    /// W 32-bitowym BI_RGB czwarty bajt formalnie nie jest używany. Jeśli w
+    /// całym obrazie jest zerowy, piksele są RGB8, inaczej traktowany jest jako
+    /// kanał alfa (tak zapisuje go większość programów).
+    pub fn read_pixels_rgb(
+        &mut self,
+        xs: i32,
+        ys: i32,
+        bits: u16,
+        pos: u64,
+    ) -> Result<ImageBuffer<Pixel>> {
+        let rows = self.read_rows(xs, ys, bits, pos)?;
+        let bytes = bits as usize / 8;
+        let alpha = bits == 32 && rows.iter().flatten().skip(3).step_by(4).any(|a| *a != 0);
+        let format = match alpha {
+            true => PixelFormat::RGBA8,
+            false => PixelFormat::RGB8,
+        };
+
+        // BGR(A) na RGB(A)
+        let dlugosc = xs as usize * format.bytes_per_pixel();
+        let mut dane = Vec::with_capacity(rows.len() * dlugosc);
+        for px in rows.iter().flat_map(|line| line.chunks_exact(bytes)) {
+            dane.extend([px[2], px[1], px[0]]);
+            if alpha {
+                dane.push(px[3]);
+            }
+        }
+
+        ImageBuffer::from_raw(xs as usize, rows.len(), format, dlugosc, dane)
+    }
+}
+
+/// Rozpakowuje linię indeksów 1, 2, 4 lub 8-bitowych, upakowanych od
+/// najstarszych bitów bajtu
+pub(crate) fn rozpakuj(line: &[u8], xs: usize, bits: u16) -> Vec<u8> {
+    let bits = bits as usize;
+    (0..xs)
+        .map(|x| {
+            let bit = x * bits;
+            let najstarszy = 7 - bit % 8;
+            ekstrakcja(line[bit / 8], najstarszy + 1 - bits..=najstarszy)
+        })
+        .collect()
+}
+
+/// Zamienia linie `xs` indeksów na obraz - kolory z palety (RGB8) albo
+/// indeksy (INDEX8)
+fn kolory_z_palety(
+    rows: Vec<Vec<u8>>,
+    xs: usize,
+    pal: &Palette,
+    rozwin: bool,
+) -> Result<ImageBuffer<Pixel>> {
+    let ys = rows.len();
+    let indeksy = rows.concat();
+    if indeksy.iter().any(|idx| *idx as usize >= pal.len()) {
+        return Err(eyre!("Color byond palette"));
+    }
+
+    match rozwin {
+        true => {
+            let dane = indeksy
                 .iter()
-                .map(|idx| Pixel::RGB8(pal[*idx as usize]))
-                .collect::<Vec<_>>();
+                .flat_map(|idx| {
+                    let ColorRGB8(r, g, b) = pal[*idx as usize];
+                    [r, g, b]
+                })
+                .collect();
+            ImageBuffer::from_raw(xs, ys, PixelFormat::RGB8, 3 * xs, dane)
+        }
+        false => ImageBuffer::from_raw(xs, ys, PixelFormat::INDEX8, xs, indeksy),
+    }
+}
 
-            bitmap.push(scanline);
+/// Rozpakowuje dane RLE8 (`bits` = 8) lub RLE4 (`bits` = 4) do linii indeksów
+/// palety, zwracanych od górnej
+///
+/// Piksele pominięte skokiem albo końcem linii/obrazu dostają indeks `fill`.
+/// Koniec danych bez znacznika końca obrazu jest akceptowany, ale serie
+/// wychodzące poza szerokość lub wysokość obrazu są błędem.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::image_codec::bmp::decode_rle;
+/// // Seria, koniec linii, skok o (1, 0), ciąg bezwzględny z wyrównaniem, koniec
+/// let rle8 = [2, 7, 0, 0, 0, 2, 1, 0, 0, 3, 4, 5, 6, 0, 0, 1];
+/// assert_eq!(
+///     vec![vec![9, 4, 5, 6], vec![7, 7, 9, 9]],
+///     decode_rle(&rle8, 4, 2, 8, 9).unwrap()
+/// );
+///
+/// // W RLE4 seria powtarza na przemian obie połówki bajtu
+/// let rle4 = [3, 0x12, 0, 0, 0, 3, 0x45, 0x60, 0, 1];
+/// assert_eq!(
+///     vec![vec![4, 5, 6], vec![1, 2, 1]],
+///     decode_rle(&rle4, 3, 2, 4, 0).unwrap()
+/// );
+///
+/// assert!(decode_rle(&[0, 2, 5, 0], 4, 2, 8, 0).is_err()); // skok poza linię
+/// assert!(decode_rle(&[0, 0, 0, 0, 1, 1], 4, 2, 8, 0).is_err()); // poza obraz
+/// assert!(decode_rle(&[0, 4, 1, 2], 4, 2, 8, 0).is_err()); // urwany ciąg
+/// ```
+pub fn decode_rle(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    bits: u16,
+    fill: u8,
+) -> Result<Vec<Vec<u8>>> {
+    // Linie w kolejności z pliku, czyli od dolnej
+    let mut rows = vec![vec![fill; width]; height];
+    let (mut x, mut y) = (0, 0);
+    // Indeks `k`-tego piksela zapisanego w bajcie `b`
+    let piksel = |b: u8, k: usize| match bits {
+        8 => b,
+        _ if k.is_multiple_of(2) => b >> 4,
+        _ => b & 0x0F,
+    };
+    // Miejsce na `n` pikseli od (x, y), o ile mieści się w obrazie
+    fn miejsce(
+        rows: &mut [Vec<u8>],
+        width: usize,
+        (x, y, n): (usize, usize, usize),
+        offset: usize,
+    ) -> Result<&mut [u8]> {
+        if y >= rows.len() || x + n > width {
+            return Err(eyre!(
+                "RLE run of {n} pixels at offset {offset} overruns row {y} (x={x}, width {width})"
+            ));
         }
+        Ok(&mut rows[y][x..x + n])
+    }
 
-        if rev {
-            bitmap.reverse()
+    let mut i = 0;
+    while i + 1 < data.len() {
+        let (n, kod) = (data[i] as usize, data[i + 1]);
+        match (n, kod) {
+            (0, 0) => {
+                x = 0;
+                y += 1;
+            }
+            (0, 1) => break,
+            (0, 2) => {
+                let delta = data
+                    .get(i + 2..i + 4)
+                    .ok_or(eyre!("Truncated RLE delta at offset {i}"))?;
+                x += delta[0] as usize;
+                y += delta[1] as usize;
+                if x > width || y >= height {
+                    return Err(eyre!("RLE delta at offset {i} jumps outside the image"));
+                }
+                i += 2;
+            }
+            (0, n) => {
+                let n = n as usize;
+                let dlugosc = if bits == 8 { n } else { n.div_ceil(2) };
+                let zrodlo = data
+                    .get(i + 2..i + 2 + dlugosc)
+                    .ok_or(eyre!("Truncated RLE absolute run at offset {i}"))?;
+                for (k, p) in miejsce(&mut rows, width, (x, y, n), i)?
+                    .iter_mut()
+                    .enumerate()
+                {
+                    *p = piksel(zrodlo[if bits == 8 { k } else { k / 2 }], k);
+                }
+                x += n;
+                // Ciągi bezwzględne wyrównane są do 16 bitów
+                i += dlugosc + dlugosc % 2;
+            }
+            (n, b) => {
+                for (k, p) in miejsce(&mut rows, width, (x, y, n), i)?
+                    .iter_mut()
+                    .enumerate()
+                {
+                    *p = piksel(b, k);
+                }
+                x += n;
+            }
         }
+        i += 2;
+    }
 
-        Ok(bitmap)
+    rows.reverse();
+    Ok(rows)
+}
+
+/// Długość linii w pliku - liczba bajtów wyrównana w górę do 4
+pub(crate) fn stride(xs: i32, bits: u16) -> usize {
+    (xs as usize * bits as usize).div_ceil(32) * 4
+}
+
+/// Opcje zapisu BMP
+#[derive(Clone, Debug, Default)]
+pub struct WriteOptions {
+    /// Liczba bitów na piksel; domyślnie najmniejsza mieszcząca paletę, a dla
+    /// obrazów bez palety 24 (32, gdy jest kanał alfa)
+    pub bit_count: Option<u16>,
+    /// Kompresja RLE8 (tylko dla 8 bitów)
+    pub rle: bool,
+    /// Zapis linii od górnej (ujemna wysokość); niedostępny dla RLE
+    pub top_down: bool,
+}
+
+/// Zapisuje obraz jako plik BMP
+///
+/// Obrazy z paletą muszą składać się z pikseli [`Pixel::INDEX8`] i są
+/// zapisywane z paletą 1, 2, 4 lub 8-bitową. Pozostałe zapisywane są jako
+/// 24-bitowe BGR albo 32-bitowe - z kanałem alfa w nagłówku V4 z maskami.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::image_codec::bmp::{read_bmp, write_bmp, WriteOptions};
+/// let plik = std::env::temp_dir().join("write_bmp_doc.bmp");
+/// let plik = plik.to_str().unwrap();
+///
+/// for zrodlo in ["rgb24", "bgra32", "index1", "index2", "index4"] {
+///     let img = read_bmp(&format!("data/bmp/{zrodlo}.bmp")).unwrap();
+///     write_bmp(plik, &img, &WriteOptions::default()).unwrap();
+///     assert_eq!(img, read_bmp(plik).unwrap(), "{zrodlo}");
+/// }
+///
+/// let img = read_bmp("data/bmp/rgb24.bmp").unwrap();
+/// let opcje = WriteOptions { bit_count: Some(32), top_down: true, ..Default::default() };
+/// write_bmp(plik, &img, &opcje).unwrap();
+/// assert_eq!(img, read_bmp(plik).unwrap());
+///
+/// let img = read_bmp("data/bmp/rle4.bmp").unwrap();
+/// let opcje = WriteOptions { bit_count: Some(8), rle: true, ..Default::default() };
+/// write_bmp(plik, &img, &opcje).unwrap();
+/// // Te same indeksy i paleta, choć zapisane na 8 bitach
+/// assert_eq!(img, read_bmp(plik).unwrap());
+///
+/// let opcje = WriteOptions { bit_count: Some(1), ..Default::default() };
+/// assert!(write_bmp(plik, &img, &opcje).is_err()); // 16 kolorów nie zmieści się
+/// ```
+pub fn write_bmp(out_file_name: &str, img: &Image, options: &WriteOptions) -> Result<()> {
+    let data = encode_bmp(img, options)?;
+    let mut f = files::File::create(out_file_name)
+        .wrap_err_with(|| format!("Failed to create {out_file_name:?}"))?;
+    f.write_exact(&data)
+}
+
+/// Koduje obraz do zawartości pliku BMP (zob. [`write_bmp`])
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::image_codec::bmp::{encode_bmp, WriteOptions};
+/// use sekurak_hex_gynvael::image_codec::{ColorRGB8, Image, ImageBuffer, PixelFormat};
+/// let mut pixels = ImageBuffer::new(1, 1, PixelFormat::RGB8);
+/// pixels.put_pixel(0, 0, ColorRGB8(1, 2, 3));
+/// let img = Image {
+///     palette: None,
+///     pixels: pixels.cast().unwrap(),
+/// };
+/// let bmp = encode_bmp(&img, &WriteOptions::default()).unwrap();
+/// assert_eq!(b"BM", &bmp[..2]);
+/// assert_eq!(58, u32::from_le_bytes(bmp[2..6].try_into().unwrap())); // bfSize
+/// assert_eq!(54, u32::from_le_bytes(bmp[10..14].try_into().unwrap())); // bfOffBits
+/// assert_eq!([3, 2, 1, 0], bmp[54..]); // BGR i wyrównanie do 4 bajtów
+/// ```
+pub fn encode_bmp(img: &Image, options: &WriteOptions) -> Result<Vec<u8>> {
+    if img.width() > 0xFFFF || img.height() > 0xFFFF {
+        return Err(eyre!("Image too large for BMP"));
+    }
+
+    let alpha = img.pixels.format() == PixelFormat::RGBA8;
+    let bits = match (options.bit_count, &img.palette) {
+        (Some(bits), Some(pal)) if [1, 2, 4, 8].contains(&bits) => {
+            if pal.len() > 1 << bits {
+                return Err(eyre!("{} colors do not fit {bits}-bit palette", pal.len()));
+            }
+            bits
+        }
+        (Some(bits @ (24 | 32)), None) => bits,
+        (Some(bits), _) => return Err(eyre!("Unsupported bit depth {bits} for this image")),
+        (None, Some(pal)) => match pal.len() {
+            0..=2 => 1,
+            3..=16 => 4,
+            17..=256 => 8,
+            n => return Err(eyre!("Palette of {n} colors is too large")),
+        },
+        (None, None) if alpha => 32,
+        (None, None) => 24,
+    };
+    if options.rle && bits != 8 {
+        return Err(eyre!("RLE8 requires 8-bit output"));
+    }
+    if options.rle && options.top_down {
+        return Err(eyre!("RLE bitmaps cannot be top-down"));
+    }
+
+    // Linie od górnej: indeksy palety albo bajty BGRA
+    let mut rows = match &img.palette {
+        Some(pal) => indeksy(img, pal)?,
+        None => (0..img.height())
+            .map(|y| {
+                let linia = img.pixels.row_pixels(y).map(|p| bgra(&p));
+                Ok(linia.collect::<Result<Vec<_>>>()?.concat())
+            })
+            .collect::<Result<Vec<_>>>()?,
+    };
+    let dane = if options.rle {
+        encode_rle8(&rows)
+    } else {
+        if !options.top_down {
+            rows.reverse();
+        }
+        let dlugosc = stride(img.width() as i32, bits);
+        let mut dane = Vec::with_capacity(dlugosc * rows.len());
+        for line in &rows {
+            let mut linia = match bits {
+                1 | 2 | 4 => upakuj(line, bits),
+                24 => line.chunks(4).flat_map(|p| &p[..3]).copied().collect(),
+                _ => line.clone(),
+            };
+            linia.resize(dlugosc, 0);
+            dane.extend(linia);
+        }
+        dane
+    };
+
+    // Alfa wymaga masek; nagłówek V4 rozumie większość programów
+    let v4 = bits == 32 && alpha;
+    let pal = img.palette.as_deref().unwrap_or_default();
+    let biSize: u32 = if v4 { 108 } else { 40 };
+    let bfOffBits = BITMAP_FILE_HEADER_SIZE as u32 + biSize + 4 * pal.len() as u32;
+    let bfh = BitmapFileHeader {
+        bfType: BM_MAGIC,
+        bfSize: bfOffBits + dane.len() as u32,
+        bfReserved1: 0,
+        bfReserved2: 0,
+        bfOffBits,
+    };
+    let bih = BitmapInfoHeader {
+        biSize,
+        biWidth: img.width() as i32,
+        biHeight: match options.top_down {
+            true => -(img.height() as i32),
+            false => img.height() as i32,
+        },
+        biPlanes: 1,
+        biBitCount: bits,
+        biCompression: match (options.rle, v4) {
+            (true, _) => BI_RLE8,
+            (_, true) => BI_BITFIELDS,
+            _ => BI_RGB,
+        },
+        biSizeImage: dane.len() as u32,
+        // 72 DPI
+        biXPelsPerMeter: 2835,
+        biYPelsPerMeter: 2835,
+        biClrUsed: pal.len() as u32,
+        biClrImportant: 0,
+    };
+
+    let mut out = Vec::with_capacity(bfh.bfSize as usize);
+    bfh.zapisz(&mut out);
+    bih.zapisz(&mut out);
+    if v4 {
+        let srgb = u32::from_be_bytes(*b"sRGB");
+        for pole in [0x00FF_0000, 0x0000_FF00, 0x0000_00FF, 0xFF00_0000, srgb] {
+            out.extend(u32::to_le_bytes(pole));
+        }
+        // Punkty końcowe i gamma nie są używane dla sRGB
+        out.resize(out.len() + 48, 0);
+    }
+    for ColorRGB8(r, g, b) in pal {
+        out.extend([*b, *g, *r, 0]);
+    }
+    out.extend(dane);
+
+    Ok(out)
+}
+
+impl BitmapFileHeader {
+    fn zapisz(&self, out: &mut Vec<u8>) {
+        out.extend(self.bfType.to_le_bytes());
+        out.extend(self.bfSize.to_le_bytes());
+        out.extend(self.bfReserved1.to_le_bytes());
+        out.extend(self.bfReserved2.to_le_bytes());
+        out.extend(self.bfOffBits.to_le_bytes());
+    }
+}
+
+impl BitmapInfoHeader {
+    fn zapisz(&self, out: &mut Vec<u8>) {
+        out.extend(self.biSize.to_le_bytes());
+        out.extend(self.biWidth.to_le_bytes());
+        out.extend(self.biHeight.to_le_bytes());
+        out.extend(self.biPlanes.to_le_bytes());
+        out.extend(self.biBitCount.to_le_bytes());
+        out.extend(self.biCompression.to_le_bytes());
+        out.extend(self.biSizeImage.to_le_bytes());
+        out.extend(self.biXPelsPerMeter.to_le_bytes());
+        out.extend(self.biYPelsPerMeter.to_le_bytes());
+        out.extend(self.biClrUsed.to_le_bytes());
+        out.extend(self.biClrImportant.to_le_bytes());
+    }
+}
+
+/// Linie indeksów obrazu z paletą
+fn indeksy(img: &Image, pal: &Palette) -> Result<Vec<Vec<u8>>> {
+    (0..img.height())
+        .map(|y| {
+            img.pixels
+                .row_pixels(y)
+                .enumerate()
+                .map(|(x, p)| match p {
+                    Pixel::INDEX8(i) if (i as usize) < pal.len() => Ok(i),
+                    _ => Err(eyre!("Pixel {p:?} at ({x}, {y}) is not a palette index")),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Bajty piksela w kolejności BMP: B, G, R, A
+fn bgra(p: &Pixel) -> Result<[u8; 4]> {
+    match *p {
+        Pixel::RGB8(ColorRGB8(r, g, b)) => Ok([b, g, r, 0]),
+        Pixel::RGBA8(ColorRGB8(r, g, b), a) => Ok([b, g, r, a]),
+        Pixel::GRAY8(g) => Ok([g, g, g, 0]),
+        Pixel::INDEX8(_) => Err(eyre!("Indexed pixels require a palette")),
+    }
+}
+
+/// Pakuje indeksy 1, 2 lub 4-bitowe od najstarszych bitów bajtu
+fn upakuj(indeksy: &[u8], bits: u16) -> Vec<u8> {
+    let bits = bits as usize;
+    let mut out = vec![0u8; (indeksy.len() * bits).div_ceil(8)];
+    for (x, idx) in indeksy.iter().enumerate() {
+        let bit = x * bits;
+        let najstarszy = 7 - bit % 8;
+        out[bit / 8] = wstawienie(out[bit / 8], *idx, najstarszy + 1 - bits..=najstarszy);
     }
+    out
+}
+
+/// Kompresuje linie indeksów (od górnej) do RLE8
+///
+/// Powtórzenia zapisywane są jako serie, pozostałe bajty - jako ciągi
+/// bezwzględne (od 3 bajtów, krótsze jako serie długości 1). Każda linia
+/// kończy się znacznikiem końca linii, a ostatnia - końca obrazu.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::image_codec::bmp::{decode_rle, encode_rle8};
+/// let rows = vec![vec![1, 1, 1, 1, 2, 3, 4, 5, 5], vec![7; 9]];
+/// let rle = encode_rle8(&rows);
+/// assert_eq!(vec![9, 7, 0, 0, 4, 1, 0, 3, 2, 3, 4, 0, 2, 5, 0, 1], rle);
+/// assert_eq!(rows, decode_rle(&rle, 9, 2, 8, 0).unwrap());
+///
+/// // Serie dłuższe niż 255 pikseli są dzielone
+/// assert_eq!(vec![255, 7, 45, 7, 0, 1], encode_rle8(&[vec![7; 300]]));
+/// ```
+pub fn encode_rle8(rows: &[Vec<u8>]) -> Vec<u8> {
+    let mut out = Vec::new();
+    // Plik zaczyna się od dolnej linii
+    for line in rows.iter().rev() {
+        let mut x = 0;
+        while x < line.len() {
+            let seria = powtorzenia(&line[x..]);
+            if seria >= 2 {
+                out.extend([seria as u8, line[x]]);
+                x += seria;
+                continue;
+            }
+            // Ciąg bezwzględny aż do początku serii
+            let mut koniec = x + 1;
+            while koniec < line.len() && koniec - x < 255 && powtorzenia(&line[koniec..]) < 2 {
+                koniec += 1;
+            }
+            let ciag = &line[x..koniec];
+            if ciag.len() < 3 {
+                for b in ciag {
+                    out.extend([1, *b]);
+                }
+            } else {
+                out.extend([0, ciag.len() as u8]);
+                out.extend(ciag);
+                if ciag.len() % 2 == 1 {
+                    out.push(0);
+                }
+            }
+            x = koniec;
+        }
+        out.extend([0, 0]);
+    }
+    // Ostatni koniec linii zamieniany jest na koniec obrazu
+    match out.last_mut() {
+        Some(ostatni) => *ostatni = 1,
+        None => out.extend([0, 1]),
+    }
+    out
+}
+
+/// Długość serii takich samych bajtów na początku (najwyżej 255)
+fn powtorzenia(dane: &[u8]) -> usize {
+    dane.iter()
+        .take(255)
+        .take_while(|b| Some(*b) == dane.first())
+        .count()
 }
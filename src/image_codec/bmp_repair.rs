@@ -0,0 +1,579 @@
+use std::fmt;
+use std::ops::Range;
+
+use eyre::{eyre, Result};
+
+use super::bmp::{
+    decode_rle, rozpakuj, stride, BITMAP_FILE_HEADER_SIZE, BI_ALPHABITFIELDS, BI_BITFIELDS, BI_RGB,
+    BI_RLE4, BI_RLE8, BM_MAGIC,
+};
+use super::{ColorRGB8, Palette};
+
+/// Pojedyncza poprawka wprowadzona w uszkodzonym pliku BMP
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Correction {
+    /// Przywrócone `BM` na początku pliku
+    Magic {
+        old: [u8; 2],
+    },
+    /// Nieznany rozmiar nagłówka zastąpiony rozmiarem BITMAPINFOHEADER
+    HeaderSize {
+        old: u32,
+        new: u32,
+    },
+    Planes {
+        old: u16,
+        new: u16,
+    },
+    /// Głębia dobrana tak, żeby dane pikseli miały oczekiwaną długość
+    BitCount {
+        old: u16,
+        new: u16,
+    },
+    /// Liczba kolorów większa niż pozwala głębia
+    ColorsUsed {
+        old: u32,
+        new: u32,
+    },
+    FileSize {
+        old: u32,
+        new: u32,
+    },
+    PixelOffset {
+        old: u32,
+        new: u32,
+    },
+    ImageSize {
+        old: u32,
+        new: u32,
+    },
+    /// Szerokość wyliczona z długości linii (może obejmować piksele wyrównania)
+    Width {
+        old: i32,
+        new: i32,
+    },
+    /// Wysokość wyliczona z długości danych pikseli
+    Height {
+        old: i32,
+        new: i32,
+    },
+    /// Odwrócone kolory (255 - składowa) w zakresie wpisów palety
+    InvertedPalette {
+        entries: Range<usize>,
+    },
+}
+
+impl fmt::Display for Correction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Correction::Magic { old } => write!(f, "magic {old:02X?} -> \"BM\""),
+            Correction::HeaderSize { old, new } => write!(f, "header size {old} -> {new}"),
+            Correction::Planes { old, new } => write!(f, "planes {old} -> {new}"),
+            Correction::BitCount { old, new } => write!(f, "bit count {old} -> {new}"),
+            Correction::ColorsUsed { old, new } => write!(f, "colors used {old} -> {new}"),
+            Correction::FileSize { old, new } => write!(f, "file size {old} -> {new}"),
+            Correction::PixelOffset { old, new } => {
+                write!(f, "pixel data offset {old:#x} -> {new:#x}")
+            }
+            Correction::ImageSize { old, new } => write!(f, "image size {old} -> {new}"),
+            Correction::Width { old, new } => write!(f, "width {old} -> {new} (from row stride)"),
+            Correction::Height { old, new } => {
+                write!(f, "height {old} -> {new} (from pixel data length)")
+            }
+            Correction::InvertedPalette { entries } => write!(
+                f,
+                "inverted palette entries {}..={}",
+                entries.start,
+                entries.end - 1
+            ),
+        }
+    }
+}
+
+/// Naprawiony plik i lista wprowadzonych poprawek
+#[derive(Clone, Debug)]
+pub struct Repaired {
+    pub data: Vec<u8>,
+    pub corrections: Vec<Correction>,
+}
+
+// Przesunięcia pól w pliku
+const BF_SIZE: usize = 2;
+const BF_OFF_BITS: usize = 10;
+const BI_SIZE: usize = 14;
+const BI_WIDTH: usize = 18;
+const BI_HEIGHT: usize = 22;
+const BI_PLANES: usize = 26;
+const BI_BIT_COUNT: usize = 28;
+const BI_COMPRESSION: usize = 30;
+const BI_SIZE_IMAGE: usize = 34;
+const BI_CLR_USED: usize = 46;
+
+/// Minimalny udział sąsiedztw, na których opiera się decyzja o odwróceniu
+/// bloku palety
+const MIN_DOWODY: usize = 1000;
+/// Odwrócenie musi co najmniej tyle razy zmniejszyć różnice między sąsiadami
+const MIN_POPRAWA: f64 = 2.0;
+
+/// Naprawia nagłówki i paletę uszkodzonego pliku BMP z BITMAPINFOHEADER (lub
+/// V4/V5)
+///
+/// Pola nagłówków odtwarzane są z rozmiaru pliku i długości danych pikseli,
+/// a odwrócone fragmenty palety wykrywane statystycznie (zob.
+/// [`detect_inverted_palette`]). Poprawki stosowane są tylko, gdy pole jest
+/// niepoprawne albo niezgodne z resztą pliku.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::image_codec::bmp_repair::{repair, Correction};
+/// let dobry = std::fs::read("data/sing_scape.bmp").unwrap();
+/// let mut zly = dobry.clone();
+/// zly[..2].copy_from_slice(b"XX");
+/// zly[2..6].copy_from_slice(&7u32.to_le_bytes()); // bfSize
+/// zly[28..30].copy_from_slice(&7u16.to_le_bytes()); // biBitCount
+/// // Odwrócona druga połowa palety (bez bajtów wypełnienia wpisów BGRX)
+/// for bgrx in zly[54 + 128 * 4..1078].chunks_mut(4) {
+///     bgrx[..3].iter_mut().for_each(|b| *b = 255 - *b);
+/// }
+///
+/// let wynik = repair(&zly).unwrap();
+/// assert_eq!(
+///     vec![
+///         Correction::Magic { old: *b"XX" },
+///         Correction::BitCount { old: 7, new: 8 },
+///         Correction::FileSize { old: 7, new: 82038 },
+///         Correction::InvertedPalette { entries: 128..256 },
+///     ],
+///     wynik.corrections
+/// );
+/// assert_eq!(dobry, wynik.data);
+///
+/// // Szerokość z długości linii, przesunięcie danych za paletą
+/// let mut zly = dobry.clone();
+/// zly[10..14].copy_from_slice(&0x1234_5678u32.to_le_bytes()); // bfOffBits
+/// zly[18..22].copy_from_slice(&0u32.to_le_bytes()); // biWidth
+/// let wynik = repair(&zly).unwrap();
+/// assert_eq!(
+///     vec![
+///         Correction::PixelOffset { old: 0x1234_5678, new: 1078 },
+///         Correction::Width { old: 0, new: 320 },
+///     ],
+///     wynik.corrections
+/// );
+/// assert_eq!(dobry, wynik.data);
+///
+/// // Samo przesunięcie danych poza plikiem
+/// let mut zly = dobry.clone();
+/// zly[10..14].copy_from_slice(&0x1234_5678u32.to_le_bytes()); // bfOffBits
+/// let wynik = repair(&zly).unwrap();
+/// assert_eq!(vec![Correction::PixelOffset { old: 0x1234_5678, new: 1078 }], wynik.corrections);
+/// assert_eq!(dobry, wynik.data);
+///
+/// // Geometrii nie da się odtworzyć, ale to nie jest błąd
+/// let mut zly = dobry.clone();
+/// zly[18..22].copy_from_slice(&i32::MIN.to_le_bytes()); // biWidth
+/// zly[22..26].copy_from_slice(&0i32.to_le_bytes()); // biHeight
+/// assert!(repair(&zly).is_ok());
+///
+/// // Poprawny plik zostaje bez zmian
+/// assert!(repair(&dobry).unwrap().corrections.is_empty());
+/// ```
+pub fn repair(data: &[u8]) -> Result<Repaired> {
+    if data.len() < BI_CLR_USED + 8 {
+        return Err(eyre!("File too short for BMP headers"));
+    }
+    let mut naprawa = Naprawa {
+        data: data.to_vec(),
+        corrections: Vec::new(),
+    };
+    if naprawa.u32(BI_SIZE) == 12 {
+        // Nagłówek OS/2 ma inny układ pól - poprawiane są tylko magia i rozmiar
+        naprawa.magia();
+        naprawa.rozmiar_pliku();
+    } else {
+        naprawa.naglowki()?;
+        naprawa.paleta()?;
+    }
+    Ok(Repaired {
+        data: naprawa.data,
+        corrections: naprawa.corrections,
+    })
+}
+
+struct Naprawa {
+    data: Vec<u8>,
+    corrections: Vec<Correction>,
+}
+
+impl Naprawa {
+    fn u16(&self, o: usize) -> u16 {
+        u16::from_le_bytes([self.data[o], self.data[o + 1]])
+    }
+
+    fn u32(&self, o: usize) -> u32 {
+        u32::from_le_bytes(self.data[o..o + 4].try_into().unwrap())
+    }
+
+    fn i32(&self, o: usize) -> i32 {
+        self.u32(o) as i32
+    }
+
+    fn set_u16(&mut self, o: usize, v: u16) {
+        self.data[o..o + 2].copy_from_slice(&v.to_le_bytes());
+    }
+
+    fn set_u32(&mut self, o: usize, v: u32) {
+        self.data[o..o + 4].copy_from_slice(&v.to_le_bytes());
+    }
+
+    fn magia(&mut self) {
+        if self.u16(0) != BM_MAGIC {
+            let old = [self.data[0], self.data[1]];
+            self.set_u16(0, BM_MAGIC);
+            self.corrections.push(Correction::Magic { old });
+        }
+    }
+
+    fn rozmiar_pliku(&mut self) {
+        let len = self.data.len();
+        let bf_size = self.u32(BF_SIZE);
+        if bf_size as usize != len {
+            self.set_u32(BF_SIZE, len as u32);
+            self.corrections.push(Correction::FileSize {
+                old: bf_size,
+                new: len as u32,
+            });
+        }
+    }
+
+    fn naglowki(&mut self) -> Result<()> {
+        let len = self.data.len();
+        self.magia();
+
+        let size = self.u32(BI_SIZE);
+        // Znane rozmiary: OS/2 2.x (16, 64), BITMAPINFOHEADER, V2 (52), V3 (56), V4, V5
+        if ![16, 40, 52, 56, 64, 108, 124].contains(&size) {
+            self.set_u32(BI_SIZE, 40);
+            self.corrections
+                .push(Correction::HeaderSize { old: size, new: 40 });
+        }
+
+        let planes = self.u16(BI_PLANES);
+        if planes != 1 {
+            self.set_u16(BI_PLANES, 1);
+            self.corrections.push(Correction::Planes {
+                old: planes,
+                new: 1,
+            });
+        }
+
+        let compression = self.u32(BI_COMPRESSION);
+        let rle = [BI_RLE8, BI_RLE4].contains(&compression);
+
+        let bits = self.u16(BI_BIT_COUNT);
+        if ![1, 2, 4, 8, 16, 24, 32].contains(&bits) {
+            // Głębia, przy której dane pikseli zajmują dokładnie resztę pliku
+            let nowa = match compression {
+                BI_RLE8 => Some(8),
+                BI_RLE4 => Some(4),
+                _ => [1, 2, 4, 8, 16, 24, 32].into_iter().find(|b| {
+                    let dane = self.rozmiar_danych(*b);
+                    dane > 0 && self.koniec_naglowkow(*b).checked_add(dane) == Some(len)
+                }),
+            };
+            let nowa = nowa.ok_or(eyre!("Cannot infer bit count {bits}"))?;
+            self.set_u16(BI_BIT_COUNT, nowa);
+            self.corrections.push(Correction::BitCount {
+                old: bits,
+                new: nowa,
+            });
+        }
+        let bits = self.u16(BI_BIT_COUNT);
+
+        let used = self.u32(BI_CLR_USED);
+        if bits <= 8 && used > 1 << bits || bits > 8 && used > 256 {
+            self.set_u32(BI_CLR_USED, 0);
+            self.corrections
+                .push(Correction::ColorsUsed { old: used, new: 0 });
+        }
+
+        let poczatek = self.koniec_naglowkow(bits);
+        if poczatek > len {
+            return Err(eyre!("File too short for palette"));
+        }
+        let off = self.u32(BF_OFF_BITS) as usize;
+        let dane = self.rozmiar_danych(bits);
+        let zly_off = off < poczatek || off > len;
+        let lepszy_off =
+            !zly_off && !rle && off != poczatek && len - poczatek == dane && len - off != dane;
+        if zly_off || lepszy_off {
+            self.set_u32(BF_OFF_BITS, poczatek as u32);
+            self.corrections.push(Correction::PixelOffset {
+                old: off as u32,
+                new: poczatek as u32,
+            });
+        }
+        let off = self.u32(BF_OFF_BITS) as usize;
+        let dostepne = len - off;
+
+        if !rle {
+            self.geometria(bits, dostepne);
+        }
+
+        let image = self.u32(BI_SIZE_IMAGE);
+        let oczekiwany = match rle {
+            true if image == 0 || image as usize > dostepne => dostepne as u32,
+            true => image,
+            false => self.rozmiar_danych(bits) as u32,
+        };
+        if image != oczekiwany && (rle || image != 0) {
+            self.set_u32(BI_SIZE_IMAGE, oczekiwany);
+            self.corrections.push(Correction::ImageSize {
+                old: image,
+                new: oczekiwany,
+            });
+        }
+
+        self.rozmiar_pliku();
+
+        Ok(())
+    }
+
+    /// Odtwarza szerokość albo wysokość, gdy jedna z nich jest niepoprawna
+    /// lub nie zgadza się z długością danych
+    fn geometria(&mut self, bits: u16, dostepne: usize) {
+        let (w, h) = (self.i32(BI_WIDTH), self.i32(BI_HEIGHT));
+        let w_ok = (1..=0xFFFF).contains(&w);
+        let h_ok = (1..=0xFFFF).contains(&h.unsigned_abs());
+        if w_ok && h_ok && self.rozmiar_danych(bits) <= dostepne {
+            return;
+        }
+
+        let wiersze = h.unsigned_abs() as usize;
+        if h_ok && dostepne.is_multiple_of(wiersze) && (dostepne / wiersze).is_multiple_of(4) {
+            // Najszersza szerokość o tej długości linii
+            let nowa = (dostepne / wiersze * 8 / bits as usize) as i32;
+            if nowa != w && (1..=0xFFFF).contains(&nowa) {
+                self.set_u32(BI_WIDTH, nowa as u32);
+                self.corrections
+                    .push(Correction::Width { old: w, new: nowa });
+                return;
+            }
+        }
+
+        if !w_ok {
+            return;
+        }
+        let linia = stride(w, bits);
+        if dostepne.is_multiple_of(linia) {
+            let nowa = (dostepne / linia) as i32;
+            let nowa = if h < 0 { -nowa } else { nowa };
+            if nowa != h && (1..=0xFFFF).contains(&nowa.unsigned_abs()) {
+                self.set_u32(BI_HEIGHT, nowa as u32);
+                self.corrections
+                    .push(Correction::Height { old: h, new: nowa });
+            }
+        }
+    }
+
+    /// Liczba kolorów palety dla podanej głębi
+    fn kolory(&self, bits: u16) -> usize {
+        let used = self.u32(BI_CLR_USED) as usize;
+        match bits {
+            1 | 2 | 4 | 8 if (1..=1 << bits).contains(&used) => used,
+            1 | 2 | 4 | 8 => 1 << bits,
+            _ => 0,
+        }
+    }
+
+    /// Początek danych pikseli zaraz za nagłówkami, maskami i paletą
+    fn koniec_naglowkow(&self, bits: u16) -> usize {
+        let size = self.u32(BI_SIZE) as usize;
+        let maski = match (size, self.u32(BI_COMPRESSION)) {
+            (40, BI_BITFIELDS) => 12,
+            (40, BI_ALPHABITFIELDS) => 16,
+            _ => 0,
+        };
+        BITMAP_FILE_HEADER_SIZE as usize + size + maski + 4 * self.kolory(bits)
+    }
+
+    /// Długość nieskompresowanych danych pikseli
+    fn rozmiar_danych(&self, bits: u16) -> usize {
+        let (w, h) = (self.i32(BI_WIDTH), self.i32(BI_HEIGHT));
+        if !(0..=0xFFFF).contains(&w) || h.unsigned_abs() > 0xFFFF {
+            return usize::MAX;
+        }
+        stride(w, bits) * h.unsigned_abs() as usize
+    }
+
+    /// Wykrywa i odwraca uszkodzone fragmenty palety
+    fn paleta(&mut self) -> Result<()> {
+        let bits = self.u16(BI_BIT_COUNT);
+        let compression = self.u32(BI_COMPRESSION);
+        if bits > 8 || ![BI_RGB, BI_RLE8, BI_RLE4].contains(&compression) {
+            return Ok(());
+        }
+        let (w, h) = (self.i32(BI_WIDTH), self.i32(BI_HEIGHT));
+        if !(1..=0xFFFF).contains(&w) || !(1..=0xFFFF).contains(&h.unsigned_abs()) {
+            return Ok(());
+        }
+        let (w, wiersze) = (w as usize, h.unsigned_abs() as usize);
+        let off = self.u32(BF_OFF_BITS) as usize;
+        let pal_off = self.koniec_naglowkow(bits) - 4 * self.kolory(bits);
+        let pal: Palette = self.data[pal_off..pal_off + 4 * self.kolory(bits)]
+            .chunks(4)
+            .map(|q| ColorRGB8(q[2], q[1], q[0]))
+            .collect();
+
+        let rows = match compression {
+            BI_RGB => {
+                let linia = stride(w as i32, bits);
+                if self.data.len() < off + linia * wiersze {
+                    return Ok(());
+                }
+                self.data[off..off + linia * wiersze]
+                    .chunks(linia)
+                    .map(|l| rozpakuj(l, w, bits))
+                    .collect()
+            }
+            _ => match decode_rle(&self.data[off..], w, wiersze, bits, 0) {
+                Ok(rows) => rows,
+                Err(_) => return Ok(()),
+            },
+        };
+
+        for entries in detect_inverted_palette(&pal, &rows) {
+            for i in entries.clone() {
+                for b in &mut self.data[pal_off + 4 * i..pal_off + 4 * i + 3] {
+                    *b = 255 - *b;
+                }
+            }
+            self.corrections
+                .push(Correction::InvertedPalette { entries });
+        }
+        Ok(())
+    }
+}
+
+/// Wykrywa fragmenty palety z odwróconymi kolorami
+///
+/// Sąsiednie piksele w obrazie mają zwykle podobne kolory. Dla bloków palety
+/// wyrównanych do potęg dwójki (typowe dla uszkodzeń bitowych) sprawdzane
+/// jest, czy odwrócenie kolorów bloku wyraźnie zmniejsza różnice między
+/// sąsiadującymi pikselami z bloku i spoza niego. Najlepsze odwrócenia są
+/// stosowane zachłannie, dopóki przynoszą poprawę.
+///
+/// Pod uwagę brane są tylko bloki pokrywające mniej niż połowę obrazu -
+/// odwrócenie dopełnienia daje te same różnice, a uszkodzona jest zwykle
+/// mniejsza część. Pomijane są też bloki, po których odwróceniu wszyscy
+/// sąsiedzi spoza bloku mieliby identyczne kolory.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::image_codec::bmp_repair::detect_inverted_palette;
+/// use sekurak_hex_gynvael::image_codec::ColorRGB8;
+/// // Gradient od czarnego do białego; wpis 5 odwrócony
+/// let pal: Vec<_> = [0, 40, 80, 120, 160, 255 - 200, 240]
+///     .map(|v| ColorRGB8(v, v, v))
+///     .to_vec();
+/// let rows: Vec<Vec<u8>> = (0..20).map(|_| (0..7).collect()).collect();
+/// assert_eq!(vec![5..6], detect_inverted_palette(&pal, &rows));
+/// ```
+pub fn detect_inverted_palette(pal: &Palette, rows: &[Vec<u8>]) -> Vec<Range<usize>> {
+    // Liczności sąsiedztw (w poziomie i pionie) par różnych indeksów
+    let mut pary = std::collections::HashMap::<(usize, usize), usize>::new();
+    let mut dodaj = |a: u8, b: u8| {
+        if a != b {
+            *pary
+                .entry((a.min(b) as usize, a.max(b) as usize))
+                .or_default() += 1;
+        }
+    };
+    for (y, line) in rows.iter().enumerate() {
+        for (x, idx) in line.iter().enumerate() {
+            if let Some(prawy) = line.get(x + 1) {
+                dodaj(*idx, *prawy);
+            }
+            if let Some(dolny) = rows.get(y + 1).and_then(|l| l.get(x)) {
+                dodaj(*idx, *dolny);
+            }
+        }
+    }
+    let wszystkie: usize = rows.iter().map(|l| l.len()).sum();
+    let mut uzycie = vec![0usize; pal.len().max(256)];
+    for idx in rows.iter().flatten() {
+        uzycie[*idx as usize] += 1;
+    }
+    let min_dowody = (wszystkie / MIN_DOWODY).max(16);
+
+    let mut kolory: Vec<[f64; 3]> = pal
+        .iter()
+        .map(|ColorRGB8(r, g, b)| [*r as f64, *g as f64, *b as f64])
+        .collect();
+    let odleglosc = |a: &[f64; 3], b: &[f64; 3]| {
+        a.iter()
+            .zip(b)
+            .map(|(x, y)| (x - y) * (x - y))
+            .sum::<f64>()
+            .sqrt()
+    };
+    let odwroc = |c: &[f64; 3]| c.map(|v| 255.0 - v);
+
+    // Od największych, żeby przy remisie wybrać cały blok
+    let mut bloki = Vec::new();
+    let mut rozmiar = pal.len().next_power_of_two();
+    while rozmiar > 0 {
+        for start in (0..pal.len()).step_by(rozmiar) {
+            bloki.push(start..(start + rozmiar).min(pal.len()));
+        }
+        rozmiar /= 2;
+    }
+
+    let mut wynik: Vec<Range<usize>> = Vec::new();
+    loop {
+        let mut najlepszy: Option<(f64, Range<usize>)> = None;
+        for blok in &bloki {
+            if 2 * uzycie[blok.clone()].iter().sum::<usize>() >= wszystkie {
+                continue;
+            }
+            let (mut przed, mut po, mut dowody) = (0.0, 0.0, 0);
+            for ((a, b), n) in &pary {
+                if *a >= pal.len() || *b >= pal.len() || blok.contains(a) == blok.contains(b) {
+                    continue;
+                }
+                let (wewn, zewn) = if blok.contains(a) { (a, b) } else { (b, a) };
+                przed += *n as f64 * odleglosc(&kolory[*wewn], &kolory[*zewn]);
+                po += *n as f64 * odleglosc(&odwroc(&kolory[*wewn]), &kolory[*zewn]);
+                dowody += n;
+            }
+            let zysk = przed - po;
+            // Zerowe różnice po odwróceniu (np. obraz czarno-biały) to raczej
+            // przypadek niż naprawa
+            if dowody >= min_dowody
+                && po > 0.0
+                && po * MIN_POPRAWA < przed
+                && najlepszy.as_ref().is_none_or(|(z, _)| zysk > *z)
+            {
+                najlepszy = Some((zysk, blok.clone()));
+            }
+        }
+        let Some((_, blok)) = najlepszy else {
+            break;
+        };
+        for c in &mut kolory[blok.clone()] {
+            *c = odwroc(c);
+        }
+        // Ponowne odwrócenie tego samego bloku cofa poprzednie
+        match wynik.iter().position(|r| *r == blok) {
+            Some(i) => {
+                wynik.remove(i);
+            }
+            None => wynik.push(blok),
+        }
+    }
+
+    wynik.sort_by_key(|r| r.start);
+    wynik
+}
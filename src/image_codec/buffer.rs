@@ -0,0 +1,616 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use eyre::{eyre, Result};
+
+use super::{ColorRGB8, Frame, Pixel};
+
+/// Format pikseli w buforze
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum PixelFormat {
+    RGB8,
+    RGBA8,
+    GRAY8,
+    INDEX8,
+}
+
+impl PixelFormat {
+    pub const ALL: [PixelFormat; 4] = [
+        PixelFormat::RGB8,
+        PixelFormat::RGBA8,
+        PixelFormat::GRAY8,
+        PixelFormat::INDEX8,
+    ];
+
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            PixelFormat::RGB8 => 3,
+            PixelFormat::RGBA8 => 4,
+            PixelFormat::GRAY8 | PixelFormat::INDEX8 => 1,
+        }
+    }
+
+    /// Format, w którym zapisany jest dany piksel
+    pub fn of(pixel: &Pixel) -> PixelFormat {
+        match pixel {
+            Pixel::RGB8(_) => PixelFormat::RGB8,
+            Pixel::RGBA8(..) => PixelFormat::RGBA8,
+            Pixel::GRAY8(_) => PixelFormat::GRAY8,
+            Pixel::INDEX8(_) => PixelFormat::INDEX8,
+        }
+    }
+}
+
+impl fmt::Display for PixelFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Piksel RGBA, 8 bitów na kanał
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ColorRGBA8(pub u8, pub u8, pub u8, pub u8);
+
+/// Piksel w skali szarości
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Gray8(pub u8);
+
+/// Indeks palety
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Index8(pub u8);
+
+/// Typ piksela, którym można czytać i zapisywać [`ImageBuffer`]
+///
+/// Typy konkretne (np. [`ColorRGB8`]) obsługują jeden format, [`Pixel`] -
+/// wszystkie, a format wybierany jest przy tworzeniu bufora.
+pub trait PixelType: Copy {
+    /// Czy typ może reprezentować piksele w tym formacie
+    fn accepts(format: PixelFormat) -> bool;
+
+    /// Odczyt piksela z `format.bytes_per_pixel()` bajtów
+    fn read(format: PixelFormat, bytes: &[u8]) -> Self;
+
+    /// Zapis piksela; `false`, gdy piksel nie pasuje do formatu
+    fn write(self, format: PixelFormat, bytes: &mut [u8]) -> bool;
+}
+
+impl PixelType for ColorRGB8 {
+    fn accepts(format: PixelFormat) -> bool {
+        format == PixelFormat::RGB8
+    }
+
+    fn read(_: PixelFormat, bytes: &[u8]) -> Self {
+        ColorRGB8(bytes[0], bytes[1], bytes[2])
+    }
+
+    fn write(self, _: PixelFormat, bytes: &mut [u8]) -> bool {
+        bytes.copy_from_slice(&[self.0, self.1, self.2]);
+        true
+    }
+}
+
+impl PixelType for ColorRGBA8 {
+    fn accepts(format: PixelFormat) -> bool {
+        format == PixelFormat::RGBA8
+    }
+
+    fn read(_: PixelFormat, bytes: &[u8]) -> Self {
+        ColorRGBA8(bytes[0], bytes[1], bytes[2], bytes[3])
+    }
+
+    fn write(self, _: PixelFormat, bytes: &mut [u8]) -> bool {
+        bytes.copy_from_slice(&[self.0, self.1, self.2, self.3]);
+        true
+    }
+}
+
+impl PixelType for Gray8 {
+    fn accepts(format: PixelFormat) -> bool {
+        format == PixelFormat::GRAY8
+    }
+
+    fn read(_: PixelFormat, bytes: &[u8]) -> Self {
+        Gray8(bytes[0])
+    }
+
+    fn write(self, _: PixelFormat, bytes: &mut [u8]) -> bool {
+        bytes[0] = self.0;
+        true
+    }
+}
+
+impl PixelType for Index8 {
+    fn accepts(format: PixelFormat) -> bool {
+        format == PixelFormat::INDEX8
+    }
+
+    fn read(_: PixelFormat, bytes: &[u8]) -> Self {
+        Index8(bytes[0])
+    }
+
+    fn write(self, _: PixelFormat, bytes: &mut [u8]) -> bool {
+        bytes[0] = self.0;
+        true
+    }
+}
+
+impl PixelType for Pixel {
+    fn accepts(_: PixelFormat) -> bool {
+        true
+    }
+
+    fn read(format: PixelFormat, bytes: &[u8]) -> Self {
+        match format {
+            PixelFormat::RGB8 => Pixel::RGB8(ColorRGB8(bytes[0], bytes[1], bytes[2])),
+            PixelFormat::RGBA8 => Pixel::RGBA8(ColorRGB8(bytes[0], bytes[1], bytes[2]), bytes[3]),
+            PixelFormat::GRAY8 => Pixel::GRAY8(bytes[0]),
+            PixelFormat::INDEX8 => Pixel::INDEX8(bytes[0]),
+        }
+    }
+
+    fn write(self, format: PixelFormat, bytes: &mut [u8]) -> bool {
+        match (self, format) {
+            (Pixel::RGB8(c), PixelFormat::RGB8) => c.write(format, bytes),
+            (Pixel::RGBA8(ColorRGB8(r, g, b), a), PixelFormat::RGBA8) => {
+                ColorRGBA8(r, g, b, a).write(format, bytes)
+            }
+            (Pixel::GRAY8(v), PixelFormat::GRAY8) | (Pixel::INDEX8(v), PixelFormat::INDEX8) => {
+                bytes[0] = v;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Obraz w jednym, ciągłym buforze bajtów
+///
+/// Linie zaczynają się co `stride` bajtów; bajty za ostatnim pikselem linii
+/// (wyrównanie) nie należą do obrazu. `P` określa typ zwracanych pikseli.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::image_codec::{ColorRGB8, ImageBuffer, Pixel, PixelFormat};
+/// let mut img = ImageBuffer::<ColorRGB8>::new(3, 2, PixelFormat::RGB8);
+/// img.put_pixel(2, 1, ColorRGB8(1, 2, 3));
+/// assert_eq!(ColorRGB8(1, 2, 3), img.get_pixel(2, 1));
+/// assert_eq!(&[0, 0, 0, 0, 0, 0, 1, 2, 3], img.row(1));
+/// assert_eq!(18, img.as_bytes().len());
+///
+/// // Ten sam bufor czytany pikselami dowolnego formatu
+/// let img = img.cast::<Pixel>().unwrap();
+/// assert_eq!(Pixel::RGB8(ColorRGB8(1, 2, 3)), img.get_pixel(2, 1));
+///
+/// // Linie wyrównane do 4 bajtów, jak w BMP
+/// let dane = vec![1, 2, 3, 0, 4, 5, 6, 0];
+/// let img = ImageBuffer::<Pixel>::from_raw(3, 2, PixelFormat::GRAY8, 4, dane).unwrap();
+/// assert_eq!(&[4, 5, 6], img.row(1));
+/// assert_eq!(vec![1, 2, 3, 4, 5, 6], img.pixels().map(|p| match p {
+///     Pixel::GRAY8(v) => v,
+///     _ => unreachable!(),
+/// }).collect::<Vec<_>>());
+/// ```
+#[derive(Clone)]
+pub struct ImageBuffer<P> {
+    width: usize,
+    height: usize,
+    stride: usize,
+    format: PixelFormat,
+    data: Vec<u8>,
+    typ: PhantomData<P>,
+}
+
+impl<P: PixelType> ImageBuffer<P> {
+    /// Wyzerowany bufor bez wyrównania linii
+    ///
+    /// # Panics
+    ///
+    /// Gdy `P` nie obsługuje formatu.
+    pub fn new(width: usize, height: usize, format: PixelFormat) -> Self {
+        Self::with_stride(width, height, format, width * format.bytes_per_pixel())
+    }
+
+    /// Wyzerowany bufor o podanej długości linii w bajtach
+    ///
+    /// # Panics
+    ///
+    /// Gdy `P` nie obsługuje formatu albo linia jest krótsza niż piksele.
+    pub fn with_stride(width: usize, height: usize, format: PixelFormat, stride: usize) -> Self {
+        assert!(P::accepts(format), "Pixel type cannot hold {format}");
+        assert!(
+            stride >= width * format.bytes_per_pixel(),
+            "Stride too short"
+        );
+        ImageBuffer {
+            width,
+            height,
+            stride,
+            format,
+            data: vec![0; stride * height],
+            typ: PhantomData,
+        }
+    }
+
+    /// Bufor z gotowych danych; ostatnia linia nie musi mieć wyrównania
+    pub fn from_raw(
+        width: usize,
+        height: usize,
+        format: PixelFormat,
+        stride: usize,
+        mut data: Vec<u8>,
+    ) -> Result<Self> {
+        if !P::accepts(format) {
+            return Err(eyre!("Pixel type cannot hold {format}"));
+        }
+        let linia = width * format.bytes_per_pixel();
+        if stride < linia {
+            return Err(eyre!(
+                "Stride {stride} shorter than {linia} bytes of pixels"
+            ));
+        }
+        let potrzebne = match height {
+            0 => 0,
+            h => stride * (h - 1) + linia,
+        };
+        if data.len() < potrzebne {
+            return Err(eyre!(
+                "Got {} bytes, {width}x{height} {format} needs {potrzebne}",
+                data.len()
+            ));
+        }
+        data.resize(stride * height, 0);
+        Ok(ImageBuffer {
+            width,
+            height,
+            stride,
+            format,
+            data,
+            typ: PhantomData,
+        })
+    }
+
+    /// Bufor wypełniony pikselami zwracanymi przez `f(x, y)`
+    ///
+    /// # Panics
+    ///
+    /// Gdy `P` nie obsługuje formatu albo piksel do niego nie pasuje.
+    pub fn from_fn(
+        width: usize,
+        height: usize,
+        format: PixelFormat,
+        mut f: impl FnMut(usize, usize) -> P,
+    ) -> Self {
+        let mut img = Self::new(width, height, format);
+        let bpp = format.bytes_per_pixel();
+        for y in 0..height {
+            for (x, bajty) in img.row_mut(y).chunks_exact_mut(bpp).enumerate() {
+                assert!(
+                    f(x, y).write(format, bajty),
+                    "Pixel does not match {format}"
+                );
+            }
+        }
+        img
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn format(&self) -> PixelFormat {
+        self.format
+    }
+
+    /// Odległość między początkami linii w bajtach
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Wszystkie bajty bufora razem z wyrównaniem linii
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn into_raw(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Bajty pikseli linii `y`, bez wyrównania
+    pub fn row(&self, y: usize) -> &[u8] {
+        self.as_view().row(y)
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [u8] {
+        assert!(y < self.height, "Row {y} out of {}", self.height);
+        let poczatek = y * self.stride;
+        let bpp = self.format.bytes_per_pixel();
+        &mut self.data[poczatek..poczatek + self.width * bpp]
+    }
+
+    /// Bajty pikseli kolejnych linii
+    pub fn rows(&self) -> impl Iterator<Item = &[u8]> + '_ {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    /// # Panics
+    ///
+    /// Gdy współrzędne są poza obrazem.
+    pub fn get_pixel(&self, x: usize, y: usize) -> P {
+        self.as_view().get_pixel(x, y)
+    }
+
+    /// # Panics
+    ///
+    /// Gdy współrzędne są poza obrazem albo piksel nie pasuje do formatu
+    /// bufora (np. [`Pixel::GRAY8`] w buforze RGB8).
+    pub fn put_pixel(&mut self, x: usize, y: usize, pixel: P) {
+        assert!(x < self.width, "Column {x} out of {}", self.width);
+        let bpp = self.format.bytes_per_pixel();
+        let format = self.format;
+        let bajty = &mut self.row_mut(y)[x * bpp..(x + 1) * bpp];
+        assert!(pixel.write(format, bajty), "Pixel does not match {format}");
+    }
+
+    /// Piksele linii `y` od lewej
+    pub fn row_pixels(&self, y: usize) -> impl Iterator<Item = P> + '_ {
+        let bpp = self.format.bytes_per_pixel();
+        self.row(y)
+            .chunks_exact(bpp)
+            .map(|b| P::read(self.format, b))
+    }
+
+    /// Wszystkie piksele, linia po linii
+    pub fn pixels(&self) -> impl Iterator<Item = P> + '_ {
+        (0..self.height).flat_map(|y| self.row_pixels(y))
+    }
+
+    /// Wszystkie piksele ze współrzędnymi (x, y)
+    pub fn enumerate_pixels(&self) -> impl Iterator<Item = (usize, usize, P)> + '_ {
+        (0..self.height)
+            .flat_map(move |y| self.row_pixels(y).enumerate().map(move |(x, p)| (x, y, p)))
+    }
+
+    /// Widok całego obrazu
+    pub fn as_view(&self) -> ImageView<'_, P> {
+        ImageView {
+            buffer: self,
+            x: 0,
+            y: 0,
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Widok prostokąta bez kopiowania pikseli
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::image_codec::{Gray8, ImageBuffer, PixelFormat};
+    /// let img = ImageBuffer::from_fn(4, 3, PixelFormat::GRAY8, |x, y| Gray8((10 * y + x) as u8));
+    /// let v = img.view(1, 1, 2, 2).unwrap();
+    /// assert_eq!((2, 2), (v.width(), v.height()));
+    /// assert_eq!(&[21, 22], v.row(1));
+    /// assert_eq!(Gray8(12), v.get_pixel(1, 0));
+    /// assert_eq!(vec![11, 12, 21, 22], v.to_buffer().into_raw());
+    /// assert!(img.view(3, 0, 2, 1).is_err());
+    /// ```
+    pub fn view(
+        &self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Result<ImageView<'_, P>> {
+        self.as_view().view(x, y, width, height)
+    }
+
+    /// Zmienia typ pikseli bez kopiowania danych
+    pub fn cast<Q: PixelType>(self) -> Result<ImageBuffer<Q>> {
+        if !Q::accepts(self.format) {
+            return Err(eyre!("Pixel type cannot hold {}", self.format));
+        }
+        Ok(ImageBuffer {
+            width: self.width,
+            height: self.height,
+            stride: self.stride,
+            format: self.format,
+            data: self.data,
+            typ: PhantomData,
+        })
+    }
+
+    /// Kopia z inną długością linii (np. wyrównaniem do 4 bajtów)
+    pub fn with_new_stride(&self, stride: usize) -> Result<Self> {
+        let linia = self.width * self.format.bytes_per_pixel();
+        if stride < linia {
+            return Err(eyre!(
+                "Stride {stride} shorter than {linia} bytes of pixels"
+            ));
+        }
+        let mut nowy = self.clone();
+        nowy.stride = stride;
+        nowy.data = vec![0; stride * self.height];
+        for y in 0..self.height {
+            nowy.row_mut(y).copy_from_slice(self.row(y));
+        }
+        Ok(nowy)
+    }
+}
+
+impl ImageBuffer<Pixel> {
+    /// Bufor z obrazu w starej postaci - wektora linii pikseli
+    ///
+    /// Format wybierany jest tak, żeby pomieścić wszystkie piksele: RGBA8,
+    /// gdy którykolwiek ma kanał alfa, potem RGB8 i GRAY8. Indeksów nie można
+    /// mieszać z kolorami.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::image_codec::{ColorRGB8, ImageBuffer, Pixel, PixelFormat};
+    /// let frame = vec![
+    ///     vec![Pixel::RGB8(ColorRGB8(1, 2, 3)), Pixel::GRAY8(7)],
+    ///     vec![Pixel::RGBA8(ColorRGB8(4, 5, 6), 128), Pixel::RGB8(ColorRGB8(0, 0, 0))],
+    /// ];
+    /// let img = ImageBuffer::from_frame(&frame).unwrap();
+    /// assert_eq!(PixelFormat::RGBA8, img.format());
+    /// assert_eq!(Pixel::RGBA8(ColorRGB8(7, 7, 7), 255), img.get_pixel(1, 0));
+    /// assert_eq!(frame[1][0], img.get_pixel(0, 1));
+    /// assert_eq!(Pixel::RGBA8(ColorRGB8(0, 0, 0), 255), img.to_frame()[1][1]);
+    ///
+    /// assert!(ImageBuffer::from_frame(&vec![vec![Pixel::INDEX8(0), Pixel::GRAY8(0)]]).is_err());
+    /// ```
+    pub fn from_frame(frame: &Frame) -> Result<Self> {
+        let width = frame.first().map_or(0, |l| l.len());
+        if frame.iter().any(|l| l.len() != width) {
+            return Err(eyre!("Scan lines differ in length"));
+        }
+        let formaty: Vec<_> = PixelFormat::ALL
+            .into_iter()
+            .filter(|f| frame.iter().flatten().any(|p| PixelFormat::of(p) == *f))
+            .collect();
+        let format = match formaty[..] {
+            [] => PixelFormat::RGB8,
+            [f] => f,
+            _ if formaty.contains(&PixelFormat::INDEX8) => {
+                return Err(eyre!("Palette indices mixed with colors"))
+            }
+            _ if formaty.contains(&PixelFormat::RGBA8) => PixelFormat::RGBA8,
+            _ => PixelFormat::RGB8,
+        };
+
+        let mut img = Self::new(width, frame.len(), format);
+        for (y, line) in frame.iter().enumerate() {
+            for (x, p) in line.iter().enumerate() {
+                img.put_pixel(x, y, rozszerz(*p, format));
+            }
+        }
+        Ok(img)
+    }
+
+    /// Obraz w starej postaci - wektor linii pikseli
+    pub fn to_frame(&self) -> Frame {
+        (0..self.height)
+            .map(|y| self.row_pixels(y).collect())
+            .collect()
+    }
+}
+
+/// Zapis piksela w szerszym formacie (szarość do RGB, RGB do RGBA)
+fn rozszerz(p: Pixel, format: PixelFormat) -> Pixel {
+    match (p, format) {
+        (Pixel::GRAY8(v), PixelFormat::RGB8) => Pixel::RGB8(ColorRGB8(v, v, v)),
+        (Pixel::GRAY8(v), PixelFormat::RGBA8) => Pixel::RGBA8(ColorRGB8(v, v, v), 255),
+        (Pixel::RGB8(c), PixelFormat::RGBA8) => Pixel::RGBA8(c, 255),
+        (p, _) => p,
+    }
+}
+
+impl<P: PixelType> PartialEq for ImageBuffer<P> {
+    /// Porównuje piksele; wyrównanie linii nie ma znaczenia
+    fn eq(&self, other: &Self) -> bool {
+        self.width == other.width
+            && self.height == other.height
+            && self.format == other.format
+            && self.rows().eq(other.rows())
+    }
+}
+
+impl<P: PixelType> Eq for ImageBuffer<P> {}
+
+impl<P: PixelType> fmt::Debug for ImageBuffer<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ImageBuffer")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("stride", &self.stride)
+            .field("format", &self.format)
+            .field("rows", &self.rows().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Prostokątny fragment [`ImageBuffer`] bez własnej kopii pikseli
+#[derive(Copy, Clone)]
+pub struct ImageView<'a, P> {
+    buffer: &'a ImageBuffer<P>,
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+impl<'a, P: PixelType> ImageView<'a, P> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn format(&self) -> PixelFormat {
+        self.buffer.format
+    }
+
+    /// Bajty pikseli linii `y` widoku
+    pub fn row(&self, y: usize) -> &'a [u8] {
+        assert!(y < self.height, "Row {y} out of {}", self.height);
+        let bpp = self.buffer.format.bytes_per_pixel();
+        let poczatek = (self.y + y) * self.buffer.stride + self.x * bpp;
+        &self.buffer.data[poczatek..poczatek + self.width * bpp]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [u8]> + '_ {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    pub fn get_pixel(&self, x: usize, y: usize) -> P {
+        assert!(x < self.width, "Column {x} out of {}", self.width);
+        let bpp = self.buffer.format.bytes_per_pixel();
+        P::read(self.buffer.format, &self.row(y)[x * bpp..(x + 1) * bpp])
+    }
+
+    pub fn pixels(&self) -> impl Iterator<Item = P> + '_ {
+        let bpp = self.buffer.format.bytes_per_pixel();
+        self.rows()
+            .flat_map(move |r| r.chunks_exact(bpp).map(|b| P::read(self.buffer.format, b)))
+    }
+
+    /// Fragment widoku; współrzędne względem widoku
+    pub fn view(&self, x: usize, y: usize, width: usize, height: usize) -> Result<Self> {
+        if x + width > self.width || y + height > self.height {
+            return Err(eyre!(
+                "View {width}x{height} at ({x}, {y}) exceeds {}x{}",
+                self.width,
+                self.height
+            ));
+        }
+        Ok(ImageView {
+            buffer: self.buffer,
+            x: self.x + x,
+            y: self.y + y,
+            width,
+            height,
+        })
+    }
+
+    /// Kopia widoku jako osobny bufor
+    pub fn to_buffer(&self) -> ImageBuffer<P> {
+        let bpp = self.buffer.format.bytes_per_pixel();
+        ImageBuffer {
+            width: self.width,
+            height: self.height,
+            stride: self.width * bpp,
+            format: self.buffer.format,
+            data: self.rows().flatten().copied().collect(),
+            typ: PhantomData,
+        }
+    }
+}
@@ -0,0 +1,353 @@
+use eyre::{eyre, Result};
+
+use super::*;
+
+/// Wagi kanałów przy liczeniu jasności (luma)
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Luma {
+    /// ITU-R BT.601 (0.299, 0.587, 0.114) - telewizja SD, JPEG
+    #[default]
+    Bt601,
+    /// ITU-R BT.709 (0.2126, 0.7152, 0.0722) - HDTV, sRGB
+    Bt709,
+}
+
+impl Luma {
+    /// Jasność koloru, zaokrąglona
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::image_codec::convert::Luma;
+    /// use sekurak_hex_gynvael::image_codec::ColorRGB8;
+    /// assert_eq!(76, Luma::Bt601.of(ColorRGB8(255, 0, 0)));
+    /// assert_eq!(54, Luma::Bt709.of(ColorRGB8(255, 0, 0)));
+    /// assert_eq!(255, Luma::Bt709.of(ColorRGB8(255, 255, 255)));
+    /// ```
+    pub fn of(&self, ColorRGB8(r, g, b): ColorRGB8) -> u8 {
+        let (wr, wg, wb) = match self {
+            Luma::Bt601 => (0.299, 0.587, 0.114),
+            Luma::Bt709 => (0.2126, 0.7152, 0.0722),
+        };
+        (wr * r as f64 + wg * g as f64 + wb * b as f64).round() as u8
+    }
+}
+
+impl Image {
+    /// Konwertuje obraz do innego formatu pikseli (szarość według BT.601)
+    ///
+    /// Zob. [`Image::convert_with`].
+    pub fn convert(&self, format: PixelFormat) -> Result<Image> {
+        self.convert_with(format, Luma::default())
+    }
+
+    /// Konwertuje obraz do innego formatu pikseli
+    ///
+    /// * indeksy są najpierw zamieniane na kolory z palety,
+    /// * szarość liczona jest jako jasność (`luma`), kanał alfa jest pomijany,
+    /// * brakujący kanał alfa dostaje wartość 255 (nieprzezroczysty),
+    /// * do INDEX8 szarość trafia z paletą 256 odcieni, a kolory z paletą
+    ///   zbudowaną z kolorów obrazu (najwyżej 256).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::image_codec::bmp::read_bmp;
+    /// use sekurak_hex_gynvael::image_codec::convert::Luma;
+    /// use sekurak_hex_gynvael::image_codec::{ColorRGB8, Pixel, PixelFormat};
+    /// let img = read_bmp("data/bmp/rgb24.bmp").unwrap();
+    ///
+    /// let szary = img.convert_with(PixelFormat::GRAY8, Luma::Bt709).unwrap();
+    /// assert_eq!(Pixel::GRAY8(182), szary.pixels.get_pixel(1, 0));
+    /// let szary = img.convert(PixelFormat::GRAY8).unwrap();
+    /// assert_eq!(Pixel::GRAY8(150), szary.pixels.get_pixel(1, 0));
+    ///
+    /// let rgba = img.convert(PixelFormat::RGBA8).unwrap();
+    /// assert_eq!(Pixel::RGBA8(ColorRGB8(0, 255, 0), 255), rgba.pixels.get_pixel(1, 0));
+    /// assert_eq!(img, rgba.convert(PixelFormat::RGB8).unwrap());
+    ///
+    /// let indeksowany = img.convert(PixelFormat::INDEX8).unwrap();
+    /// assert_eq!(6, indeksowany.palette.as_ref().unwrap().len());
+    /// assert_eq!(img, indeksowany.convert(PixelFormat::RGB8).unwrap());
+    ///
+    /// let img = read_bmp("data/bmp/index1.bmp").unwrap();
+    /// let szary = img.convert(PixelFormat::GRAY8).unwrap();
+    /// assert_eq!(Pixel::GRAY8(255), szary.pixels.get_pixel(0, 0));
+    /// assert_eq!(None, szary.palette);
+    /// let indeksowany = szary.convert(PixelFormat::INDEX8).unwrap();
+    /// assert_eq!(Pixel::INDEX8(255), indeksowany.pixels.get_pixel(0, 0));
+    /// assert_eq!(256, indeksowany.palette.unwrap().len());
+    /// ```
+    pub fn convert_with(&self, format: PixelFormat, luma: Luma) -> Result<Image> {
+        let zrodlo = self.pixels.format();
+        if zrodlo == format {
+            return Ok(self.clone());
+        }
+        if zrodlo == PixelFormat::INDEX8 && self.palette.is_none() {
+            return Err(eyre!("Indexed image has no palette"));
+        }
+
+        match (zrodlo, format) {
+            (PixelFormat::GRAY8, PixelFormat::INDEX8) => {
+                let pixels = self.map_pixels(format, |_, _, p| match p {
+                    Pixel::GRAY8(v) => Ok(Pixel::INDEX8(v)),
+                    p => Ok(p),
+                })?;
+                Ok(Image {
+                    palette: Some((0..=255).map(|i| ColorRGB8(i, i, i)).collect()),
+                    pixels,
+                })
+            }
+            (_, PixelFormat::INDEX8) => {
+                let rgb = self.convert_with(PixelFormat::RGB8, luma)?;
+                let mut paleta = Palette::new();
+                let mut znane = HashMap::new();
+                for p in rgb.pixels.pixels() {
+                    if let Pixel::RGB8(c) = p {
+                        znane.entry(c).or_insert_with(|| {
+                            paleta.push(c);
+                            paleta.len()
+                        });
+                    }
+                }
+                if paleta.len() > 256 {
+                    return Err(eyre!("Image has {} colors, more than 256", paleta.len()));
+                }
+                rgb.to_indexed(&paleta)
+            }
+            _ => {
+                let img = self.expand_palette()?;
+                let pixels = img.map_pixels(format, |_, _, p| Ok(konwertuj(p, format, luma)))?;
+                Ok(Image {
+                    palette: None,
+                    pixels,
+                })
+            }
+        }
+    }
+
+    /// Mnoży kolory przez kanał alfa (obrazy bez alfy są nieprzezroczyste i
+    /// zwracane bez zmian)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::image_codec::bmp::read_bmp;
+    /// use sekurak_hex_gynvael::image_codec::{ColorRGB8, Pixel};
+    /// let img = read_bmp("data/bmp/bgra32.bmp").unwrap();
+    /// let pm = img.premultiply();
+    /// assert_eq!(Pixel::RGBA8(ColorRGB8(0, 0, 0), 0), pm.pixels.get_pixel(2, 0));
+    /// assert_eq!(Pixel::RGBA8(ColorRGB8(2, 7, 11), 32), pm.pixels.get_pixel(2, 1));
+    ///
+    /// // Powrót jest stratny dla małych wartości alfa
+    /// let un = pm.unpremultiply();
+    /// assert_eq!(Pixel::RGBA8(ColorRGB8(16, 56, 88), 32), un.pixels.get_pixel(2, 1));
+    /// assert_eq!(img.pixels.get_pixel(0, 0), un.pixels.get_pixel(0, 0));
+    /// ```
+    pub fn premultiply(&self) -> Image {
+        self.map_alpha(|c, a| ((c as u32 * a as u32 + 127) / 255) as u8)
+    }
+
+    /// Dzieli kolory przez kanał alfa - odwrotność [`Image::premultiply`]
+    pub fn unpremultiply(&self) -> Image {
+        self.map_alpha(|c, a| match a {
+            0 => 0,
+            a => ((c as u32 * 255 + a as u32 / 2) / a as u32).min(255) as u8,
+        })
+    }
+
+    /// Rozdziela obraz na kanały - obrazy GRAY8 w kolejności R, G, B (i A)
+    ///
+    /// Obraz szary daje jeden kanał, indeksy są zamieniane na kolory z palety.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::image_codec::bmp::read_bmp;
+    /// use sekurak_hex_gynvael::image_codec::{Image, Pixel, PixelFormat};
+    /// let img = read_bmp("data/bmp/bgra32.bmp").unwrap();
+    /// let kanaly = img.split().unwrap();
+    /// assert_eq!(4, kanaly.len());
+    /// assert_eq!(&[0x12, 0x34, 0x56, 32], &kanaly.iter().map(|k| k.pixels.row(1)[2]).collect::<Vec<_>>()[..]);
+    /// assert_eq!(img, Image::merge(&kanaly).unwrap());
+    ///
+    /// let rgb = Image::merge(&kanaly[..3]).unwrap();
+    /// assert_eq!(PixelFormat::RGB8, rgb.pixels.format());
+    /// let bgr = Image::merge(&[kanaly[2].clone(), kanaly[1].clone(), kanaly[0].clone()]).unwrap();
+    /// assert_eq!(&[0x56, 0x34, 0x12], &bgr.pixels.row(1)[6..]);
+    ///
+    /// assert!(Image::merge(&kanaly[..2]).is_err());
+    /// ```
+    pub fn split(&self) -> Result<Vec<Image>> {
+        let img = self.expand_palette()?;
+        let bpp = img.pixels.format().bytes_per_pixel();
+        if img.pixels.format() == PixelFormat::INDEX8 {
+            return Err(eyre!("Indexed image has no palette"));
+        }
+        (0..bpp)
+            .map(|k| {
+                let mut kanal = ImageBuffer::new(img.width(), img.height(), PixelFormat::GRAY8);
+                for y in 0..img.height() {
+                    let zrodlo = img.pixels.row(y).iter().skip(k).step_by(bpp);
+                    for (b, v) in kanal.row_mut(y).iter_mut().zip(zrodlo) {
+                        *b = *v;
+                    }
+                }
+                Ok(Image {
+                    palette: None,
+                    pixels: kanal,
+                })
+            })
+            .collect()
+    }
+
+    /// Składa obraz z kanałów GRAY8 tej samej wielkości: 1 - szary, 3 - RGB8,
+    /// 4 - RGBA8 (zob. [`Image::split`])
+    pub fn merge(channels: &[Image]) -> Result<Image> {
+        let format = match channels.len() {
+            1 => PixelFormat::GRAY8,
+            3 => PixelFormat::RGB8,
+            4 => PixelFormat::RGBA8,
+            n => return Err(eyre!("Cannot merge {n} channels")),
+        };
+        let (width, height) = (channels[0].width(), channels[0].height());
+        for kanal in channels {
+            if kanal.pixels.format() != PixelFormat::GRAY8 {
+                return Err(eyre!("Channel is {}, not GRAY8", kanal.pixels.format()));
+            }
+            if (kanal.width(), kanal.height()) != (width, height) {
+                return Err(eyre!("Channels differ in size"));
+            }
+        }
+
+        let mut pixels = ImageBuffer::new(width, height, format);
+        for y in 0..height {
+            let linia = pixels.row_mut(y);
+            for (k, kanal) in channels.iter().enumerate() {
+                let cel = linia.iter_mut().skip(k).step_by(channels.len());
+                for (b, v) in cel.zip(kanal.pixels.row(y)) {
+                    *b = *v;
+                }
+            }
+        }
+        Ok(Image {
+            palette: None,
+            pixels,
+        })
+    }
+
+    /// Negatyw; kanał alfa bez zmian, w obrazach z paletą zmieniana jest paleta
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::image_codec::bmp::read_bmp;
+    /// use sekurak_hex_gynvael::image_codec::{ColorRGB8, Pixel};
+    /// let img = read_bmp("data/bmp/bgra32.bmp").unwrap();
+    /// let neg = img.invert();
+    /// assert_eq!(Pixel::RGBA8(ColorRGB8(0, 255, 255), 255), neg.pixels.get_pixel(0, 0));
+    /// assert_eq!(img, neg.invert());
+    ///
+    /// let img = read_bmp("data/bmp/index1.bmp").unwrap();
+    /// let neg = img.invert();
+    /// assert_eq!(img.pixels, neg.pixels);
+    /// assert_eq!(Some(vec![ColorRGB8(255, 255, 255), ColorRGB8(0, 0, 0)]), neg.palette);
+    /// ```
+    pub fn invert(&self) -> Image {
+        self.map_channels(|v| 255 - v)
+    }
+
+    /// Progowanie każdego kanału koloru: wartości od `level` w górę dają 255,
+    /// pozostałe 0
+    ///
+    /// Dla progowania jasności najpierw trzeba zamienić obraz na GRAY8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::image_codec::bmp::read_bmp;
+    /// use sekurak_hex_gynvael::image_codec::{ColorRGB8, Pixel, PixelFormat};
+    /// let img = read_bmp("data/bmp/rgb24.bmp").unwrap();
+    /// let prog = img.threshold(0x30);
+    /// assert_eq!(Pixel::RGB8(ColorRGB8(0, 255, 255)), prog.pixels.get_pixel(2, 1));
+    ///
+    /// let prog = img.convert(PixelFormat::GRAY8).unwrap().threshold(128);
+    /// assert_eq!(&[0, 255, 0], prog.pixels.row(0));
+    /// ```
+    pub fn threshold(&self, level: u8) -> Image {
+        self.map_channels(|v| if v >= level { 255 } else { 0 })
+    }
+
+    /// Korekcja gamma każdego kanału koloru: `255 * (v / 255) ^ (1 / gamma)`;
+    /// `gamma` większa od 1 rozjaśnia obraz
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::image_codec::bmp::read_bmp;
+    /// use sekurak_hex_gynvael::image_codec::{ColorRGB8, Pixel};
+    /// let img = read_bmp("data/bmp/rgb24.bmp").unwrap();
+    /// let jasny = img.gamma(2.2);
+    /// assert_eq!(Pixel::RGB8(ColorRGB8(76, 124, 156)), jasny.pixels.get_pixel(2, 1));
+    /// assert_eq!(img, img.gamma(1.0));
+    /// ```
+    pub fn gamma(&self, gamma: f64) -> Image {
+        self.map_channels(|v| (255.0 * (v as f64 / 255.0).powf(1.0 / gamma)).round() as u8)
+    }
+
+    /// Zmienia kanały koloru (bez alfy) według funkcji; w obrazach z paletą
+    /// zmieniana jest tylko paleta
+    fn map_channels(&self, f: impl Fn(u8) -> u8) -> Image {
+        let tablica: Vec<u8> = (0..=255u8).map(&f).collect();
+        let t = |v: &mut u8| *v = tablica[*v as usize];
+        let mut img = self.clone();
+        if let Some(paleta) = &mut img.palette {
+            for ColorRGB8(r, g, b) in paleta {
+                [r, g, b].into_iter().for_each(t);
+            }
+            return img;
+        }
+
+        let alfa = img.pixels.format() == PixelFormat::RGBA8;
+        for y in 0..img.height() {
+            for (i, v) in img.pixels.row_mut(y).iter_mut().enumerate() {
+                if !(alfa && i % 4 == 3) {
+                    t(v);
+                }
+            }
+        }
+        img
+    }
+
+    /// Zmienia kanały koloru pikseli RGBA8 funkcją `f(kanał, alfa)`
+    fn map_alpha(&self, f: impl Fn(u8, u8) -> u8) -> Image {
+        let mut img = self.clone();
+        if img.pixels.format() != PixelFormat::RGBA8 {
+            return img;
+        }
+        for y in 0..img.height() {
+            for px in img.pixels.row_mut(y).chunks_exact_mut(4) {
+                let a = px[3];
+                for c in &mut px[..3] {
+                    *c = f(*c, a);
+                }
+            }
+        }
+        img
+    }
+}
+
+/// Konwersja piksela koloru lub szarości do innego formatu
+fn konwertuj(p: Pixel, format: PixelFormat, luma: Luma) -> Pixel {
+    let (kolor, alfa) = match p {
+        Pixel::RGB8(c) => (c, 255),
+        Pixel::RGBA8(c, a) => (c, a),
+        Pixel::GRAY8(v) | Pixel::INDEX8(v) => (ColorRGB8(v, v, v), 255),
+    };
+    match (p, format) {
+        (Pixel::GRAY8(v), PixelFormat::GRAY8) => Pixel::GRAY8(v),
+        (_, PixelFormat::GRAY8) => Pixel::GRAY8(luma.of(kolor)),
+        (_, PixelFormat::RGBA8) => Pixel::RGBA8(kolor, alfa),
+        _ => Pixel::RGB8(kolor),
+    }
+}
@@ -1,10 +1,18 @@
 pub mod bmp;
+pub mod bmp_repair;
+pub mod buffer;
+pub mod convert;
 pub mod raw;
+pub mod transform;
 
-use eyre::Result;
+use std::collections::HashMap;
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-pub struct ColorRGB8(u8, u8, u8);
+use eyre::{eyre, Result};
+
+pub use buffer::{ColorRGBA8, Gray8, ImageBuffer, ImageView, Index8, PixelFormat, PixelType};
+
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ColorRGB8(pub u8, pub u8, pub u8);
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum Pixel {
@@ -20,12 +28,188 @@ pub type Palette = Vec<ColorRGB8>;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Image {
-    pub x_size: usize,
-    pub y_size: usize,
     pub palette: Option<Palette>,
-    pub pixels: Frame,
+    pub pixels: ImageBuffer<Pixel>,
 }
 
 pub trait Validate {
     fn validate(&self) -> Result<()>;
 }
+
+impl Image {
+    /// Obraz z pikseli w starej postaci (wektora linii), zob.
+    /// [`ImageBuffer::from_frame`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::image_codec::{ColorRGB8, Image, Pixel, PixelFormat};
+    /// let frame = vec![vec![Pixel::RGB8(ColorRGB8(1, 2, 3)); 3]; 2];
+    /// let img = Image::from_frame(&frame, None).unwrap();
+    /// assert_eq!((3, 2), (img.width(), img.height()));
+    /// assert_eq!(PixelFormat::RGB8, img.pixels.format());
+    /// assert_eq!(frame, img.to_frame());
+    /// ```
+    pub fn from_frame(frame: &Frame, palette: Option<Palette>) -> Result<Image> {
+        Ok(Image {
+            palette,
+            pixels: ImageBuffer::from_frame(frame)?,
+        })
+    }
+
+    /// Piksele w starej postaci - wektor linii
+    pub fn to_frame(&self) -> Frame {
+        self.pixels.to_frame()
+    }
+
+    pub fn width(&self) -> usize {
+        self.pixels.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.pixels.height()
+    }
+
+    /// Zamienia indeksy palety na kolory RGB8; obraz bez palety jest zwracany
+    /// bez zmian
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::image_codec::bmp::read_bmp;
+    /// use sekurak_hex_gynvael::image_codec::{ColorRGB8, Pixel};
+    /// let img = read_bmp("data/bmp/index1.bmp").unwrap().expand_palette().unwrap();
+    /// assert_eq!(None, img.palette);
+    /// assert_eq!(Pixel::RGB8(ColorRGB8(255, 255, 255)), img.pixels.get_pixel(0, 0));
+    /// assert_eq!(Pixel::RGB8(ColorRGB8(0, 0, 0)), img.pixels.get_pixel(1, 0));
+    /// ```
+    pub fn expand_palette(&self) -> Result<Image> {
+        let Some(palette) = &self.palette else {
+            return Ok(self.clone());
+        };
+        let pixels = self.map_pixels(PixelFormat::RGB8, |x, y, p| match p {
+            Pixel::INDEX8(i) => palette
+                .get(i as usize)
+                .map(|c| Pixel::RGB8(*c))
+                .ok_or(eyre!("Index {i} at ({x}, {y}) beyond palette")),
+            p => Ok(p),
+        })?;
+        Ok(Image {
+            palette: None,
+            pixels,
+        })
+    }
+
+    /// Zamienia kolory RGB8 na indeksy podanej palety
+    ///
+    /// Każdy kolor musi występować w palecie; przy powtórzeniach wybierany jest
+    /// pierwszy wpis. Piksele już będące indeksami są sprawdzane, ale nie
+    /// zmieniane.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::image_codec::bmp::read_bmp;
+    /// use sekurak_hex_gynvael::image_codec::{ColorRGB8, Pixel};
+    /// let img = read_bmp("data/bmp/rgb24.bmp").unwrap();
+    /// let pal = vec![
+    ///     ColorRGB8(0, 0, 0),
+    ///     ColorRGB8(255, 0, 0),
+    ///     ColorRGB8(0, 255, 0),
+    ///     ColorRGB8(0, 0, 255),
+    ///     ColorRGB8(255, 255, 255),
+    ///     ColorRGB8(0x12, 0x34, 0x56),
+    /// ];
+    /// let indeksowany = img.to_indexed(&pal).unwrap();
+    /// assert_eq!(vec![Pixel::INDEX8(4), Pixel::INDEX8(0), Pixel::INDEX8(5)], indeksowany.to_frame()[1]);
+    /// assert_eq!(img, indeksowany.expand_palette().unwrap());
+    ///
+    /// let e = img.to_indexed(&pal[..5].to_vec()).unwrap_err();
+    /// assert_eq!("Color ColorRGB8(18, 52, 86) at (2, 1) not in palette", e.to_string());
+    /// ```
+    pub fn to_indexed(&self, palette: &Palette) -> Result<Image> {
+        if palette.len() > 256 {
+            return Err(eyre!("Palette of {} colors is too large", palette.len()));
+        }
+        let mut indeksy = HashMap::new();
+        for (i, c) in palette.iter().enumerate().rev() {
+            indeksy.insert(*c, i as u8);
+        }
+        let pixels = self.map_pixels(PixelFormat::INDEX8, |x, y, p| match p {
+            Pixel::RGB8(c) => indeksy
+                .get(&c)
+                .map(|i| Pixel::INDEX8(*i))
+                .ok_or(eyre!("Color {c:?} at ({x}, {y}) not in palette")),
+            Pixel::INDEX8(i) if (i as usize) < palette.len() => Ok(p),
+            p => Err(eyre!("Pixel {p:?} at ({x}, {y}) cannot be indexed")),
+        })?;
+        Ok(Image {
+            palette: Some(palette.clone()),
+            pixels,
+        })
+    }
+
+    /// Zmienia kolejność wpisów palety, zachowując wygląd obrazu
+    ///
+    /// Nowy wpis `i` to stary wpis `order[i]`; `order` musi być permutacją
+    /// indeksów palety.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::image_codec::bmp::read_bmp;
+    /// use sekurak_hex_gynvael::image_codec::{ColorRGB8, Pixel};
+    /// let img = read_bmp("data/bmp/index2.bmp").unwrap();
+    /// let nowy = img.reorder_palette(&[3, 2, 1, 0]).unwrap();
+    /// assert_eq!(Some(ColorRGB8(0, 0, 255)), nowy.palette.as_ref().map(|p| p[0]));
+    /// assert_eq!(Pixel::INDEX8(3), nowy.pixels.get_pixel(0, 0));
+    /// assert_eq!(img.expand_palette().unwrap(), nowy.expand_palette().unwrap());
+    ///
+    /// assert!(img.reorder_palette(&[0, 0, 1, 2]).is_err());
+    /// ```
+    pub fn reorder_palette(&self, order: &[usize]) -> Result<Image> {
+        let palette = self.palette.as_ref().ok_or(eyre!("Image has no palette"))?;
+        let mut odwrotna = vec![None; palette.len()];
+        if order.len() != palette.len() {
+            return Err(eyre!("Order does not cover the whole palette"));
+        }
+        for (nowy, stary) in order.iter().enumerate() {
+            match odwrotna.get_mut(*stary) {
+                Some(wpis @ None) => *wpis = Some(nowy as u8),
+                _ => return Err(eyre!("Order is not a permutation of palette entries")),
+            }
+        }
+        let pixels = self.map_pixels(self.pixels.format(), |x, y, p| match p {
+            Pixel::INDEX8(i) => odwrotna
+                .get(i as usize)
+                .map(|n| Pixel::INDEX8(n.unwrap()))
+                .ok_or(eyre!("Index {i} at ({x}, {y}) beyond palette")),
+            p => Ok(p),
+        })?;
+        Ok(Image {
+            palette: Some(order.iter().map(|i| palette[*i]).collect()),
+            pixels,
+        })
+    }
+
+    /// Przetwarza kolejno piksele (z ich współrzędnymi x, y) do nowego bufora
+    /// w podanym formacie
+    fn map_pixels(
+        &self,
+        format: PixelFormat,
+        mut f: impl FnMut(usize, usize, Pixel) -> Result<Pixel>,
+    ) -> Result<ImageBuffer<Pixel>> {
+        let mut wynik = ImageBuffer::new(self.width(), self.height(), format);
+        let bpp = format.bytes_per_pixel();
+        for y in 0..self.height() {
+            let linia = wynik.row_mut(y);
+            for (x, p) in self.pixels.row_pixels(y).enumerate() {
+                let p = f(x, y, p)?;
+                if !p.write(format, &mut linia[x * bpp..(x + 1) * bpp]) {
+                    return Err(eyre!("Pixel {p:?} at ({x}, {y}) does not match {format}"));
+                }
+            }
+        }
+        Ok(wynik)
+    }
+}
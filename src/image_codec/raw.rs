@@ -1,22 +1,322 @@
+use std::fmt;
+use std::str::FromStr;
+
 use super::*;
 use crate::files;
 use color_eyre::eyre::Result;
 use eyre::WrapErr;
+use log::debug;
+
+/// Kolejność i liczba kanałów piksela w pliku
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RawFormat {
+    RGB,
+    BGR,
+    RGBA,
+    ARGB,
+    GRAY8,
+    INDEX8,
+}
+
+impl RawFormat {
+    pub const ALL: [RawFormat; 6] = [
+        RawFormat::RGB,
+        RawFormat::BGR,
+        RawFormat::RGBA,
+        RawFormat::ARGB,
+        RawFormat::GRAY8,
+        RawFormat::INDEX8,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RawFormat::RGB => "rgb",
+            RawFormat::BGR => "bgr",
+            RawFormat::RGBA => "rgba",
+            RawFormat::ARGB => "argb",
+            RawFormat::GRAY8 => "gray8",
+            RawFormat::INDEX8 => "index8",
+        }
+    }
+
+    /// Format bufora obrazu, do którego trafiają piksele
+    pub fn pixel_format(&self) -> PixelFormat {
+        match self {
+            RawFormat::RGB | RawFormat::BGR => PixelFormat::RGB8,
+            RawFormat::RGBA | RawFormat::ARGB => PixelFormat::RGBA8,
+            RawFormat::GRAY8 => PixelFormat::GRAY8,
+            RawFormat::INDEX8 => PixelFormat::INDEX8,
+        }
+    }
+
+    /// Kolejne kanały w pliku jako numery kanałów w buforze obrazu
+    fn kanaly(&self) -> &'static [usize] {
+        match self {
+            RawFormat::RGB => &[0, 1, 2],
+            RawFormat::RGBA => &[0, 1, 2, 3],
+            RawFormat::BGR => &[2, 1, 0],
+            RawFormat::ARGB => &[3, 0, 1, 2],
+            RawFormat::GRAY8 | RawFormat::INDEX8 => &[0],
+        }
+    }
+}
 
-pub fn write_raw(out_file_name: &str, img: &Image) -> Result<()> {
-    let mut f = files::File::create(out_file_name)?;
+impl From<PixelFormat> for RawFormat {
+    /// Naturalny zapis formatu: RGB, RGBA, GRAY8 albo INDEX8
+    fn from(format: PixelFormat) -> Self {
+        match format {
+            PixelFormat::RGB8 => RawFormat::RGB,
+            PixelFormat::RGBA8 => RawFormat::RGBA,
+            PixelFormat::GRAY8 => RawFormat::GRAY8,
+            PixelFormat::INDEX8 => RawFormat::INDEX8,
+        }
+    }
+}
+
+impl FromStr for RawFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RawFormat::ALL
+            .into_iter()
+            .find(|f| f.name() == s.to_ascii_lowercase())
+            .ok_or_else(|| format!("Unknown raw format {s:?}"))
+    }
+}
 
-    for scanlines in &img.pixels {
-        for pixels in scanlines.iter() {
-            match pixels {
-                Pixel::RGB8(ColorRGB8(r, g, b)) => {
-                    let col = [*r, *g, *b];
-                    f.write_exact(&col)?;
+impl fmt::Display for RawFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Opcje zapisu i odczytu surowych pikseli
+#[derive(Clone, Debug)]
+pub struct RawOptions {
+    pub format: RawFormat,
+    /// Osobne płaszczyzny dla każdego kanału (RRR..GGG..BBB..) zamiast
+    /// przeplotu (RGBRGB..)
+    pub planar: bool,
+    /// Wyrównanie długości linii w bajtach; 0 i 1 oznaczają brak wyrównania.
+    /// W układzie płaskim wyrównywana jest każda linia każdej płaszczyzny.
+    pub row_align: usize,
+}
+
+impl Default for RawOptions {
+    fn default() -> Self {
+        RawOptions {
+            format: RawFormat::RGB,
+            planar: false,
+            row_align: 1,
+        }
+    }
+}
+
+impl RawOptions {
+    /// Długość linii w pliku (jednej płaszczyzny w układzie płaskim), razem z
+    /// wyrównaniem
+    pub fn row_size(&self, width: usize) -> usize {
+        let bajty = match self.planar {
+            true => width,
+            false => width * self.format.kanaly().len(),
+        };
+        bajty.next_multiple_of(self.row_align.max(1))
+    }
+
+    /// Rozmiar całego obrazu w pliku
+    pub fn size(&self, width: usize, height: usize) -> usize {
+        let linie = match self.planar {
+            true => height * self.format.kanaly().len(),
+            false => height,
+        };
+        self.row_size(width) * linie
+    }
+}
+
+/// Zapisuje piksele obrazu bez nagłówka (zob. [`encode_raw`])
+pub fn write_raw(out_file_name: &str, img: &Image, options: &RawOptions) -> Result<()> {
+    let data = encode_raw(img, options)?;
+    let mut f = files::File::create(out_file_name)
+        .wrap_err_with(|| format!("Failed to create {out_file_name:?}"))?;
+    f.write_exact(&data)
+}
+
+/// Czyta piksele bez nagłówka; wymiary i układ muszą być podane (zob.
+/// [`decode_raw`])
+pub fn read_raw(
+    in_file_name: &str,
+    width: usize,
+    height: usize,
+    options: &RawOptions,
+) -> Result<Image> {
+    let mut f = files::File::open(in_file_name)
+        .wrap_err_with(|| format!("Failed to open {in_file_name:?}"))?;
+    decode_raw(&f.read_to_end()?, width, height, options)
+}
+
+/// Koduje piksele obrazu do surowych bajtów
+///
+/// Obraz w innym formacie niż format zapisu ([`RawFormat::pixel_format`]) jest
+/// najpierw konwertowany ([`Image::convert`]); kolejność kanałów (np. BGR,
+/// ARGB) zmieniana jest przy zapisie. Paleta nie jest zapisywana.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::image_codec::bmp::read_bmp;
+/// use sekurak_hex_gynvael::image_codec::raw::{decode_raw, encode_raw, RawFormat, RawOptions};
+/// let img = read_bmp("data/bmp/rgb24.bmp").unwrap();
+/// let opcje = RawOptions::default();
+/// let dane = encode_raw(&img, &opcje).unwrap();
+/// assert_eq!([255, 0, 0, 0, 255, 0, 0, 0, 255], dane[..9]);
+///
+/// let opcje = RawOptions { format: RawFormat::BGR, planar: false, row_align: 4 };
+/// let dane = encode_raw(&img, &opcje).unwrap();
+/// assert_eq!(24, dane.len());
+/// assert_eq!([0, 0, 255, 0, 255, 0, 255, 0, 0, 0, 0, 0], dane[..12]);
+///
+/// let opcje = RawOptions { format: RawFormat::RGB, planar: true, row_align: 1 };
+/// let dane = encode_raw(&img, &opcje).unwrap();
+/// assert_eq!([255, 0, 0, 255, 0, 0x12], dane[..6]); // płaszczyzna R
+///
+/// // Konwersja przed zapisem
+/// let opcje = RawOptions { format: RawFormat::ARGB, ..Default::default() };
+/// assert_eq!([255, 255, 0, 0], encode_raw(&img, &opcje).unwrap()[..4]);
+/// let opcje = RawOptions { format: RawFormat::GRAY8, ..Default::default() };
+/// assert_eq!([76, 150, 29], encode_raw(&img, &opcje).unwrap()[..3]);
+///
+/// // Obraz z paletą: same indeksy albo kolory z palety
+/// let img = read_bmp("data/sing_scape.bmp").unwrap();
+/// let opcje = RawOptions { format: RawFormat::INDEX8, ..Default::default() };
+/// let indeksy: Vec<u8> = img.pixels.rows().flatten().copied().collect();
+/// assert_eq!(indeksy, encode_raw(&img, &opcje).unwrap());
+/// let rgb = encode_raw(&img.expand_palette().unwrap(), &RawOptions::default()).unwrap();
+/// assert_eq!(rgb, encode_raw(&img, &RawOptions::default()).unwrap());
+/// ```
+///
+/// Każdy format i układ da się odczytać z powrotem:
+///
+/// ```
+/// use sekurak_hex_gynvael::image_codec::raw::{decode_raw, encode_raw, RawFormat, RawOptions};
+/// use sekurak_hex_gynvael::image_codec::{Image, ImageBuffer};
+/// for format in RawFormat::ALL {
+///     let bpp = format.pixel_format().bytes_per_pixel();
+///     let bajty = (0..5 * 3 * bpp).map(|i| (i * 37) as u8).collect();
+///     let pixels = ImageBuffer::from_raw(5, 3, format.pixel_format(), 5 * bpp, bajty).unwrap();
+///     let img = Image { palette: None, pixels };
+///     for planar in [false, true] {
+///         for row_align in [0, 1, 2, 4, 8] {
+///             let opcje = RawOptions { format, planar, row_align };
+///             let dane = encode_raw(&img, &opcje).unwrap();
+///             assert_eq!(opcje.size(5, 3), dane.len());
+///             assert_eq!(img, decode_raw(&dane, 5, 3, &opcje).unwrap(), "{opcje:?}");
+///         }
+///     }
+/// }
+/// ```
+pub fn encode_raw(img: &Image, options: &RawOptions) -> Result<Vec<u8>> {
+    let format = options.format.pixel_format();
+    let img = &img.convert(format)?;
+
+    let kanaly = options.format.kanaly();
+    let dlugosc = options.row_size(img.width());
+    let mut out = Vec::with_capacity(options.size(img.width(), img.height()));
+    match options.planar {
+        true => {
+            for k in kanaly {
+                for row in img.pixels.rows() {
+                    let poczatek = out.len();
+                    out.extend(row.chunks_exact(kanaly.len()).map(|px| px[*k]));
+                    out.resize(poczatek + dlugosc, 0);
+                }
+            }
+        }
+        false => {
+            for row in img.pixels.rows() {
+                let poczatek = out.len();
+                for px in row.chunks_exact(kanaly.len()) {
+                    out.extend(kanaly.iter().map(|k| px[*k]));
+                }
+                out.resize(poczatek + dlugosc, 0);
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// Dekoduje surowe piksele o podanych wymiarach
+///
+/// Brak wyrównania po ostatniej linii jest akceptowany, nadmiarowe bajty na
+/// końcu są pomijane. Obraz INDEX8 zwracany jest bez palety.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::image_codec::raw::{decode_raw, RawFormat, RawOptions};
+/// use sekurak_hex_gynvael::image_codec::{ColorRGB8, Pixel};
+/// let opcje = RawOptions { format: RawFormat::ARGB, ..Default::default() };
+/// let img = decode_raw(&[128, 1, 2, 3, 255, 4, 5, 6], 2, 1, &opcje).unwrap();
+/// assert_eq!(Pixel::RGBA8(ColorRGB8(1, 2, 3), 128), img.pixels.get_pixel(0, 0));
+/// assert_eq!(Pixel::RGBA8(ColorRGB8(4, 5, 6), 255), img.pixels.get_pixel(1, 0));
+///
+/// let e = decode_raw(&[1, 2, 3], 2, 1, &opcje).unwrap_err();
+/// assert_eq!("Got 3 bytes, 2x1 argb needs 8", e.to_string());
+/// ```
+pub fn decode_raw(data: &[u8], width: usize, height: usize, options: &RawOptions) -> Result<Image> {
+    let kanaly = options.format.kanaly();
+    let dlugosc = options.row_size(width);
+    let linie = options.size(width, height) / dlugosc.max(1);
+    let bez_wyrownania = match options.planar {
+        true => width,
+        false => width * kanaly.len(),
+    };
+    let potrzebne = match linie {
+        0 => 0,
+        n => dlugosc * (n - 1) + bez_wyrownania,
+    };
+    if data.len() < potrzebne {
+        return Err(eyre!(
+            "Got {} bytes, {width}x{height} {} needs {potrzebne}",
+            data.len(),
+            options.format
+        ));
+    }
+    if data.len() > options.size(width, height) {
+        debug!(
+            "Ignoring {} trailing bytes",
+            data.len() - options.size(width, height)
+        );
+    }
+
+    let format = options.format.pixel_format();
+    let mut pixels = ImageBuffer::new(width, height, format);
+    let linia = |n: usize| &data[n * dlugosc..n * dlugosc + bez_wyrownania];
+    for y in 0..height {
+        let row = pixels.row_mut(y);
+        match options.planar {
+            true => {
+                for (p, k) in kanaly.iter().enumerate() {
+                    for (x, b) in linia(p * height + y).iter().enumerate() {
+                        row[x * kanaly.len() + k] = *b;
+                    }
+                }
+            }
+            false => {
+                for (px, plik) in row
+                    .chunks_exact_mut(kanaly.len())
+                    .zip(linia(y).chunks_exact(kanaly.len()))
+                {
+                    for (b, k) in plik.iter().zip(kanaly) {
+                        px[*k] = *b;
+                    }
                 }
-                _ => unimplemented!(),
             }
         }
     }
 
-    Ok(())
+    Ok(Image {
+        palette: None,
+        pixels,
+    })
 }
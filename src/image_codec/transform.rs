@@ -0,0 +1,263 @@
+use eyre::{eyre, Result};
+
+use super::*;
+
+/// Sposób wyliczania pikseli przy zmianie rozmiaru
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Filter {
+    /// Najbliższy piksel
+    #[default]
+    Nearest,
+    /// Interpolacja liniowa między czterema sąsiednimi pikselami
+    Bilinear,
+}
+
+/// Przekształcenia geometryczne; obrazy z paletą zachowują paletę i indeksy
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::image_codec::{Gray8, Image, ImageBuffer, PixelFormat};
+/// // 0 1 2
+/// // 3 4 5
+/// let pixels = ImageBuffer::from_fn(3, 2, PixelFormat::GRAY8, |x, y| Gray8((3 * y + x) as u8));
+/// let img = Image { palette: None, pixels: pixels.cast().unwrap() };
+/// let bajty = |img: Image| img.pixels.rows().flatten().copied().collect::<Vec<u8>>();
+///
+/// assert_eq!(vec![2, 1, 0, 5, 4, 3], bajty(img.flip_horizontal()));
+/// assert_eq!(vec![3, 4, 5, 0, 1, 2], bajty(img.flip_vertical()));
+/// assert_eq!(vec![3, 0, 4, 1, 5, 2], bajty(img.rotate90()));
+/// assert_eq!(vec![5, 4, 3, 2, 1, 0], bajty(img.rotate180()));
+/// assert_eq!(vec![2, 5, 1, 4, 0, 3], bajty(img.rotate270()));
+/// assert_eq!(vec![0, 3, 1, 4, 2, 5], bajty(img.transpose()));
+/// assert_eq!((2, 3), (img.rotate90().width(), img.rotate90().height()));
+///
+/// assert_eq!(img, img.rotate90().rotate90().rotate90().rotate90());
+/// assert_eq!(img.rotate90(), img.transpose().flip_horizontal());
+/// ```
+impl Image {
+    /// Odbicie w poziomie (lewa strona na prawą)
+    pub fn flip_horizontal(&self) -> Image {
+        let w = self.width();
+        self.przemapuj(w, self.height(), |x, y| (w - 1 - x, y))
+    }
+
+    /// Odbicie w pionie (góra na dół)
+    pub fn flip_vertical(&self) -> Image {
+        let h = self.height();
+        self.przemapuj(self.width(), h, |x, y| (x, h - 1 - y))
+    }
+
+    /// Obrót o 90° zgodnie z ruchem wskazówek zegara
+    pub fn rotate90(&self) -> Image {
+        let h = self.height();
+        self.przemapuj(h, self.width(), |x, y| (y, h - 1 - x))
+    }
+
+    /// Obrót o 180°
+    pub fn rotate180(&self) -> Image {
+        let (w, h) = (self.width(), self.height());
+        self.przemapuj(w, h, |x, y| (w - 1 - x, h - 1 - y))
+    }
+
+    /// Obrót o 270° zgodnie z ruchem wskazówek zegara (90° przeciwnie)
+    pub fn rotate270(&self) -> Image {
+        let w = self.width();
+        self.przemapuj(self.height(), w, |x, y| (w - 1 - y, x))
+    }
+
+    /// Zamiana wierszy z kolumnami (odbicie względem przekątnej)
+    pub fn transpose(&self) -> Image {
+        self.przemapuj(self.height(), self.width(), |x, y| (y, x))
+    }
+
+    /// Wycina prostokąt
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::image_codec::bmp::read_bmp;
+    /// let img = read_bmp("data/bmp/index4.bmp").unwrap();
+    /// let wycinek = img.crop(1, 1, 2, 2).unwrap();
+    /// assert_eq!(img.palette, wycinek.palette);
+    /// assert_eq!(&[1, 14], wycinek.pixels.row(0));
+    /// assert!(img.crop(2, 0, 2, 1).is_err());
+    /// ```
+    pub fn crop(&self, x: usize, y: usize, width: usize, height: usize) -> Result<Image> {
+        Ok(Image {
+            palette: self.palette.clone(),
+            pixels: self.pixels.view(x, y, width, height)?.to_buffer(),
+        })
+    }
+
+    /// Dodaje ramkę z pikseli `fill` (w formacie obrazu)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::image_codec::bmp::read_bmp;
+    /// use sekurak_hex_gynvael::image_codec::{ColorRGB8, Pixel};
+    /// let img = read_bmp("data/bmp/index2.bmp").unwrap();
+    /// let ramka = img.pad(1, 0, 2, 1, Pixel::INDEX8(3)).unwrap();
+    /// assert_eq!((10, 3), (ramka.width(), ramka.height()));
+    /// assert_eq!(&[3, 0, 1, 2, 3, 3, 2, 1, 3, 3], ramka.pixels.row(0));
+    /// assert_eq!(&[3; 10], ramka.pixels.row(2));
+    /// assert_eq!(img, ramka.crop(1, 0, 7, 2).unwrap());
+    ///
+    /// assert!(img.pad(1, 1, 1, 1, Pixel::RGB8(ColorRGB8(0, 0, 0))).is_err());
+    /// ```
+    pub fn pad(
+        &self,
+        left: usize,
+        top: usize,
+        right: usize,
+        bottom: usize,
+        fill: Pixel,
+    ) -> Result<Image> {
+        let format = self.pixels.format();
+        let bpp = format.bytes_per_pixel();
+        let mut wypelnienie = vec![0; bpp];
+        if !fill.write(format, &mut wypelnienie) {
+            return Err(eyre!("Fill {fill:?} does not match {format}"));
+        }
+
+        let width = left + self.width() + right;
+        let height = top + self.height() + bottom;
+        let mut pixels = ImageBuffer::new(width, height, format);
+        for y in 0..height {
+            let linia = pixels.row_mut(y);
+            for px in linia.chunks_exact_mut(bpp) {
+                px.copy_from_slice(&wypelnienie);
+            }
+            if (top..top + self.height()).contains(&y) {
+                linia[left * bpp..(left + self.width()) * bpp]
+                    .copy_from_slice(self.pixels.row(y - top));
+            }
+        }
+        Ok(Image {
+            palette: self.palette.clone(),
+            pixels,
+        })
+    }
+
+    /// Zmienia rozmiar obrazu
+    ///
+    /// Indeksów palety nie da się interpolować, więc obrazy INDEX8 zawsze
+    /// skalowane są metodą najbliższego piksela. Kanał alfa interpolowany jest
+    /// jak pozostałe.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::image_codec::transform::Filter;
+    /// use sekurak_hex_gynvael::image_codec::{Gray8, Image, ImageBuffer, PixelFormat};
+    /// let pixels = ImageBuffer::from_fn(2, 1, PixelFormat::GRAY8, |x, _| Gray8(100 * x as u8));
+    /// let img = Image { palette: None, pixels: pixels.cast().unwrap() };
+    ///
+    /// let duzy = img.resize(4, 2, Filter::Nearest).unwrap();
+    /// assert_eq!(&[0, 0, 100, 100], duzy.pixels.row(1));
+    /// let duzy = img.resize(4, 1, Filter::Bilinear).unwrap();
+    /// assert_eq!(&[0, 25, 75, 100], duzy.pixels.row(0));
+    /// assert_eq!(img, img.resize(2, 1, Filter::Bilinear).unwrap());
+    ///
+    /// assert!(img.resize(0, 1, Filter::Nearest).is_err());
+    /// ```
+    pub fn resize(&self, width: usize, height: usize, filter: Filter) -> Result<Image> {
+        let (w, h) = (self.width(), self.height());
+        if width == 0 || height == 0 || w == 0 || h == 0 {
+            return Err(eyre!("Cannot resize {w}x{h} image to {width}x{height}"));
+        }
+
+        let format = self.pixels.format();
+        if filter == Filter::Nearest || format == PixelFormat::INDEX8 {
+            // Środek piksela docelowego w układzie źródła
+            let najblizszy = |x: usize, n: usize, m: usize| ((2 * x + 1) * m / (2 * n)).min(m - 1);
+            return Ok(self.przemapuj(width, height, |x, y| {
+                (najblizszy(x, width, w), najblizszy(y, height, h))
+            }));
+        }
+
+        // Współrzędna w źródle: dwa sąsiednie piksele i waga drugiego
+        let wspolrzedna = |x: usize, n: usize, m: usize| {
+            let s = ((x as f64 + 0.5) * m as f64 / n as f64 - 0.5).clamp(0.0, (m - 1) as f64);
+            let a = s.floor() as usize;
+            (a, (a + 1).min(m - 1), s - a as f64)
+        };
+        let bpp = format.bytes_per_pixel();
+        let mut pixels = ImageBuffer::new(width, height, format);
+        for y in 0..height {
+            let (y0, y1, wy) = wspolrzedna(y, height, h);
+            let (g, d) = (self.pixels.row(y0), self.pixels.row(y1));
+            let linia = pixels.row_mut(y);
+            for x in 0..width {
+                let (x0, x1, wx) = wspolrzedna(x, width, w);
+                for k in 0..bpp {
+                    let v = |linia: &[u8], x: usize| linia[x * bpp + k] as f64;
+                    let gora = v(g, x0) * (1.0 - wx) + v(g, x1) * wx;
+                    let dol = v(d, x0) * (1.0 - wx) + v(d, x1) * wx;
+                    linia[x * bpp + k] = (gora * (1.0 - wy) + dol * wy).round() as u8;
+                }
+            }
+        }
+        Ok(Image {
+            palette: self.palette.clone(),
+            pixels,
+        })
+    }
+
+    /// Odczytuje ciąg pikseli obrazu jako obraz o innej szerokości
+    ///
+    /// Przydaje się, gdy szerokość w nagłówku jest błędna - obraz wygląda wtedy
+    /// na pochylony. Brakujące piksele ostatniej linii są zerowe.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::image_codec::bmp::read_bmp;
+    /// let img = read_bmp("data/bmp/index1.bmp").unwrap(); // 5x3
+    /// let nowy = img.restride(4).unwrap();
+    /// assert_eq!((4, 4), (nowy.width(), nowy.height()));
+    /// assert_eq!(&[1, 0, 1, 0], nowy.pixels.row(0));
+    /// assert_eq!(&[1, 0, 1, 1], nowy.pixels.row(1));
+    /// assert_eq!(&[1, 1, 1, 0], nowy.pixels.row(3));
+    /// assert_eq!(img, nowy.restride(5).unwrap().crop(0, 0, 5, 3).unwrap());
+    ///
+    /// assert!(img.restride(0).is_err());
+    /// ```
+    pub fn restride(&self, width: usize) -> Result<Image> {
+        if width == 0 {
+            return Err(eyre!("Width must not be zero"));
+        }
+        let format = self.pixels.format();
+        let bpp = format.bytes_per_pixel();
+        let height = (self.width() * self.height()).div_ceil(width);
+        let mut dane = self.pixels.rows().flatten().copied().collect::<Vec<_>>();
+        dane.resize(width * height * bpp, 0);
+        Ok(Image {
+            palette: self.palette.clone(),
+            pixels: ImageBuffer::from_raw(width, height, format, width * bpp, dane)?,
+        })
+    }
+
+    /// Nowy obraz, w którym piksel (x, y) pochodzi z piksela `f(x, y)` obrazu
+    fn przemapuj(
+        &self,
+        width: usize,
+        height: usize,
+        f: impl Fn(usize, usize) -> (usize, usize),
+    ) -> Image {
+        let format = self.pixels.format();
+        let bpp = format.bytes_per_pixel();
+        let mut pixels = ImageBuffer::new(width, height, format);
+        for y in 0..height {
+            for (x, px) in pixels.row_mut(y).chunks_exact_mut(bpp).enumerate() {
+                let (sx, sy) = f(x, y);
+                px.copy_from_slice(&self.pixels.row(sy)[sx * bpp..(sx + 1) * bpp]);
+            }
+        }
+        Image {
+            palette: self.palette.clone(),
+            pixels,
+        }
+    }
+}
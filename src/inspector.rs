@@ -0,0 +1,312 @@
+use std::fmt;
+use std::io::SeekFrom;
+
+use eyre::Result;
+
+use crate::conversions::{decode_leb128, decode_vlq128};
+use crate::files::File;
+use crate::timestamps::{DateTime, TimestampError};
+
+/// Ile bajtów od offsetu jest czytanych do inspekcji
+pub const WINDOW: usize = 64;
+
+/// Najdłuższy wyświetlany napis (w znakach)
+const MAX_ZNAKOW: usize = 32;
+
+/// Jedna interpretacja bajtów
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Field {
+    pub name: &'static str,
+    /// Odczytana wartość; None, gdy brakuje bajtów albo dane nie pasują do typu
+    pub value: Option<String>,
+}
+
+/// Wszystkie interpretacje bajtów spod jednego offsetu
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Inspection {
+    pub offset: u64,
+    pub bytes: Vec<u8>,
+    pub fields: Vec<Field>,
+}
+
+impl Inspection {
+    /// Wartość pola o podanej nazwie
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|f| f.name == name)
+            .and_then(|f| f.value.as_deref())
+    }
+}
+
+impl fmt::Display for Inspection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let podglad = &self.bytes[..self.bytes.len().min(16)];
+        writeln!(
+            f,
+            "Offset 0x{:x} ({}): {podglad:02x?}",
+            self.offset, self.offset
+        )?;
+        for pole in &self.fields {
+            writeln!(
+                f,
+                "{:<14} {}",
+                pole.name,
+                pole.value.as_deref().unwrap_or("-")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Czyta bajty spod offsetu i pokazuje je jako wszystkie obsługiwane typy
+///
+/// Pozycja w pliku po wywołaniu jest nieokreślona. Blisko końca pliku
+/// czytane jest mniej niż [`WINDOW`] bajtów, a typy, na które brakuje danych,
+/// nie mają wartości.
+pub fn inspect(plik: &mut File, offset: u64) -> Result<Inspection> {
+    plik.seek(SeekFrom::Start(offset))?;
+    let bytes = plik.read_at_most(WINDOW)?;
+    let fields = inspect_bytes(&bytes);
+    Ok(Inspection {
+        offset,
+        bytes,
+        fields,
+    })
+}
+
+/// Interpretuje początek bufora jako wszystkie obsługiwane typy
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::inspector::inspect_bytes;
+/// let pola = inspect_bytes(&[0xE5, 0x8E, 0x26, 0x00]);
+/// let wartosc = |nazwa| pola.iter().find(|p| p.name == nazwa).unwrap().value.clone();
+/// assert_eq!(Some("229".to_string()), wartosc("u8"));
+/// assert_eq!(Some("-27".to_string()), wartosc("i8"));
+/// assert_eq!(Some("2526949".to_string()), wartosc("u32 LE"));
+/// assert_eq!(Some("624485 (3 bytes)".to_string()), wartosc("LEB128"));
+/// assert_eq!(None, wartosc("u64 LE"));
+///
+/// let pola = inspect_bytes(b"Hi!\0");
+/// let wartosc = |nazwa| pola.iter().find(|p| p.name == nazwa).unwrap().value.clone();
+/// assert_eq!(Some("\"Hi!\"".to_string()), wartosc("ASCII"));
+///
+/// // GUID z nagłówka partycji GPT (typ "EFI System")
+/// let bajty = [
+///     0x28, 0x73, 0x2A, 0xC1, 0x1F, 0xF8, 0xD2, 0x11,
+///     0xBA, 0x4B, 0x00, 0xA0, 0xC9, 0x3E, 0xC9, 0x3B,
+/// ];
+/// let pola = inspect_bytes(&bajty);
+/// let wartosc = |nazwa| pola.iter().find(|p| p.name == nazwa).unwrap().value.clone();
+/// assert_eq!(Some("{c12a7328-f81f-11d2-ba4b-00a0c93ec93b}".to_string()), wartosc("GUID"));
+///
+/// // 2023-10-03 12:34:56 jako czas uniksowy, czas DOS i FILETIME
+/// let pola = inspect_bytes(&1696336496u32.to_le_bytes());
+/// let wartosc = |nazwa| pola.iter().find(|p| p.name == nazwa).unwrap().value.clone();
+/// assert_eq!(Some("2023-10-03 12:34:56".to_string()), wartosc("Unix time32 LE"));
+/// let dos = (43u32 << 25 | 10 << 21 | 3 << 16) | (12 << 11 | 34 << 5 | 28);
+/// let pola = inspect_bytes(&dos.to_le_bytes());
+/// let wartosc = |nazwa| pola.iter().find(|p| p.name == nazwa).unwrap().value.clone();
+/// assert_eq!(Some("2023-10-03 12:34:56".to_string()), wartosc("DOS date/time"));
+/// let pola = inspect_bytes(&133408100960000000u64.to_le_bytes());
+/// let wartosc = |nazwa| pola.iter().find(|p| p.name == nazwa).unwrap().value.clone();
+/// assert_eq!(Some("2023-10-03 12:34:56".to_string()), wartosc("FILETIME"));
+/// ```
+pub fn inspect_bytes(b: &[u8]) -> Vec<Field> {
+    let pole = |name, value| Field { name, value };
+
+    vec![
+        pole("binary", b.first().map(|x| format!("{x:08b}"))),
+        pole("u8", b.first().map(|x| x.to_string())),
+        pole("i8", b.first().map(|x| (*x as i8).to_string())),
+        pole(
+            "u16 LE",
+            tablica(b).map(|t| u16::from_le_bytes(t).to_string()),
+        ),
+        pole(
+            "u16 BE",
+            tablica(b).map(|t| u16::from_be_bytes(t).to_string()),
+        ),
+        pole(
+            "i16 LE",
+            tablica(b).map(|t| i16::from_le_bytes(t).to_string()),
+        ),
+        pole(
+            "i16 BE",
+            tablica(b).map(|t| i16::from_be_bytes(t).to_string()),
+        ),
+        pole(
+            "u32 LE",
+            tablica(b).map(|t| u32::from_le_bytes(t).to_string()),
+        ),
+        pole(
+            "u32 BE",
+            tablica(b).map(|t| u32::from_be_bytes(t).to_string()),
+        ),
+        pole(
+            "i32 LE",
+            tablica(b).map(|t| i32::from_le_bytes(t).to_string()),
+        ),
+        pole(
+            "i32 BE",
+            tablica(b).map(|t| i32::from_be_bytes(t).to_string()),
+        ),
+        pole(
+            "u64 LE",
+            tablica(b).map(|t| u64::from_le_bytes(t).to_string()),
+        ),
+        pole(
+            "u64 BE",
+            tablica(b).map(|t| u64::from_be_bytes(t).to_string()),
+        ),
+        pole(
+            "i64 LE",
+            tablica(b).map(|t| i64::from_le_bytes(t).to_string()),
+        ),
+        pole(
+            "i64 BE",
+            tablica(b).map(|t| i64::from_be_bytes(t).to_string()),
+        ),
+        pole("f32 LE", tablica(b).map(|t| liczba(f32::from_le_bytes(t)))),
+        pole("f32 BE", tablica(b).map(|t| liczba(f32::from_be_bytes(t)))),
+        pole("f64 LE", tablica(b).map(|t| liczba(f64::from_le_bytes(t)))),
+        pole("f64 BE", tablica(b).map(|t| liczba(f64::from_be_bytes(t)))),
+        pole(
+            "LEB128",
+            decode_leb128::<u64>(b)
+                .ok()
+                .map(|(x, n)| format!("{x} ({n} bytes)")),
+        ),
+        pole(
+            "VLQ",
+            decode_vlq128::<u64>(b)
+                .ok()
+                .map(|(x, n)| format!("{x} ({n} bytes)")),
+        ),
+        pole("ASCII", ascii(b)),
+        pole("UTF-8", utf8(b)),
+        pole("UTF-16 LE", utf16(b, u16::from_le_bytes)),
+        pole("UTF-16 BE", utf16(b, u16::from_be_bytes)),
+        pole(
+            "DOS date/time",
+            tablica(b).and_then(|t| data(DateTime::from_dos(u32::from_le_bytes(t)))),
+        ),
+        pole(
+            "Unix time32 LE",
+            tablica(b).and_then(|t| data(DateTime::from_unix(i32::from_le_bytes(t) as i64, 0))),
+        ),
+        pole(
+            "Unix time32 BE",
+            tablica(b).and_then(|t| data(DateTime::from_unix(i32::from_be_bytes(t) as i64, 0))),
+        ),
+        pole(
+            "Unix time64 LE",
+            tablica(b).and_then(|t| data(DateTime::from_unix(i64::from_le_bytes(t), 0))),
+        ),
+        pole(
+            "FILETIME",
+            tablica(b).and_then(|t| data(DateTime::from_filetime(u64::from_le_bytes(t)))),
+        ),
+        pole(
+            "Unix ms64 LE",
+            tablica(b).and_then(|t| data(DateTime::from_unix_millis(i64::from_le_bytes(t)))),
+        ),
+        pole(
+            "HFS+ BE",
+            tablica(b).and_then(|t| data(DateTime::from_hfs_plus(u32::from_be_bytes(t)))),
+        ),
+        pole(
+            "NTP BE",
+            tablica(b).and_then(|t| data(DateTime::from_ntp(u64::from_be_bytes(t)))),
+        ),
+        pole("GUID", tablica(b).map(guid)),
+        pole("UUID", tablica(b).map(uuid)),
+    ]
+}
+
+/// Float w zapisie dziesiętnym, a dla bardzo dużych i małych wartości w
+/// wykładniczym
+fn liczba<F>(x: F) -> String
+where
+    F: fmt::Display + fmt::LowerExp + Into<f64> + Copy,
+{
+    let modul = x.into().abs();
+    if modul == 0.0 || !modul.is_finite() || (1e-4..1e16).contains(&modul) {
+        x.to_string()
+    } else {
+        format!("{x:e}")
+    }
+}
+
+fn tablica<const N: usize>(bajty: &[u8]) -> Option<[u8; N]> {
+    bajty.get(..N)?.try_into().ok()
+}
+
+/// Drukowalne znaki ASCII do pierwszego innego bajtu
+fn ascii(bajty: &[u8]) -> Option<String> {
+    let tekst: String = bajty
+        .iter()
+        .take_while(|b| b.is_ascii_graphic() || **b == b' ')
+        .take(MAX_ZNAKOW)
+        .map(|b| *b as char)
+        .collect();
+    (!tekst.is_empty()).then(|| format!("{tekst:?}"))
+}
+
+/// Poprawne UTF-8 do pierwszego błędu albo znaku sterującego
+fn utf8(bajty: &[u8]) -> Option<String> {
+    let poprawne = match std::str::from_utf8(bajty) {
+        Ok(tekst) => tekst,
+        // Unwrap, bo `valid_up_to` wskazuje koniec poprawnego UTF-8
+        Err(e) => std::str::from_utf8(&bajty[..e.valid_up_to()]).unwrap(),
+    };
+    tekst_bez_sterujacych(poprawne.chars())
+}
+
+fn utf16(bajty: &[u8], konwersja: fn([u8; 2]) -> u16) -> Option<String> {
+    let jednostki = bajty.chunks_exact(2).map(|c| konwersja([c[0], c[1]]));
+    tekst_bez_sterujacych(char::decode_utf16(jednostki).map_while(|c| c.ok()))
+}
+
+fn tekst_bez_sterujacych(znaki: impl Iterator<Item = char>) -> Option<String> {
+    let tekst: String = znaki
+        .take_while(|c| !c.is_control())
+        .take(MAX_ZNAKOW)
+        .collect();
+    (!tekst.is_empty()).then(|| format!("{tekst:?}"))
+}
+
+fn data(data: Result<DateTime, TimestampError>) -> Option<String> {
+    data.ok().map(|d| d.to_string())
+}
+
+/// GUID w zapisie Microsoftu: pierwsze trzy pola little endian, reszta jak leci
+fn guid(b: [u8; 16]) -> String {
+    format!(
+        "{{{:08x}-{:04x}-{:04x}-{:02x}{:02x}-{}}}",
+        u32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+        u16::from_le_bytes([b[4], b[5]]),
+        u16::from_le_bytes([b[6], b[7]]),
+        b[8],
+        b[9],
+        hex(&b[10..])
+    )
+}
+
+/// UUID w zapisie RFC 4122 (cały big endian)
+fn uuid(b: [u8; 16]) -> String {
+    format!(
+        "{}-{}-{}-{}-{}",
+        hex(&b[..4]),
+        hex(&b[4..6]),
+        hex(&b[6..8]),
+        hex(&b[8..10]),
+        hex(&b[10..])
+    )
+}
+
+fn hex(bajty: &[u8]) -> String {
+    bajty.iter().map(|b| format!("{b:02x}")).collect()
+}
@@ -1,12 +1,25 @@
 use std::fmt;
 
+pub mod basen;
+pub mod byte_literals;
+pub mod byte_order;
 pub mod conversions;
 pub mod files;
+pub mod floats;
+pub mod guess;
 pub mod image_codec;
+pub mod inspector;
+pub mod literal;
 pub mod masks;
+pub mod number_view;
+pub mod timestamps;
+pub mod varint;
 
 pub mod unzip;
 
+/// Drukuje liczbę w kilku systemach liczbowych. Pełniejszy raport (różne
+/// reprezentacje znaku, zamiana bajtów, ASCII, float) daje
+/// [`number_view::NumberView`].
 pub fn pokaz_liczbe<N>(liczba: N, nazwa: &str)
 where
     N: fmt::Display + fmt::LowerHex + fmt::Binary + fmt::Octal,
@@ -0,0 +1,271 @@
+use std::fmt;
+
+use num::PrimInt;
+
+/// Błędy parsowania literałów liczbowych
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LiteralError {
+    /// Brak cyfr
+    Empty,
+    /// Znak niebędący cyfrą w danej podstawie; `offset` liczony w znakach
+    InvalidDigit { offset: usize, character: char },
+    /// Podstawa spoza zakresu 2..=36
+    InvalidRadix(u32),
+    /// Wartość nie mieści się w typie docelowym albo w typie z przyrostka
+    Overflow,
+}
+
+impl fmt::Display for LiteralError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LiteralError::Empty => write!(f, "No digits"),
+            LiteralError::InvalidDigit { offset, character } => {
+                write!(f, "Invalid digit {character:?} at offset {offset}")
+            }
+            LiteralError::InvalidRadix(r) => write!(f, "Invalid radix {r}, expected 2-36"),
+            LiteralError::Overflow => write!(f, "Value out of range"),
+        }
+    }
+}
+
+impl std::error::Error for LiteralError {}
+
+/// Przyrostek typu, jak w Ruście (`u8`, `i32`, `usize`...)
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct IntSuffix {
+    pub signed: bool,
+    pub bits: u32,
+}
+
+impl IntSuffix {
+    const NAZWY: [(&'static str, bool, u32); 12] = [
+        ("u8", false, 8),
+        ("u16", false, 16),
+        ("u32", false, 32),
+        ("u64", false, 64),
+        ("u128", false, 128),
+        ("usize", false, usize::BITS),
+        ("i8", true, 8),
+        ("i16", true, 16),
+        ("i32", true, 32),
+        ("i64", true, 64),
+        ("i128", true, 128),
+        ("isize", true, usize::BITS),
+    ];
+
+    /// Odcina przyrostek typu z końca tekstu
+    fn odetnij(tekst: &str) -> (&str, Option<IntSuffix>) {
+        for (nazwa, signed, bits) in Self::NAZWY {
+            if let Some(reszta) = tekst.strip_suffix(nazwa) {
+                return (reszta, Some(IntSuffix { signed, bits }));
+            }
+        }
+        (tekst, None)
+    }
+
+    /// Czy liczba (znak i moduł) mieści się w typie
+    fn miesci(&self, ujemna: bool, modul: u128) -> bool {
+        match (self.signed, ujemna) {
+            (false, true) => modul == 0,
+            (false, false) => modul <= u128::MAX >> (128 - self.bits),
+            (true, false) => modul < 1 << (self.bits - 1),
+            (true, true) => modul <= 1 << (self.bits - 1),
+        }
+    }
+}
+
+/// Rozłożony literał całkowity
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::literal::{IntSuffix, Literal};
+/// let l: Literal = "-0x_80i8".parse().unwrap();
+/// assert!(l.negative);
+/// assert_eq!(0x80, l.magnitude);
+/// assert_eq!(16, l.radix);
+/// assert_eq!(Some(IntSuffix { signed: true, bits: 8 }), l.suffix);
+/// assert_eq!(Ok(-128i8), l.value());
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Literal {
+    pub negative: bool,
+    pub magnitude: u128,
+    pub radix: u32,
+    pub suffix: Option<IntSuffix>,
+}
+
+impl Literal {
+    /// Parsuje literał w podanej podstawie (2..=36), bez przedrostków
+    /// zmieniających podstawę
+    ///
+    /// Dozwolony jest znak na początku i `_` między cyframi. Przyrostek typu
+    /// jest rozpoznawany tylko dla podstaw do 16 - w wyższych `u` oraz `i` są
+    /// cyframi.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::literal::{Literal, LiteralError};
+    /// assert_eq!(Ok(1295), Literal::parse_radix("zz", 36).map(|l| l.magnitude));
+    /// assert_eq!(Ok(-5), Literal::parse_radix("-1_2", 3).unwrap().value::<i32>());
+    /// assert_eq!(Err(LiteralError::InvalidRadix(37)), Literal::parse_radix("1", 37));
+    /// ```
+    pub fn parse_radix(tekst: &str, radix: u32) -> Result<Self, LiteralError> {
+        if !(2..=36).contains(&radix) {
+            return Err(LiteralError::InvalidRadix(radix));
+        }
+        let (negative, cyfry, poczatek) = znak(tekst);
+        Self::cyfry(negative, cyfry, poczatek, radix)
+    }
+
+    /// Wartość w typie docelowym. Przepełnienie jest sprawdzane zarówno
+    /// względem `N`, jak i typu z przyrostka.
+    pub fn value<N: PrimInt>(&self) -> Result<N, LiteralError> {
+        if let Some(przyrostek) = self.suffix {
+            if !przyrostek.miesci(self.negative, self.magnitude) {
+                return Err(LiteralError::Overflow);
+            }
+        }
+        let wynik = if self.negative {
+            // -2^127 mieści się w i128, choć 2^127 już nie
+            let wartosc = 0i128.checked_sub_unsigned(self.magnitude);
+            wartosc.and_then(N::from)
+        } else {
+            N::from(self.magnitude)
+        };
+        wynik.ok_or(LiteralError::Overflow)
+    }
+
+    fn cyfry(
+        negative: bool,
+        cyfry: &str,
+        poczatek: usize,
+        radix: u32,
+    ) -> Result<Self, LiteralError> {
+        let (cyfry, suffix) = if radix <= 16 {
+            IntSuffix::odetnij(cyfry)
+        } else {
+            (cyfry, None)
+        };
+        let mut magnitude = 0u128;
+        let mut jest_cyfra = false;
+        for (i, znak) in cyfry.chars().enumerate() {
+            if znak == '_' {
+                continue;
+            }
+            let cyfra = znak.to_digit(radix).ok_or(LiteralError::InvalidDigit {
+                offset: poczatek + i,
+                character: znak,
+            })?;
+            magnitude = magnitude
+                .checked_mul(radix as u128)
+                .and_then(|m| m.checked_add(cyfra as u128))
+                .ok_or(LiteralError::Overflow)?;
+            jest_cyfra = true;
+        }
+        if !jest_cyfra {
+            return Err(LiteralError::Empty);
+        }
+        Ok(Literal {
+            negative,
+            magnitude,
+            radix,
+            suffix,
+        })
+    }
+}
+
+impl std::str::FromStr for Literal {
+    type Err = LiteralError;
+
+    /// Parsuje literał jak w Ruście (`0x`, `0o`, `0b`, `_`, przyrostki typu)
+    /// albo w stylu asemblera (`$FF`, `0FFh`). Otaczające białe znaki są
+    /// ignorowane.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::literal::Literal;
+    /// let wartosc = |t: &str| t.parse::<Literal>().unwrap().magnitude;
+    /// assert_eq!(7, wartosc("0b111"));
+    /// assert_eq!(73, wartosc("0o111"));
+    /// assert_eq!(273, wartosc("0x111"));
+    /// assert_eq!(256, wartosc("0b_1_0000_0000"));
+    /// assert_eq!(255, wartosc("$FF"));
+    /// assert_eq!(255, wartosc("0FFh"));
+    /// assert_eq!(0x1A, wartosc("1Ah"));
+    /// assert_eq!(1000, wartosc("1_000u16"));
+    /// ```
+    fn from_str(tekst: &str) -> Result<Self, Self::Err> {
+        let przyciety = tekst.trim_start();
+        let pominiete = tekst.chars().count() - przyciety.chars().count();
+        let (negative, reszta, znak_dl) = znak(przyciety.trim_end());
+        let poczatek = pominiete + znak_dl;
+
+        let przedrostki = [
+            ("0x", 16),
+            ("0X", 16),
+            ("0o", 8),
+            ("0O", 8),
+            ("0b", 2),
+            ("0B", 2),
+        ];
+        for (przedrostek, radix) in przedrostki {
+            if let Some(cyfry) = reszta.strip_prefix(przedrostek) {
+                return Self::cyfry(negative, cyfry, poczatek + 2, radix);
+            }
+        }
+        if let Some(cyfry) = reszta.strip_prefix('$') {
+            return Self::cyfry(negative, cyfry, poczatek + 1, 16);
+        }
+        // Zapis asemblerowy musi zaczynać się cyfrą, żeby nie mylić go z
+        // identyfikatorem (stąd "0FFh" zamiast "FFh")
+        if let Some(cyfry) = reszta.strip_suffix(['h', 'H']) {
+            if cyfry.starts_with(|c: char| c.is_ascii_digit()) {
+                return Self::cyfry(negative, cyfry, poczatek, 16);
+            }
+        }
+        Self::cyfry(negative, reszta, poczatek, 10)
+    }
+}
+
+/// Odcina znak; zwraca też liczbę odciętych znaków
+fn znak(tekst: &str) -> (bool, &str, usize) {
+    if let Some(reszta) = tekst.strip_prefix('-') {
+        (true, reszta, 1)
+    } else if let Some(reszta) = tekst.strip_prefix('+') {
+        (false, reszta, 1)
+    } else {
+        (false, tekst, 0)
+    }
+}
+
+/// Parsuje literał całkowity i sprawdza, czy mieści się w typie `N`
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::literal::{parse_int, LiteralError};
+/// assert_eq!(Ok(0x123u64), parse_int("0x1_23"));
+/// assert_eq!(Ok(-128i8), parse_int("-0x80"));
+/// assert_eq!(Ok(i128::MIN), parse_int("-170141183460469231731687303715884105728"));
+/// assert_eq!(Err(LiteralError::Overflow), parse_int::<u8>("256"));
+/// assert_eq!(Err(LiteralError::Overflow), parse_int::<u32>("-1"));
+/// // Przyrostek zawęża zakres niezależnie od typu docelowego
+/// assert_eq!(Err(LiteralError::Overflow), parse_int::<u64>("300u8"));
+/// assert_eq!(
+///     Err(LiteralError::InvalidDigit { offset: 4, character: 'g' }),
+///     parse_int::<u32>("0x12g")
+/// );
+/// assert_eq!(Err(LiteralError::Empty), parse_int::<u32>("0x_"));
+/// ```
+pub fn parse_int<N: PrimInt>(tekst: &str) -> Result<N, LiteralError> {
+    tekst.parse::<Literal>()?.value()
+}
+
+/// Parsuje literał całkowity w podanej podstawie (2..=36) i sprawdza, czy
+/// mieści się w typie `N`
+pub fn parse_int_radix<N: PrimInt>(tekst: &str, radix: u32) -> Result<N, LiteralError> {
+    Literal::parse_radix(tekst.trim(), radix)?.value()
+}
@@ -0,0 +1,336 @@
+use std::fmt;
+
+use num::PrimInt;
+
+use crate::floats::{FloatFormat, FloatParts};
+
+/// Szerokości typów całkowitych obsługiwanych przez [`NumberView`]
+pub const WIDTHS: [u32; 5] = [8, 16, 32, 64, 128];
+
+/// Jedna wartość widziana na wiele sposobów - bez znaku, ze znakiem w różnych
+/// reprezentacjach, po zamianie bajtów, jako tekst i jako liczba
+/// zmiennoprzecinkowa
+///
+/// Przechowuje surowe bity i szerokość typu; wszystkie interpretacje liczone
+/// są z nich na żądanie. `Display` drukuje pełny raport.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::number_view::NumberView;
+/// let liczba = NumberView::from_int(-2_i8);
+/// assert_eq!(8, liczba.width());
+/// assert_eq!(0xFE, liczba.unsigned());
+/// assert_eq!(-2, liczba.twos_complement());
+/// assert_eq!(-1, liczba.ones_complement());
+/// assert_eq!(-126, liczba.sign_magnitude());
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct NumberView {
+    bity: u128,
+    szerokosc: u32,
+}
+
+impl NumberView {
+    /// Tworzy widok z surowych bitów. Bity powyżej szerokości są obcinane.
+    ///
+    /// returns: None, gdy szerokość nie należy do [`WIDTHS`]
+    pub fn new(bity: u128, szerokosc: u32) -> Option<Self> {
+        if !WIDTHS.contains(&szerokosc) {
+            return None;
+        }
+        Some(NumberView {
+            bity: bity & maska(szerokosc),
+            szerokosc,
+        })
+    }
+
+    /// Tworzy widok z dowolnej liczby całkowitej, szerokość jest brana z typu
+    pub fn from_int<N: PrimInt>(liczba: N) -> Self {
+        let szerokosc = N::zero().count_zeros();
+        // Liczby ujemne nie mieszczą się w u128, ale w i128 już tak
+        let bity = liczba
+            .to_u128()
+            .or_else(|| liczba.to_i128().map(|l| l as u128))
+            .unwrap_or_default();
+        NumberView {
+            bity: bity & maska(szerokosc),
+            szerokosc,
+        }
+    }
+
+    /// Tworzy widok z bajtów little endian (1, 2, 4, 8 lub 16 bajtów)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::number_view::NumberView;
+    /// let liczba = NumberView::from_le_bytes(&[0xAE, 0xF6]).unwrap();
+    /// assert_eq!(0xF6AE, liczba.unsigned());
+    /// assert_eq!(liczba.swap_bytes(), NumberView::from_be_bytes(&[0xAE, 0xF6]).unwrap());
+    /// assert_eq!(None, NumberView::from_le_bytes(&[1, 2, 3]));
+    /// ```
+    pub fn from_le_bytes(bajty: &[u8]) -> Option<Self> {
+        let bity = bajty.iter().rfold(0_u128, |acc, b| (acc << 8) | *b as u128);
+        Self::new(bity, bajty.len() as u32 * 8)
+    }
+
+    /// Tworzy widok z bajtów big endian (1, 2, 4, 8 lub 16 bajtów)
+    pub fn from_be_bytes(bajty: &[u8]) -> Option<Self> {
+        let bity = bajty.iter().fold(0_u128, |acc, b| (acc << 8) | *b as u128);
+        Self::new(bity, bajty.len() as u32 * 8)
+    }
+
+    /// Szerokość w bitach
+    pub fn width(&self) -> u32 {
+        self.szerokosc
+    }
+
+    /// Wartość bez znaku
+    pub fn unsigned(&self) -> u128 {
+        self.bity
+    }
+
+    fn bit_znaku(&self) -> bool {
+        (self.bity >> (self.szerokosc - 1)) & 1 == 1
+    }
+
+    /// Wartość ze znakiem w kodzie uzupełnień do dwóch (tak jak typy `iXX`)
+    pub fn twos_complement(&self) -> i128 {
+        // Przesunięcie bitu znaku na pozycję 127 i arytmetyczne z powrotem
+        let przesuniecie = 128 - self.szerokosc;
+        ((self.bity << przesuniecie) as i128) >> przesuniecie
+    }
+
+    /// Wartość ze znakiem w kodzie uzupełnień do jedności. Ujemne zero (same
+    /// jedynki) daje 0.
+    pub fn ones_complement(&self) -> i128 {
+        if self.bit_znaku() {
+            -((!self.bity & maska(self.szerokosc)) as i128)
+        } else {
+            self.bity as i128
+        }
+    }
+
+    /// Wartość w kodzie znak-moduł. Ujemne zero (sam bit znaku) daje 0.
+    pub fn sign_magnitude(&self) -> i128 {
+        let modul = (self.bity & (maska(self.szerokosc) >> 1)) as i128;
+        if self.bit_znaku() {
+            -modul
+        } else {
+            modul
+        }
+    }
+
+    /// Młodsze bity wartości jako węższy typ
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::number_view::NumberView;
+    /// let liczba = NumberView::from_int(0x1234_u16);
+    /// assert_eq!(Some(NumberView::from_int(0x34_u8)), liczba.truncate(8));
+    /// assert_eq!(None, liczba.truncate(32));
+    /// ```
+    pub fn truncate(&self, szerokosc: u32) -> Option<Self> {
+        if szerokosc > self.szerokosc {
+            return None;
+        }
+        Self::new(self.bity, szerokosc)
+    }
+
+    /// Wartość z odwróconą kolejnością bajtów (LE <-> BE)
+    pub fn swap_bytes(&self) -> Self {
+        NumberView {
+            bity: self.bity.swap_bytes() >> (128 - self.szerokosc),
+            szerokosc: self.szerokosc,
+        }
+    }
+
+    /// Bajty wartości little endian
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        self.bity.to_le_bytes()[..self.szerokosc as usize / 8].to_vec()
+    }
+
+    /// Bajty wartości big endian
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        let mut bajty = self.to_le_bytes();
+        bajty.reverse();
+        bajty
+    }
+
+    /// Zapis dwójkowy na pełnej szerokości, z "kładką" co 4 cyfry
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::number_view::NumberView;
+    /// assert_eq!("0b_0000_0001_0000_0000", NumberView::from_int(256_u16).bin_grouped());
+    /// assert_eq!("0x_0001_0000", NumberView::from_int(65536_i32).hex_grouped());
+    /// assert_eq!("0o_177_777", NumberView::from_int(u16::MAX).oct_grouped());
+    /// assert_eq!("-2_147_483_648", NumberView::from_int(i32::MIN).dec_grouped());
+    /// ```
+    pub fn bin_grouped(&self) -> String {
+        let cyfry = format!("{:0w$b}", self.bity, w = self.szerokosc as usize);
+        format!("0b_{}", grupuj(&cyfry, 4))
+    }
+
+    /// Zapis szesnastkowy na pełnej szerokości, z "kładką" co 4 cyfry
+    pub fn hex_grouped(&self) -> String {
+        let cyfry = format!("{:0w$x}", self.bity, w = self.szerokosc as usize / 4);
+        format!("0x_{}", grupuj(&cyfry, 4))
+    }
+
+    /// Zapis ósemkowy na pełnej szerokości, z "kładką" co 3 cyfry
+    pub fn oct_grouped(&self) -> String {
+        let cyfry = format!(
+            "{:0w$o}",
+            self.bity,
+            w = self.szerokosc.div_ceil(3) as usize
+        );
+        format!("0o_{}", grupuj(&cyfry, 3))
+    }
+
+    /// Zapis dziesiętny ze znakiem (uzupełnienie do dwóch), z "kładką" co 3
+    /// cyfry
+    pub fn dec_grouped(&self) -> String {
+        let liczba = self.twos_complement();
+        let cyfry = grupuj(&liczba.unsigned_abs().to_string(), 3);
+        if liczba < 0 {
+            format!("-{cyfry}")
+        } else {
+            cyfry
+        }
+    }
+
+    /// Bajty w kolejności little endian jako tekst ASCII (np. FourCC), znaki
+    /// niedrukowalne jako `.`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::number_view::NumberView;
+    /// let riff = NumberView::from_int(0x4646_4952_u32);
+    /// assert_eq!("RIFF", riff.ascii_le());
+    /// assert_eq!("FFIR", riff.ascii_be());
+    /// assert_eq!("..", NumberView::from_int(0x0a00_u16).ascii_le());
+    /// ```
+    pub fn ascii_le(&self) -> String {
+        self.to_le_bytes().into_iter().map(ascii).collect()
+    }
+
+    /// Bajty w kolejności big endian jako tekst ASCII
+    pub fn ascii_be(&self) -> String {
+        self.to_be_bytes().into_iter().map(ascii).collect()
+    }
+
+    /// Bity jako `f32`, jeśli wartość ma 32 bity
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::number_view::NumberView;
+    /// assert_eq!(Some(1.0), NumberView::from_int(0x3F80_0000_u32).as_f32());
+    /// assert_eq!(Some(-2.0), NumberView::from_int(0xC000_0000_0000_0000_u64).as_f64());
+    /// assert_eq!(None, NumberView::from_int(0_u16).as_f32());
+    /// ```
+    pub fn as_f32(&self) -> Option<f32> {
+        (self.szerokosc == 32).then(|| f32::from_bits(self.bity as u32))
+    }
+
+    /// Bity jako `f64`, jeśli wartość ma 64 bity
+    pub fn as_f64(&self) -> Option<f64> {
+        (self.szerokosc == 64).then(|| f64::from_bits(self.bity as u64))
+    }
+}
+
+impl fmt::Display for NumberView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let w = self.szerokosc;
+        writeln!(f, "{:<14}{w} bits", "Width:")?;
+        writeln!(f, "{:<14}{}", "HEX:", self.hex_grouped())?;
+        writeln!(f, "{:<14}{}", "DEC:", self.dec_grouped())?;
+        writeln!(f, "{:<14}{}", "OCT:", self.oct_grouped())?;
+        writeln!(f, "{:<14}{}", "BIN:", self.bin_grouped())?;
+        writeln!(f, "{:<14}{}", format!("u{w}:"), self.unsigned())?;
+        writeln!(
+            f,
+            "{:<14}{}",
+            format!("i{w} (2's):"),
+            self.twos_complement()
+        )?;
+        writeln!(
+            f,
+            "{:<14}{}",
+            format!("i{w} (1's):"),
+            self.ones_complement()
+        )?;
+        writeln!(f, "{:<14}{}", format!("i{w} (S-M):"), self.sign_magnitude())?;
+
+        // Młodsze bity jako węższe typy
+        for szerokosc in WIDTHS.iter().filter(|s| **s < w) {
+            // Unwrap, bo szerokość jest mniejsza od bieżącej
+            let mlodsze = self.truncate(*szerokosc).unwrap();
+            writeln!(
+                f,
+                "{:<14}{} / {}",
+                format!("u{szerokosc}/i{szerokosc}:"),
+                mlodsze.unsigned(),
+                mlodsze.twos_complement()
+            )?;
+        }
+
+        if w > 8 {
+            let zamienione = self.swap_bytes();
+            writeln!(
+                f,
+                "{:<14}{} = {} / {}",
+                "Swapped:",
+                zamienione.hex_grouped(),
+                zamienione.unsigned(),
+                zamienione.twos_complement()
+            )?;
+        }
+
+        writeln!(f, "{:<14}{:?}", "ASCII LE:", self.ascii_le())?;
+        writeln!(f, "{:<14}{:?}", "ASCII BE:", self.ascii_be())?;
+
+        if w == 16 {
+            let polowa = FloatParts::from_bits(FloatFormat::Binary16, self.bity);
+            let bf16 = FloatParts::from_bits(FloatFormat::BFloat16, self.bity);
+            writeln!(f, "{:<14}{:e}", "f16:", polowa.to_f64())?;
+            writeln!(f, "{:<14}{:e}", "bf16:", bf16.to_f64())?;
+        }
+        if let Some(liczba) = self.as_f32() {
+            writeln!(f, "{:<14}{liczba:e}", "f32:")?;
+        }
+        if let Some(liczba) = self.as_f64() {
+            writeln!(f, "{:<14}{liczba:e}", "f64:")?;
+        }
+        Ok(())
+    }
+}
+
+fn maska(szerokosc: u32) -> u128 {
+    u128::MAX >> (128 - szerokosc)
+}
+
+/// Wstawia `_` co `co_ile` cyfr, licząc od prawej
+fn grupuj(cyfry: &str, co_ile: usize) -> String {
+    let mut wynik = String::with_capacity(cyfry.len() * 2);
+    for (i, c) in cyfry.chars().enumerate() {
+        if i > 0 && (cyfry.len() - i).is_multiple_of(co_ile) {
+            wynik.push('_');
+        }
+        wynik.push(c);
+    }
+    wynik
+}
+
+fn ascii(bajt: u8) -> char {
+    if bajt.is_ascii_graphic() || bajt == b' ' {
+        bajt as char
+    } else {
+        '.'
+    }
+}
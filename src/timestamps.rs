@@ -0,0 +1,392 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::masks::{ekstrakcja, wstawienie};
+
+/// Błędy dekodowania i kodowania znaczników czasu
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TimestampError {
+    /// Pole o niemożliwej wartości, np. miesiąc 13 albo sekunda 62
+    InvalidField { field: &'static str, value: i64 },
+    /// Data nie mieści się w zakresie formatu (albo w latach 1..=9999)
+    OutOfRange,
+}
+
+impl fmt::Display for TimestampError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimestampError::InvalidField { field, value } => write!(f, "Invalid {field} {value}"),
+            TimestampError::OutOfRange => write!(f, "Date out of range for this format"),
+        }
+    }
+}
+
+impl std::error::Error for TimestampError {}
+
+/// Data i czas w kalendarzu gregoriańskim (proleptycznym), bez strefy
+/// czasowej
+///
+/// Formaty uniksowe, FILETIME i NTP liczą czas w UTC. DOS i HFS+ zapisują
+/// czas lokalny - tu strefa nie jest w żaden sposób przeliczana. Sekundy
+/// przestępne nie są obsługiwane (jak w czasie uniksowym).
+///
+/// Kolejność pól sprawia, że porównanie dwóch dat jest chronologiczne.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::timestamps::{DateTime, TimestampError};
+/// let t = DateTime::new(2023, 10, 3, 12, 34, 56, 0).unwrap();
+/// assert_eq!("2023-10-03 12:34:56", t.to_string());
+/// assert_eq!(
+///     Err(TimestampError::InvalidField { field: "month", value: 13 }),
+///     DateTime::new(2023, 13, 1, 0, 0, 0, 0)
+/// );
+/// assert_eq!(
+///     Err(TimestampError::InvalidField { field: "day", value: 29 }),
+///     DateTime::new(2023, 2, 29, 0, 0, 0, 0)
+/// );
+/// assert!(DateTime::new(2024, 2, 29, 0, 0, 0, 0).is_ok());
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct DateTime {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    pub nanosecond: u32,
+}
+
+/// Sekundy między 1601-01-01 (początek FILETIME) a 1970-01-01
+const FILETIME_EPOKA: i64 = 11_644_473_600;
+/// Sekundy między 1904-01-01 (początek HFS+) a 1970-01-01
+const HFS_EPOKA: i64 = 2_082_844_800;
+/// Sekundy między 1900-01-01 (początek NTP) a 1970-01-01
+const NTP_EPOKA: i64 = 2_208_988_800;
+
+const NANO: i64 = 1_000_000_000;
+
+// Pola czasu i daty MS-DOS (czas w młodszym, data w starszym słowie)
+const DOS_SEKUNDA: RangeInclusive<usize> = 0..=4;
+const DOS_MINUTA: RangeInclusive<usize> = 5..=10;
+const DOS_GODZINA: RangeInclusive<usize> = 11..=15;
+const DOS_DZIEN: RangeInclusive<usize> = 16..=20;
+const DOS_MIESIAC: RangeInclusive<usize> = 21..=24;
+const DOS_ROK: RangeInclusive<usize> = 25..=31;
+
+impl DateTime {
+    /// Tworzy datę, sprawdzając każde pole
+    pub fn new(
+        year: i32,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+        nanosecond: u32,
+    ) -> Result<Self, TimestampError> {
+        let blad = |field, value: i64| Err(TimestampError::InvalidField { field, value });
+        if !(1..=9999).contains(&year) {
+            return blad("year", year as i64);
+        }
+        if !(1..=12).contains(&month) {
+            return blad("month", month as i64);
+        }
+        if day == 0 || day > dni_w_miesiacu(year, month) {
+            return blad("day", day as i64);
+        }
+        if hour > 23 {
+            return blad("hour", hour as i64);
+        }
+        if minute > 59 {
+            return blad("minute", minute as i64);
+        }
+        if second > 59 {
+            return blad("second", second as i64);
+        }
+        if nanosecond >= NANO as u32 {
+            return blad("nanosecond", nanosecond as i64);
+        }
+        Ok(DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            nanosecond,
+        })
+    }
+
+    /// Data z liczby sekund i nanosekund od 1970-01-01
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::timestamps::DateTime;
+    /// assert_eq!("1970-01-01 00:00:00", DateTime::from_unix(0, 0).unwrap().to_string());
+    /// assert_eq!("1969-12-31 23:59:59", DateTime::from_unix(-1, 0).unwrap().to_string());
+    /// // Koniec 32-bitowego czasu
+    /// assert_eq!("2038-01-19 03:14:07", DateTime::from_unix(i32::MAX as i64, 0).unwrap().to_string());
+    /// assert!(DateTime::from_unix(i64::MAX, 0).is_err());
+    /// ```
+    pub fn from_unix(sekundy: i64, nanosekundy: u32) -> Result<Self, TimestampError> {
+        if nanosekundy >= NANO as u32 {
+            return Err(TimestampError::InvalidField {
+                field: "nanosecond",
+                value: nanosekundy as i64,
+            });
+        }
+        let (rok, miesiac, dzien) = data_z_dni(sekundy.div_euclid(86400));
+        let rok = i32::try_from(rok).map_err(|_| TimestampError::OutOfRange)?;
+        let w_dniu = sekundy.rem_euclid(86400);
+        Self::new(
+            rok,
+            miesiac,
+            dzien,
+            (w_dniu / 3600) as u8,
+            (w_dniu / 60 % 60) as u8,
+            (w_dniu % 60) as u8,
+            nanosekundy,
+        )
+        .map_err(|_| TimestampError::OutOfRange)
+    }
+
+    /// Sekundy od 1970-01-01 (bez części ułamkowej)
+    pub fn to_unix(&self) -> i64 {
+        dni_z_daty(self.year as i64, self.month, self.day) * 86400
+            + self.hour as i64 * 3600
+            + self.minute as i64 * 60
+            + self.second as i64
+    }
+
+    /// Data z milisekund od 1970-01-01 (np. JavaScript `Date`, Java)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::timestamps::DateTime;
+    /// let t = DateTime::from_unix_millis(1696336496789).unwrap();
+    /// assert_eq!("2023-10-03 12:34:56.789", t.to_string());
+    /// assert_eq!(1696336496789, t.to_unix_millis());
+    /// assert_eq!("1969-12-31 23:59:59.999", DateTime::from_unix_millis(-1).unwrap().to_string());
+    /// ```
+    pub fn from_unix_millis(ms: i64) -> Result<Self, TimestampError> {
+        Self::from_unix(ms.div_euclid(1000), ms.rem_euclid(1000) as u32 * 1_000_000)
+    }
+
+    /// Milisekundy od 1970-01-01 (nadmiarowa precyzja jest obcinana)
+    pub fn to_unix_millis(&self) -> i64 {
+        self.to_unix() * 1000 + (self.nanosecond / 1_000_000) as i64
+    }
+
+    /// Data z nanosekund od 1970-01-01 (np. Go `UnixNano`, ext4 po złożeniu
+    /// pól)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::timestamps::{DateTime, TimestampError};
+    /// let t = DateTime::from_unix_nanos(1696336496000000001).unwrap();
+    /// assert_eq!("2023-10-03 12:34:56.000000001", t.to_string());
+    /// assert_eq!(Ok(1696336496000000001), t.to_unix_nanos());
+    /// // i64 w nanosekundach kończy się w 2262 roku
+    /// let t = DateTime::new(2300, 1, 1, 0, 0, 0, 0).unwrap();
+    /// assert_eq!(Err(TimestampError::OutOfRange), t.to_unix_nanos());
+    /// ```
+    pub fn from_unix_nanos(ns: i64) -> Result<Self, TimestampError> {
+        Self::from_unix(ns.div_euclid(NANO), ns.rem_euclid(NANO) as u32)
+    }
+
+    /// Nanosekundy od 1970-01-01
+    pub fn to_unix_nanos(&self) -> Result<i64, TimestampError> {
+        self.to_unix()
+            .checked_mul(NANO)
+            .and_then(|ns| ns.checked_add(self.nanosecond as i64))
+            .ok_or(TimestampError::OutOfRange)
+    }
+
+    /// Data z Windows FILETIME (odcinki 100 ns od 1601-01-01 UTC)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::timestamps::DateTime;
+    /// let t = DateTime::from_filetime(133408100961234567).unwrap();
+    /// assert_eq!("2023-10-03 12:34:56.1234567", t.to_string());
+    /// assert_eq!(Ok(133408100961234567), t.to_filetime());
+    /// assert_eq!("1601-01-01 00:00:00", DateTime::from_filetime(0).unwrap().to_string());
+    /// ```
+    pub fn from_filetime(wartosc: u64) -> Result<Self, TimestampError> {
+        let sekundy = (wartosc / 10_000_000) as i64 - FILETIME_EPOKA;
+        Self::from_unix(sekundy, (wartosc % 10_000_000) as u32 * 100)
+    }
+
+    /// Windows FILETIME (precyzja ponad 100 ns jest obcinana)
+    pub fn to_filetime(&self) -> Result<u64, TimestampError> {
+        let sekundy = u64::try_from(self.to_unix() + FILETIME_EPOKA)
+            .map_err(|_| TimestampError::OutOfRange)?;
+        Ok(sekundy * 10_000_000 + (self.nanosecond / 100) as u64)
+    }
+
+    /// Data z czasu HFS+ (sekundy od 1904-01-01, bez znaku)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::timestamps::{DateTime, TimestampError};
+    /// let t = DateTime::from_hfs_plus(0xE141_BAF0).unwrap();
+    /// assert_eq!("2023-10-03 12:34:56", t.to_string());
+    /// assert_eq!(Ok(0xE141_BAF0), t.to_hfs_plus());
+    /// // HFS+ kończy się w 2040 roku
+    /// let t = DateTime::new(2041, 1, 1, 0, 0, 0, 0).unwrap();
+    /// assert_eq!(Err(TimestampError::OutOfRange), t.to_hfs_plus());
+    /// ```
+    pub fn from_hfs_plus(wartosc: u32) -> Result<Self, TimestampError> {
+        Self::from_unix(wartosc as i64 - HFS_EPOKA, 0)
+    }
+
+    /// Czas HFS+ (ułamek sekundy jest obcinany)
+    pub fn to_hfs_plus(&self) -> Result<u32, TimestampError> {
+        u32::try_from(self.to_unix() + HFS_EPOKA).map_err(|_| TimestampError::OutOfRange)
+    }
+
+    /// Data z 64-bitowego znacznika NTP (32 bity sekund od 1900-01-01 UTC
+    /// i 32 bity ułamka, era 0)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::timestamps::DateTime;
+    /// let t = DateTime::from_ntp(0xE8C6_88F0_8000_0000).unwrap();
+    /// assert_eq!("2023-10-03 12:34:56.5", t.to_string());
+    /// assert_eq!(Ok(0xE8C6_88F0_8000_0000), t.to_ntp());
+    /// ```
+    pub fn from_ntp(wartosc: u64) -> Result<Self, TimestampError> {
+        let sekundy = (wartosc >> 32) as i64 - NTP_EPOKA;
+        // Ułamek 32-bitowy na nanosekundy, zaokrąglany w dół
+        let nanosekundy = ((wartosc & 0xFFFF_FFFF) * NANO as u64) >> 32;
+        Self::from_unix(sekundy, nanosekundy as u32)
+    }
+
+    /// Znacznik NTP (era 0, czyli lata 1900-2036)
+    pub fn to_ntp(&self) -> Result<u64, TimestampError> {
+        let sekundy =
+            u32::try_from(self.to_unix() + NTP_EPOKA).map_err(|_| TimestampError::OutOfRange)?;
+        // Zaokrąglenie w górę, żeby from_ntp(to_ntp(t)) dawało t
+        let ulamek = ((self.nanosecond as u64) << 32).div_ceil(NANO as u64);
+        Ok((sekundy as u64) << 32 | ulamek)
+    }
+
+    /// Data z czasu MS-DOS (FAT, ZIP): młodsze 16 bitów to czas, starsze to
+    /// data
+    ///
+    /// Sekundy są zapisane z rozdzielczością 2 s, a lata od 1980 do 2107.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sekurak_hex_gynvael::timestamps::{DateTime, TimestampError};
+    /// let t = DateTime::from_dos(0x5743_644E).unwrap();
+    /// assert_eq!("2023-10-03 12:34:28", t.to_string());
+    /// assert_eq!(Ok(0x5743_644E), t.to_dos());
+    /// // Pole sekund 31 to 62 sekundy
+    /// assert_eq!(
+    ///     Err(TimestampError::InvalidField { field: "second", value: 62 }),
+    ///     DateTime::from_dos(0x5743_645F)
+    /// );
+    /// // Miesiąc 13
+    /// assert_eq!(
+    ///     Err(TimestampError::InvalidField { field: "month", value: 13 }),
+    ///     DateTime::from_dos(0x57A3_644E)
+    /// );
+    /// ```
+    pub fn from_dos(wartosc: u32) -> Result<Self, TimestampError> {
+        Self::new(
+            ekstrakcja(wartosc, DOS_ROK) as i32 + 1980,
+            ekstrakcja(wartosc, DOS_MIESIAC) as u8,
+            ekstrakcja(wartosc, DOS_DZIEN) as u8,
+            ekstrakcja(wartosc, DOS_GODZINA) as u8,
+            ekstrakcja(wartosc, DOS_MINUTA) as u8,
+            ekstrakcja(wartosc, DOS_SEKUNDA) as u8 * 2,
+            0,
+        )
+    }
+
+    /// Czas MS-DOS (nieparzyste sekundy i ułamek są obcinane)
+    pub fn to_dos(&self) -> Result<u32, TimestampError> {
+        if !(1980..=2107).contains(&self.year) {
+            return Err(TimestampError::OutOfRange);
+        }
+        let mut wynik = 0u32;
+        wynik = wstawienie(wynik, (self.year - 1980) as u32, DOS_ROK);
+        wynik = wstawienie(wynik, self.month as u32, DOS_MIESIAC);
+        wynik = wstawienie(wynik, self.day as u32, DOS_DZIEN);
+        wynik = wstawienie(wynik, self.hour as u32, DOS_GODZINA);
+        wynik = wstawienie(wynik, self.minute as u32, DOS_MINUTA);
+        wynik = wstawienie(wynik, self.second as u32 / 2, DOS_SEKUNDA);
+        Ok(wynik)
+    }
+
+    /// Data z osobnych słów daty i czasu MS-DOS, w kolejności, w jakiej leżą
+    /// w nagłówkach ZIP
+    pub fn from_dos_parts(czas: u16, data: u16) -> Result<Self, TimestampError> {
+        Self::from_dos((data as u32) << 16 | czas as u32)
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )?;
+        if self.nanosecond != 0 {
+            let ulamek = format!("{:09}", self.nanosecond);
+            write!(f, ".{}", ulamek.trim_end_matches('0'))?;
+        }
+        Ok(())
+    }
+}
+
+fn przestepny(rok: i32) -> bool {
+    rok % 4 == 0 && (rok % 100 != 0 || rok % 400 == 0)
+}
+
+fn dni_w_miesiacu(rok: i32, miesiac: u8) -> u8 {
+    match miesiac {
+        2 if przestepny(rok) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Data w kalendarzu gregoriańskim z liczby dni od 1970-01-01 (algorytm
+/// "civil_from_days" Howarda Hinnanta)
+fn data_z_dni(dni: i64) -> (i64, u8, u8) {
+    let z = dni + 719_468;
+    let era = z.div_euclid(146_097);
+    let dzien_ery = z.rem_euclid(146_097);
+    let rok_ery = (dzien_ery - dzien_ery / 1460 + dzien_ery / 36524 - dzien_ery / 146_096) / 365;
+    let dzien_roku = dzien_ery - (365 * rok_ery + rok_ery / 4 - rok_ery / 100);
+    let mp = (5 * dzien_roku + 2) / 153;
+    let dzien = (dzien_roku - (153 * mp + 2) / 5 + 1) as u8;
+    let miesiac = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+    let rok = rok_ery + era * 400 + (miesiac <= 2) as i64;
+    (rok, miesiac, dzien)
+}
+
+/// Liczba dni od 1970-01-01 ("days_from_civil", odwrotność [`data_z_dni`])
+fn dni_z_daty(rok: i64, miesiac: u8, dzien: u8) -> i64 {
+    let rok = if miesiac <= 2 { rok - 1 } else { rok };
+    let era = rok.div_euclid(400);
+    let rok_ery = rok.rem_euclid(400);
+    let mp = (miesiac as i64 + 9) % 12;
+    let dzien_roku = (153 * mp + 2) / 5 + dzien as i64 - 1;
+    let dzien_ery = rok_ery * 365 + rok_ery / 4 - rok_ery / 100 + dzien_roku;
+    era * 146_097 + dzien_ery - 719_468
+}
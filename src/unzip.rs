@@ -1,12 +1,14 @@
 use crate::files;
 use crate::files::File;
+use crate::timestamps::DateTime;
 use eyre::{eyre, ContextCompat, Result, WrapErr};
-use log::{debug, error};
+use log::debug;
 use normalize_path::NormalizePath;
 use std::fmt::Debug;
 use std::fs;
 use std::io::{Cursor, Read, SeekFrom};
 use std::path::Path;
+use std::time::{Duration, UNIX_EPOCH};
 
 const MAGIC_EOCD: u32 = 0x06054b50_u32;
 const MAGIC_CD: u32 = 0x02014b50_u32;
@@ -21,20 +23,14 @@ pub struct ZipFile {
 #[derive(Debug)]
 pub struct EndOfCentralDirectory {
     central_directory_entries: u16,
-    size_of_central_directory: u32,
     offset_of_central_directory: u32,
-    comment: Option<String>,
 }
 
 #[derive(Debug)]
 pub struct CentralDirectoryEntry {
-    crc32: u32,
-    comp_size: u32,
-    uncomp_size: u32,
     offset: u32,
+    modified: Option<DateTime>,
     name: String,
-    extra: Option<Vec<u8>>,
-    comment: Option<String>,
 }
 
 impl ZipFile {
@@ -76,29 +72,15 @@ impl ZipFile {
                         "Multi-disk unsupported {central_directory_entries_this_disk}!={central_directory_entries}"
                     ));
                 }
-                let size_of_central_directory = sf
-                    .read_u32le()
+                sf.read_u32le()
                     .wrap_err("Failed read size of the central directory")?;
                 let offset_of_central_directory = sf
                     .read_u32le()
                     .wrap_err("Failed read offset of start of central directory with respect to the starting disk number")?;
 
-                let comment_length = sf
-                    .read_u16le()
-                    .wrap_err("Failed read .ZIP file comment length")?;
-
-                let comment = if comment_length == 0 {
-                    None
-                } else {
-                    let comment_bin = sf.read_as_vec(comment_length as usize)?;
-                    Some(String::from_utf8(comment_bin).wrap_err("EOCD comment not UTF-8")?)
-                };
-
                 let eocd = EndOfCentralDirectory {
                     central_directory_entries,
-                    size_of_central_directory,
                     offset_of_central_directory,
-                    comment,
                 };
                 debug!("EOCD record: {eocd:?}");
                 return Ok(eocd);
@@ -133,13 +115,20 @@ impl ZipFile {
                 return Err(eyre!("Unsupported compression method {comp_method}"));
             }
 
-            sf.read_u16le().wrap_err("can't read last mod file time")?;
-            sf.read_u16le().wrap_err("can't read last mod file date")?;
+            let mod_time = sf.read_u16le().wrap_err("can't read last mod file time")?;
+            let mod_date = sf.read_u16le().wrap_err("can't read last mod file date")?;
+            let modified = match DateTime::from_dos_parts(mod_time, mod_date) {
+                Ok(modified) => Some(modified),
+                Err(e) => {
+                    debug!("CD#{n}: bad last mod file time/date: {e}");
+                    None
+                }
+            };
 
-            let crc32 = sf.read_u32le().wrap_err("can't read crc-32")?;
+            sf.read_u32le().wrap_err("can't read crc-32")?;
 
-            let comp_size = sf.read_u32le().wrap_err("can't read compressed size")?;
-            let uncomp_size = sf.read_u32le().wrap_err("can't read uncompressed size")?;
+            sf.read_u32le().wrap_err("can't read compressed size")?;
+            sf.read_u32le().wrap_err("can't read uncompressed size")?;
 
             let fname_len = sf.read_u16le().wrap_err("can't read file name length")?;
             if fname_len == 0 {
@@ -164,27 +153,13 @@ impl ZipFile {
             let file_name_bin = sf.read_as_vec(fname_len as usize)?;
             let name = String::from_utf8(file_name_bin).wrap_err("CD file name #{n} UTF-8")?;
 
-            let extra = if extra_len > 0 {
-                Some(sf.read_as_vec(extra_len as usize)?)
-            } else {
-                None
-            };
-
-            let comment = if comment_len == 0 {
-                None
-            } else {
-                let comment_bin = sf.read_as_vec(comment_len as usize)?;
-                Some(String::from_utf8(comment_bin).wrap_err("CD comment #{n} not UTF-8")?)
-            };
+            // Pole dodatkowe i komentarz nie są używane
+            sf.seek(SeekFrom::Current(extra_len as i64 + comment_len as i64))?;
 
             let cd = CentralDirectoryEntry {
-                crc32,
-                comp_size,
-                uncomp_size,
                 offset,
+                modified,
                 name,
-                extra,
-                comment,
             };
             debug!("CD#{n}: {cd:?}");
             entries.push(cd);
@@ -220,7 +195,7 @@ impl ZipFile {
         sf.read_u16le().wrap_err("can't read last mod file time")?;
         sf.read_u16le().wrap_err("can't read last mod file date")?;
 
-        let crc32 = sf.read_u32le().wrap_err("can't read crc-32")?;
+        sf.read_u32le().wrap_err("can't read crc-32")?;
 
         let comp_size = sf.read_u32le().wrap_err("can't read compressed size")?;
         let uncomp_size = sf.read_u32le().wrap_err("can't read uncompressed size")?;
@@ -234,11 +209,7 @@ impl ZipFile {
         let file_name_bin = sf.read_as_vec(fname_len as usize)?;
         let name = String::from_utf8(file_name_bin).wrap_err("CD file name #{n} UTF-8")?;
 
-        let extra = if extra_len > 0 {
-            Some(sf.read_as_vec(extra_len as usize)?)
-        } else {
-            None
-        };
+        sf.seek(SeekFrom::Current(extra_len as i64))?;
 
         let payload = sf.read_as_vec(comp_size as usize)?;
 
@@ -277,7 +248,19 @@ impl ZipFile {
         file.write_exact(&uncompressed_data)
             .wrap_err("Failed to write data to {out_file}")?;
 
-        debug!("Created {out_file}");
+        // Czas modyfikacji z katalogu centralnego (czas DOS nie ma strefy,
+        // traktowany jest jak UTC)
+        if let Some(modified) = &cd.modified {
+            let mtime = UNIX_EPOCH + Duration::from_secs(modified.to_unix() as u64);
+            fs::File::options()
+                .write(true)
+                .open(&out_file)
+                .and_then(|f| f.set_modified(mtime))
+                .wrap_err_with(|| format!("Failed to set modification time of {out_file}"))?;
+            debug!("Created {out_file}, modified {modified}");
+        } else {
+            debug!("Created {out_file}");
+        }
         Ok(())
     }
 }
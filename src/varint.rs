@@ -0,0 +1,542 @@
+use crate::conversions::{decode_leb128, decode_vlq128, encode_leb128, encode_vlq128, VarintError};
+
+/// Wspólny interfejs kodowań liczb o zmiennej długości
+///
+/// Wartości są przekazywane jako `i128`, żeby jednym typem objąć zarówno
+/// kodowania bez znaku (do `u64`), jak i ze znakiem (ZigZag). Dzięki temu
+/// kodowanie można wybrać w czasie działania programu, np. jako `&dyn Varint`.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::varint::*;
+/// let schematy: [&dyn Varint; 3] = [&Leb128, &Quic, &CompactSize];
+/// for schemat in schematy {
+///     let bajty = schemat.encode(300).unwrap();
+///     assert_eq!(Ok((300, bajty.len())), schemat.decode(&bajty));
+/// }
+/// ```
+pub trait Varint {
+    /// Krótka nazwa kodowania
+    fn name(&self) -> &'static str;
+
+    /// Dekoduje jedną liczbę z początku bufora. Zwraca wartość i liczbę
+    /// zużytych bajtów.
+    fn decode(&self, bajty: &[u8]) -> Result<(i128, usize), VarintError>;
+
+    /// Koduje liczbę. Wartości spoza zakresu kodowania dają
+    /// `VarintError::Overflow`.
+    fn encode(&self, wartosc: i128) -> Result<Vec<u8>, VarintError>;
+}
+
+/// Sprawdza, czy wartość mieści się w zakresie `0 ..= max`
+fn bez_znaku(wartosc: i128, max: u64) -> Result<u64, VarintError> {
+    u64::try_from(wartosc)
+        .ok()
+        .filter(|w| *w <= max)
+        .ok_or(VarintError::Overflow)
+}
+
+/// Kodowanie jest kanoniczne, jeśli ponowne zakodowanie wartości daje tyle samo
+/// bajtów
+fn kanoniczne<V: Varint + ?Sized>(
+    schemat: &V,
+    wynik: (i128, usize),
+) -> Result<(i128, usize), VarintError> {
+    if schemat.encode(wynik.0)?.len() != wynik.1 {
+        return Err(VarintError::NonCanonical);
+    }
+    Ok(wynik)
+}
+
+/// LEB128 bez znaku, do 64 bitów (DWARF, WebAssembly, protobuf `uint64`)
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::varint::{Leb128, Varint};
+/// // Przykład ze specyfikacji DWARF 5, tabela 7.6
+/// assert_eq!(Ok((12857, 2)), Leb128.decode(&[0x80 + 57, 100]));
+/// assert_eq!(Ok(vec![0x80 + 1, 1]), Leb128.encode(129));
+/// ```
+pub struct Leb128;
+
+impl Varint for Leb128 {
+    fn name(&self) -> &'static str {
+        "leb128"
+    }
+
+    fn decode(&self, bajty: &[u8]) -> Result<(i128, usize), VarintError> {
+        let (wartosc, dlugosc) = decode_leb128::<u64>(bajty)?;
+        Ok((wartosc as i128, dlugosc))
+    }
+
+    fn encode(&self, wartosc: i128) -> Result<Vec<u8>, VarintError> {
+        Ok(encode_leb128(bez_znaku(wartosc, u64::MAX)? as u128))
+    }
+}
+
+/// VLQ bez znaku, do 64 bitów (MIDI, ASN.1 OID)
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::varint::{Varint, Vlq};
+/// // Przykłady ze specyfikacji MIDI (Standard MIDI Files 1.0)
+/// assert_eq!(Ok((0x0000_2000, 2)), Vlq.decode(&[0xC0, 0x00]));
+/// assert_eq!(Ok(vec![0x81, 0x80, 0x80, 0x00]), Vlq.encode(0x0020_0000));
+/// ```
+pub struct Vlq;
+
+impl Varint for Vlq {
+    fn name(&self) -> &'static str {
+        "vlq"
+    }
+
+    fn decode(&self, bajty: &[u8]) -> Result<(i128, usize), VarintError> {
+        let (wartosc, dlugosc) = decode_vlq128::<u64>(bajty)?;
+        Ok((wartosc as i128, dlugosc))
+    }
+
+    fn encode(&self, wartosc: i128) -> Result<Vec<u8>, VarintError> {
+        Ok(encode_vlq128(bez_znaku(wartosc, u64::MAX)? as u128))
+    }
+}
+
+/// Koduje liczbę ze znakiem metodą ZigZag - małe wartości bezwzględne dają
+/// małe liczby bez znaku
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::varint::zigzag_encode;
+/// // Tabela z dokumentacji protobuf ("Signed Integers")
+/// assert_eq!(0, zigzag_encode(0));
+/// assert_eq!(1, zigzag_encode(-1));
+/// assert_eq!(2, zigzag_encode(1));
+/// assert_eq!(3, zigzag_encode(-2));
+/// assert_eq!(0xFFFF_FFFE, zigzag_encode(0x7FFF_FFFF));
+/// assert_eq!(0xFFFF_FFFF, zigzag_encode(-0x8000_0000));
+/// ```
+pub fn zigzag_encode(liczba: i64) -> u64 {
+    // Przesunięcie arytmetyczne o 63 daje same zera albo same jedynki
+    ((liczba << 1) ^ (liczba >> 63)) as u64
+}
+
+/// Odwrotność [`zigzag_encode`]
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::varint::zigzag_decode;
+/// assert_eq!(-1, zigzag_decode(1));
+/// assert_eq!(i64::MIN, zigzag_decode(u64::MAX));
+/// ```
+pub fn zigzag_decode(liczba: u64) -> i64 {
+    ((liczba >> 1) as i64) ^ -((liczba & 1) as i64)
+}
+
+/// Protobuf `sint64` - ZigZag, a potem LEB128
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::varint::{ProtobufZigZag, Varint};
+/// assert_eq!(Ok(vec![0x03]), ProtobufZigZag.encode(-2));
+/// // 150 z przykładu w dokumentacji protobuf to po ZigZag liczba 75
+/// assert_eq!(Ok((75, 2)), ProtobufZigZag.decode(&[0x96, 0x01]));
+/// assert_eq!(Ok((i64::MIN as i128, 10)), ProtobufZigZag.decode(&[0xFF; 9].iter().chain(&[0x01]).copied().collect::<Vec<_>>()));
+/// ```
+pub struct ProtobufZigZag;
+
+impl Varint for ProtobufZigZag {
+    fn name(&self) -> &'static str {
+        "zigzag"
+    }
+
+    fn decode(&self, bajty: &[u8]) -> Result<(i128, usize), VarintError> {
+        let (wartosc, dlugosc) = decode_leb128::<u64>(bajty)?;
+        Ok((zigzag_decode(wartosc) as i128, dlugosc))
+    }
+
+    fn encode(&self, wartosc: i128) -> Result<Vec<u8>, VarintError> {
+        let liczba = i64::try_from(wartosc).map_err(|_| VarintError::Overflow)?;
+        Ok(encode_leb128(zigzag_encode(liczba) as u128))
+    }
+}
+
+/// Varint z formatu plików SQLite - big endian, 1 do 9 bajtów. Dziewiąty
+/// bajt niesie pełne 8 bitów.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::varint::{Sqlite, Varint};
+/// assert_eq!(Ok((127, 1)), Sqlite.decode(&[0x7F]));
+/// assert_eq!(Ok((128, 2)), Sqlite.decode(&[0x81, 0x00]));
+/// assert_eq!(Ok((u64::MAX as i128, 9)), Sqlite.decode(&[0xFF; 9]));
+/// assert_eq!(Ok(vec![0x81, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00]), Sqlite.encode(1 << 57));
+/// ```
+pub struct Sqlite;
+
+impl Varint for Sqlite {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    fn decode(&self, bajty: &[u8]) -> Result<(i128, usize), VarintError> {
+        let mut wynik = 0_u64;
+        for (i, bajt) in bajty.iter().enumerate() {
+            if i == 8 {
+                wynik = (wynik << 8) | *bajt as u64;
+                return kanoniczne(self, (wynik as i128, 9));
+            }
+            wynik = (wynik << 7) | (*bajt & 0x7F) as u64;
+            if *bajt & 0x80 == 0 {
+                return kanoniczne(self, (wynik as i128, i + 1));
+            }
+        }
+        Err(VarintError::Truncated)
+    }
+
+    fn encode(&self, wartosc: i128) -> Result<Vec<u8>, VarintError> {
+        let liczba = bez_znaku(wartosc, u64::MAX)?;
+        if liczba >> 56 == 0 {
+            return Ok(encode_vlq128(liczba as u128));
+        }
+
+        // Osiem 7-bitowych grup z bitem kontynuacji i pełny ostatni bajt
+        let mut wynik = vec![0u8; 9];
+        wynik[8] = liczba as u8;
+        let mut reszta = liczba >> 8;
+        for bajt in wynik[..8].iter_mut().rev() {
+            *bajt = 0x80 | (reszta & 0x7F) as u8;
+            reszta >>= 7;
+        }
+        Ok(wynik)
+    }
+}
+
+/// Varint z protokołu QUIC (RFC 9000, sekcja 16) - dwa najstarsze bity
+/// pierwszego bajtu to długość (1, 2, 4 lub 8 bajtów), reszta to big endian.
+///
+/// Specyfikacja pozwala na dłuższe niż konieczne kodowanie, więc nie jest ono
+/// traktowane jako błąd.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::varint::{Quic, Varint};
+/// // RFC 9000, dodatek A.1
+/// let c2 = [0xc2, 0x19, 0x7c, 0x5e, 0xff, 0x14, 0xe8, 0x8c];
+/// assert_eq!(Ok((151288809941952652, 8)), Quic.decode(&c2));
+/// assert_eq!(Ok((494878333, 4)), Quic.decode(&[0x9d, 0x7f, 0x3e, 0x7d]));
+/// assert_eq!(Ok((15293, 2)), Quic.decode(&[0x7b, 0xbd]));
+/// assert_eq!(Ok((37, 1)), Quic.decode(&[0x25]));
+/// assert_eq!(Ok((37, 2)), Quic.decode(&[0x40, 0x25]));
+/// assert_eq!(Ok(c2.to_vec()), Quic.encode(151288809941952652));
+/// ```
+pub struct Quic;
+
+impl Varint for Quic {
+    fn name(&self) -> &'static str {
+        "quic"
+    }
+
+    fn decode(&self, bajty: &[u8]) -> Result<(i128, usize), VarintError> {
+        let pierwszy = *bajty.first().ok_or(VarintError::Truncated)?;
+        let dlugosc = 1 << (pierwszy >> 6);
+        let dane = bajty.get(..dlugosc).ok_or(VarintError::Truncated)?;
+
+        let wynik = dane[1..]
+            .iter()
+            .fold((pierwszy & 0x3F) as u64, |acc, v| (acc << 8) | *v as u64);
+        Ok((wynik as i128, dlugosc))
+    }
+
+    fn encode(&self, wartosc: i128) -> Result<Vec<u8>, VarintError> {
+        let liczba = bez_znaku(wartosc, (1 << 62) - 1)?;
+        let (prefiks, dlugosc) = match liczba {
+            0..=0x3F => (0b00, 1),
+            0x40..=0x3FFF => (0b01, 2),
+            0x4000..=0x3FFF_FFFF => (0b10, 4),
+            _ => (0b11, 8),
+        };
+        let mut wynik = liczba.to_be_bytes()[8 - dlugosc..].to_vec();
+        wynik[0] |= prefiks << 6;
+        Ok(wynik)
+    }
+}
+
+/// CompactSize z protokołu Bitcoin - jeden bajt dla wartości do 0xFC, a potem
+/// znacznik 0xFD/0xFE/0xFF i liczba little endian na 2/4/8 bajtach
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::varint::{CompactSize, Varint};
+/// use sekurak_hex_gynvael::conversions::VarintError;
+/// assert_eq!(Ok(vec![0xFC]), CompactSize.encode(0xFC));
+/// assert_eq!(Ok(vec![0xFD, 0xFD, 0x00]), CompactSize.encode(0xFD));
+/// assert_eq!(Ok(vec![0xFE, 0x00, 0x00, 0x01, 0x00]), CompactSize.encode(0x1_0000));
+/// assert_eq!(Ok((0x1_0000_0000, 9)), CompactSize.decode(&[0xFF, 0, 0, 0, 0, 1, 0, 0, 0]));
+/// // Bitcoin Core odrzuca niekanoniczne kodowanie
+/// assert_eq!(Err(VarintError::NonCanonical), CompactSize.decode(&[0xFD, 0x10, 0x00]));
+/// ```
+pub struct CompactSize;
+
+impl Varint for CompactSize {
+    fn name(&self) -> &'static str {
+        "compactsize"
+    }
+
+    fn decode(&self, bajty: &[u8]) -> Result<(i128, usize), VarintError> {
+        let pierwszy = *bajty.first().ok_or(VarintError::Truncated)?;
+        let dlugosc = match pierwszy {
+            0xFD => 2,
+            0xFE => 4,
+            0xFF => 8,
+            n => return Ok((n as i128, 1)),
+        };
+        let dane = bajty.get(1..=dlugosc).ok_or(VarintError::Truncated)?;
+
+        // Little endian, więc składamy od końca
+        let wynik = dane.iter().rfold(0_u64, |acc, v| (acc << 8) | *v as u64);
+        kanoniczne(self, (wynik as i128, dlugosc + 1))
+    }
+
+    fn encode(&self, wartosc: i128) -> Result<Vec<u8>, VarintError> {
+        let liczba = bez_znaku(wartosc, u64::MAX)?;
+        let (znacznik, dlugosc) = match liczba {
+            0..=0xFC => return Ok(vec![liczba as u8]),
+            0xFD..=0xFFFF => (0xFD, 2),
+            0x1_0000..=0xFFFF_FFFF => (0xFE, 4),
+            _ => (0xFF, 8),
+        };
+        let mut wynik = vec![znacznik];
+        wynik.extend_from_slice(&liczba.to_le_bytes()[..dlugosc]);
+        Ok(wynik)
+    }
+}
+
+/// Nagłówek obiektu w pliku `.pack` Gita - 3 bity typu i rozmiar: 4 bity w
+/// pierwszym bajcie, potem grupy po 7 bitów little endian
+///
+/// Przez interfejs [`Varint`] przechodzi sam rozmiar, a typ to pole `typ`:
+/// jest zapisywany przy kodowaniu, a przy dekodowaniu nagłówek innego typu
+/// daje [`VarintError::Malformed`]. Nagłówek z dowolnym typem zwraca
+/// [`GitObjectHeader::decode_header`].
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::conversions::VarintError;
+/// use sekurak_hex_gynvael::varint::{GitObjectHeader, Varint};
+/// // Blob (typ 3) o rozmiarze 100 bajtów
+/// let blob = GitObjectHeader { typ: 3 };
+/// assert_eq!(Ok(vec![0xB4, 0x06]), blob.encode(100));
+/// assert_eq!(Ok((3, 100, 2)), GitObjectHeader::decode_header(&[0xB4, 0x06]));
+/// assert_eq!(Ok((100, 2)), blob.decode(&[0xB4, 0x06]));
+/// // Commit (typ 1) o rozmiarze 15
+/// assert_eq!(Ok((1, 15, 1)), GitObjectHeader::decode_header(&[0x1F]));
+/// assert_eq!(Ok((15, 1)), GitObjectHeader { typ: 1 }.decode(&[0x1F]));
+/// assert_eq!(Err(VarintError::Malformed), blob.decode(&[0x1F]));
+/// ```
+pub struct GitObjectHeader {
+    pub typ: u8,
+}
+
+impl GitObjectHeader {
+    /// Dekoduje nagłówek obiektu. Zwraca typ, rozmiar i liczbę zużytych bajtów.
+    pub fn decode_header(bajty: &[u8]) -> Result<(u8, u64, usize), VarintError> {
+        let pierwszy = *bajty.first().ok_or(VarintError::Truncated)?;
+        let typ = (pierwszy >> 4) & 0x07;
+        let mut wynik = (pierwszy & 0x0F) as u64;
+
+        if pierwszy & 0x80 == 0 {
+            return Ok((typ, wynik, 1));
+        }
+
+        let mut przesuniecie = 4;
+        for (i, bajt) in bajty.iter().enumerate().skip(1) {
+            let grupa = (*bajt & 0x7F) as u64;
+            if grupa != 0 {
+                if przesuniecie >= 64 || (grupa << przesuniecie) >> przesuniecie != grupa {
+                    return Err(VarintError::Overflow);
+                }
+                wynik |= grupa << przesuniecie;
+            }
+            if *bajt & 0x80 == 0 {
+                if grupa == 0 {
+                    return Err(VarintError::NonCanonical);
+                }
+                return Ok((typ, wynik, i + 1));
+            }
+            przesuniecie += 7;
+        }
+        Err(VarintError::Truncated)
+    }
+}
+
+impl Varint for GitObjectHeader {
+    fn name(&self) -> &'static str {
+        "git-object-size"
+    }
+
+    fn decode(&self, bajty: &[u8]) -> Result<(i128, usize), VarintError> {
+        let (typ, rozmiar, dlugosc) = Self::decode_header(bajty)?;
+        if typ != self.typ {
+            return Err(VarintError::Malformed);
+        }
+        Ok((rozmiar as i128, dlugosc))
+    }
+
+    fn encode(&self, wartosc: i128) -> Result<Vec<u8>, VarintError> {
+        if self.typ > 0x07 {
+            return Err(VarintError::Overflow);
+        }
+        let rozmiar = bez_znaku(wartosc, u64::MAX)?;
+
+        let mut wynik = vec![(self.typ << 4) | (rozmiar & 0x0F) as u8];
+        let mut reszta = rozmiar >> 4;
+        while reszta != 0 {
+            // Unwrap, bo wektor jest niepusty
+            *wynik.last_mut().unwrap() |= 0x80;
+            wynik.push((reszta & 0x7F) as u8);
+            reszta >>= 7;
+        }
+        Ok(wynik)
+    }
+}
+
+/// Przesunięcie bazy w obiektach `OFS_DELTA` Gita - big endian, a każda
+/// kontynuacja dodaje 1 przed przesunięciem. Dzięki temu każda wartość ma
+/// dokładnie jedno kodowanie.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::conversions::VarintError;
+/// use sekurak_hex_gynvael::varint::{GitOffset, Varint};
+/// assert_eq!(Ok(vec![0x7F]), GitOffset.encode(127));
+/// assert_eq!(Ok(vec![0x80, 0x00]), GitOffset.encode(128));
+/// assert_eq!(Ok(vec![0xFF, 0x7F]), GitOffset.encode(16511));
+/// assert_eq!(Ok((16512, 3)), GitOffset.decode(&[0x80, 0x80, 0x00]));
+///
+/// // Dodanie 1 też może przekroczyć 64 bity
+/// let mut za_duza = vec![0x80];
+/// za_duza.extend([0xFE; 8]);
+/// za_duza.extend([0xFF, 0x00]);
+/// assert_eq!(Err(VarintError::Overflow), GitOffset.decode(&za_duza));
+/// ```
+pub struct GitOffset;
+
+impl Varint for GitOffset {
+    fn name(&self) -> &'static str {
+        "git-offset"
+    }
+
+    fn decode(&self, bajty: &[u8]) -> Result<(i128, usize), VarintError> {
+        let mut bajt = *bajty.first().ok_or(VarintError::Truncated)?;
+        let mut wynik = (bajt & 0x7F) as u64;
+        let mut dlugosc = 1;
+
+        while bajt & 0x80 != 0 {
+            bajt = *bajty.get(dlugosc).ok_or(VarintError::Truncated)?;
+            dlugosc += 1;
+
+            // Tak samo jak w Gicie: +1, a potem przesunięcie bez utraty bitów
+            wynik = wynik.checked_add(1).ok_or(VarintError::Overflow)?;
+            if wynik >> 57 != 0 {
+                return Err(VarintError::Overflow);
+            }
+            wynik = (wynik << 7) | (bajt & 0x7F) as u64;
+        }
+        Ok((wynik as i128, dlugosc))
+    }
+
+    fn encode(&self, wartosc: i128) -> Result<Vec<u8>, VarintError> {
+        let liczba = bez_znaku(wartosc, u64::MAX)?;
+
+        // Budujemy od najmłodszej grupy i odwracamy na końcu
+        let mut wynik = vec![(liczba & 0x7F) as u8];
+        let mut reszta = liczba >> 7;
+        while reszta != 0 {
+            reszta -= 1;
+            wynik.push(0x80 | (reszta & 0x7F) as u8);
+            reszta >>= 7;
+        }
+        wynik.reverse();
+        Ok(wynik)
+    }
+}
+
+/// Prefiksowe kodowanie w stylu UTF-8 (oryginalne, do 6 bajtów i 31 bitów) -
+/// liczba jedynek na początku pierwszego bajtu to długość, kolejne bajty mają
+/// postać `10xxxxxx`
+///
+/// Kodowane są dowolne liczby, więc np. surogaty UTF-16 nie są odrzucane.
+///
+/// # Examples
+///
+/// ```
+/// use sekurak_hex_gynvael::varint::{Utf8Prefix, Varint};
+/// use sekurak_hex_gynvael::conversions::VarintError;
+/// // Przykłady z RFC 3629, rozdział 7
+/// assert_eq!(Ok(vec![0xE2, 0x89, 0xA2]), Utf8Prefix.encode(0x2262));
+/// assert_eq!(Ok((0x0391, 2)), Utf8Prefix.decode(&[0xCE, 0x91]));
+/// assert_eq!(Ok((0xD55C, 3)), Utf8Prefix.decode(&[0xED, 0x95, 0x9C]));
+/// assert_eq!(Ok((0x233B4, 4)), Utf8Prefix.decode(&[0xF0, 0xA3, 0x8E, 0xB4]));
+/// // Zgodność z UTF-8 z biblioteki standardowej
+/// let mut bufor = [0u8; 4];
+/// let znak = '日';
+/// assert_eq!(Ok(znak.encode_utf8(&mut bufor).as_bytes().to_vec()), Utf8Prefix.encode(znak as i128));
+/// // Nadmiarowo długie kodowanie znaku '/'
+/// assert_eq!(Err(VarintError::NonCanonical), Utf8Prefix.decode(&[0xC0, 0xAF]));
+/// ```
+pub struct Utf8Prefix;
+
+impl Varint for Utf8Prefix {
+    fn name(&self) -> &'static str {
+        "utf8"
+    }
+
+    fn decode(&self, bajty: &[u8]) -> Result<(i128, usize), VarintError> {
+        let pierwszy = *bajty.first().ok_or(VarintError::Truncated)?;
+        let dlugosc = match pierwszy.leading_ones() as usize {
+            0 => return Ok((pierwszy as i128, 1)),
+            n @ 2..=6 => n,
+            // Bajt kontynuacji na początku albo 0xFE/0xFF
+            _ => return Err(VarintError::Malformed),
+        };
+
+        let mut wynik = (pierwszy & (0x7F >> dlugosc)) as u64;
+        for i in 1..dlugosc {
+            let bajt = *bajty.get(i).ok_or(VarintError::Truncated)?;
+            if bajt & 0xC0 != 0x80 {
+                return Err(VarintError::Malformed);
+            }
+            wynik = (wynik << 6) | (bajt & 0x3F) as u64;
+        }
+        kanoniczne(self, (wynik as i128, dlugosc))
+    }
+
+    fn encode(&self, wartosc: i128) -> Result<Vec<u8>, VarintError> {
+        let mut liczba = bez_znaku(wartosc, 0x7FFF_FFFF)?;
+        if liczba < 0x80 {
+            return Ok(vec![liczba as u8]);
+        }
+
+        // Bajt o długości n mieści 5n+1 bitów (11, 16, 21, 26, 31)
+        // Unwrap, bo 31 bitów mieści się zawsze w 6 bajtach
+        let dlugosc = (2..=6).find(|n| liczba >> (5 * n + 1) == 0).unwrap();
+
+        let mut wynik = vec![0u8; dlugosc];
+        for bajt in wynik[1..].iter_mut().rev() {
+            *bajt = 0x80 | (liczba & 0x3F) as u8;
+            liczba >>= 6;
+        }
+        wynik[0] = (0xFF_u8 << (8 - dlugosc)) | liczba as u8;
+        Ok(wynik)
+    }
+}